@@ -0,0 +1,344 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mdp_project::evaluation::Objective;
+use mdp_project::formulation::MipFormulation;
+use mdp_project::generator::InstanceKind;
+use mdp_project::graph::IndexBase;
+use mdp_project::qubo::QuboFormat;
+use mdp_project::solver::Budget;
+use mdp_project::solver_grouping::GroupingMethod;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "mdp_project", about = "Maximum Diversity Problem solvers and benchmark driver")]
+pub struct Cli {
+    /// Defaults to `bench` over examples_from_mdp
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Solve a single instance with one solver
+    Solve(SolveArgs),
+    /// Run solvers over every instance in a directory or glob
+    Bench(BenchArgs),
+    /// Check a solution against an instance and report its diversity
+    Validate(ValidateArgs),
+    /// Write a random instance in MDPLIB format
+    Generate(GenerateArgs),
+    /// Write an instance's QUBO, Ising or MIP model for external solvers
+    Export(ExportArgs),
+    /// Partition all elements into diverse groups (Maximally Diverse Grouping Problem)
+    Group(GroupArgs),
+    /// List the registered solvers and their default parameters
+    Solvers,
+}
+
+#[derive(Args)]
+pub struct BudgetArgs {
+    /// Time limit per solver run, in seconds
+    #[arg(long, value_parser = parse_seconds)]
+    pub time: Option<f64>,
+    /// Maximum number of move/solution evaluations
+    #[arg(long)]
+    pub max_evals: Option<u64>,
+    /// Maximum number of solver iterations
+    #[arg(long)]
+    pub max_iters: Option<u64>,
+    /// Stop as soon as a solution with at least this diversity is found
+    #[arg(long)]
+    pub target: Option<f64>,
+}
+
+impl BudgetArgs {
+    /// The requested budget, or `None` when no limit was given on the command line.
+    pub fn to_budget(&self) -> Option<Budget> {
+        if self.time.is_none() && self.max_evals.is_none() && self.max_iters.is_none() && self.target.is_none() {
+            return None;
+        }
+        Some(Budget {
+            time_limit: self.time.map(Duration::from_secs_f64),
+            max_evaluations: self.max_evals,
+            max_iterations: self.max_iters,
+            target_value: self.target,
+        })
+    }
+}
+
+#[derive(Args)]
+pub struct SolveArgs {
+    /// Instance file in MDPLIB format, or an edge list for MaxCut
+    pub instance: String,
+    /// Registered solver name (see `solvers`); Tabu by default, Pareto-NSGA2 for pareto
+    #[arg(long)]
+    pub solver: Option<String>,
+    #[arg(long, value_enum, default_value = "mdp")]
+    pub problem: ProblemArg,
+    #[command(flatten)]
+    pub budget: BudgetArgs,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Objective to optimize, for solvers with an `objective` parameter
+    #[arg(long, value_enum)]
+    pub objective: Option<ObjectiveArg>,
+    /// Solver parameter override, repeatable
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub params: Vec<(String, String)>,
+    /// Index base of a MaxCut edge list, when the file does not settle it
+    #[arg(long, value_enum)]
+    pub index_base: Option<IndexBaseArg>,
+    /// Print the result as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Directory (searched recursively for .txt files) or glob pattern
+    #[arg(default_value = "examples_from_mdp")]
+    pub input: String,
+    /// Comma-separated solver names; defaults to the size-based suites
+    #[arg(long, value_delimiter = ',')]
+    pub solvers: Vec<String>,
+    /// Problem to solve; maxcut reads the instances as weighted graphs and runs the Cut-* solvers,
+    /// pareto runs the Pareto-* solvers and saves their fronts
+    #[arg(long, value_enum, default_value = "mdp")]
+    pub problem: ProblemArg,
+    #[command(flatten)]
+    pub budget: BudgetArgs,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Objective to optimize; solvers without an `objective` parameter are skipped
+    #[arg(long, value_enum)]
+    pub objective: Option<ObjectiveArg>,
+    /// Index base of the MaxCut edge lists that do not settle it themselves
+    #[arg(long, value_enum)]
+    pub index_base: Option<IndexBaseArg>,
+    /// Results file; defaults to results_<timestamp>.json
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Instance file in MDPLIB format
+    pub instance: String,
+    /// Selected indices (whitespace or comma separated) or JSON from `solve --json`
+    pub solution: String,
+    /// Also report the value under this objective (max-mean accepts any size from 2)
+    #[arg(long, value_enum)]
+    pub objective: Option<ObjectiveArg>,
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    #[arg(long)]
+    pub n: usize,
+    #[arg(long)]
+    pub k: usize,
+    #[arg(long, value_enum, default_value = "euclidean")]
+    pub kind: KindArg,
+    /// Coordinates per point for Euclidean instances
+    #[arg(long, default_value_t = 2)]
+    pub dims: usize,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Also write a `groups` line for this many balanced groups
+    #[arg(long)]
+    pub groups: Option<usize>,
+    /// Also write integer weights in 1..=10 and a capacity of this fraction of their total
+    #[arg(long)]
+    pub capacity: Option<f64>,
+    /// Output file; the instance is written to stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProblemArg {
+    /// Maximum Diversity Problem
+    Mdp,
+    /// MaxCut on a weighted graph: an `n m` header and `i j [w]` edge lines, 0-based if a 0 index appears,
+    /// 1-based if n does, otherwise as --index-base says
+    Maxcut,
+    /// Bi-objective MDP: the Pareto front of max-sum against max-min, with the Pareto-* solvers
+    Pareto,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ObjectiveArg {
+    #[value(name = "max-sum")]
+    Sum,
+    #[value(name = "max-min")]
+    Min,
+    #[value(name = "max-mean")]
+    Mean,
+    #[value(name = "max-min-sum")]
+    MinSum,
+    #[value(name = "min-diff")]
+    Diff,
+}
+
+impl From<ObjectiveArg> for Objective {
+    fn from(objective: ObjectiveArg) -> Self {
+        match objective {
+            ObjectiveArg::Sum => Objective::MaxSum,
+            ObjectiveArg::Min => Objective::MaxMin,
+            ObjectiveArg::Mean => Objective::MaxMean,
+            ObjectiveArg::MinSum => Objective::MaxMinSum,
+            ObjectiveArg::Diff => Objective::MinDiff,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum IndexBaseArg {
+    #[value(name = "0")]
+    Zero,
+    #[value(name = "1")]
+    One,
+}
+
+impl From<IndexBaseArg> for IndexBase {
+    fn from(base: IndexBaseArg) -> Self {
+        match base {
+            IndexBaseArg::Zero => IndexBase::Zero,
+            IndexBaseArg::One => IndexBase::One,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum KindArg {
+    Euclidean,
+    Uniform,
+    Integer,
+    Signed,
+}
+
+impl From<KindArg> for InstanceKind {
+    fn from(kind: KindArg) -> Self {
+        match kind {
+            KindArg::Euclidean => InstanceKind::Euclidean,
+            KindArg::Uniform => InstanceKind::Uniform,
+            KindArg::Integer => InstanceKind::Integer,
+            KindArg::Signed => InstanceKind::Signed,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Instance file in MDPLIB format
+    pub instance: String,
+    #[arg(long, value_enum, default_value = "qbsolv")]
+    pub format: ExportFormat,
+    /// MIP formulation written by the lp and mps formats
+    #[arg(long, value_enum, default_value = "quad")]
+    pub formulation: FormulationArg,
+    /// QUBO penalty λ: a number, "bound" or "relative:<factor>"
+    #[arg(long, default_value = "bound")]
+    pub penalty: String,
+    /// Output file; the model is written to stdout when omitted
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Qbsolv,
+    Coordinate,
+    Dense,
+    Ising,
+    Lp,
+    Mps,
+}
+
+impl ExportFormat {
+    /// The QUBO text format, or `None` for the MIP formats.
+    pub fn qubo_format(self) -> Option<QuboFormat> {
+        match self {
+            ExportFormat::Qbsolv => Some(QuboFormat::Qbsolv),
+            ExportFormat::Coordinate => Some(QuboFormat::Coordinate),
+            ExportFormat::Dense => Some(QuboFormat::Dense),
+            ExportFormat::Ising => Some(QuboFormat::Ising),
+            ExportFormat::Lp | ExportFormat::Mps => None,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct GroupArgs {
+    /// Instance file in MDPLIB format, optionally with a `groups` line
+    pub instance: String,
+    /// Number of groups; overrides the instance's `groups` line
+    #[arg(long)]
+    pub groups: Option<usize>,
+    /// Smallest group size; defaults to the instance's limit or balanced groups
+    #[arg(long)]
+    pub min_size: Option<usize>,
+    /// Largest group size; defaults to the instance's limit or balanced groups
+    #[arg(long)]
+    pub max_size: Option<usize>,
+    #[arg(long, value_enum, default_value = "tabu")]
+    pub method: GroupMethodArg,
+    #[command(flatten)]
+    pub budget: BudgetArgs,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Solver parameter override, repeatable
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub params: Vec<(String, String)>,
+    /// Print the result as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum GroupMethodArg {
+    Tabu,
+    Ils,
+}
+
+impl From<GroupMethodArg> for GroupingMethod {
+    fn from(method: GroupMethodArg) -> Self {
+        match method {
+            GroupMethodArg::Tabu => GroupingMethod::TabuSearch { tabu_tenure: 10 },
+            GroupMethodArg::Ils => GroupingMethod::IteratedLocalSearch { perturbation: 5 },
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FormulationArg {
+    Quad,
+    Glover,
+    Kgd,
+}
+
+impl From<FormulationArg> for MipFormulation {
+    fn from(formulation: FormulationArg) -> Self {
+        match formulation {
+            FormulationArg::Quad => MipFormulation::Quadratic,
+            FormulationArg::Glover => MipFormulation::Glover,
+            FormulationArg::Kgd => MipFormulation::KuoGloverDhir,
+        }
+    }
+}
+
+fn parse_key_value(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))
+}
+
+// A time limit that converts to a `Duration`: finite, not negative and not too large
+fn parse_seconds(arg: &str) -> Result<f64, String> {
+    match arg.parse::<f64>() {
+        Ok(seconds) if Budget::try_with_time_limit(seconds).is_ok() => Ok(seconds),
+        _ => Err(format!("expected a non-negative, finite number of seconds, got '{}'", arg)),
+    }
+}
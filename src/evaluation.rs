@@ -0,0 +1,1113 @@
+//! Objective evaluation, feasibility checks and incremental solution state.
+
+use crate::parser::{MdpData, SideConstraints};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Position of an element between being detached from one list and pushed onto the other
+const NOT_LISTED: usize = usize::MAX;
+
+/// Sum of pairwise distances within `selected`, computed from scratch in O(k²).
+pub fn calculate_diversity(selected: &[usize], data: &MdpData) -> f64 {
+    let mut sum = 0.0;
+    for (idx, &a) in selected.iter().enumerate() {
+        for &b in &selected[idx + 1..] {
+            sum += data.get_dist(a, b);
+        }
+    }
+    sum
+}
+
+/// Smallest pairwise distance within `selected` (0 for fewer than two elements), in O(k²).
+pub fn calculate_min_distance(selected: &[usize], data: &MdpData) -> f64 {
+    let mut min = f64::INFINITY;
+    for (idx, &a) in selected.iter().enumerate() {
+        for &b in &selected[idx + 1..] {
+            min = min.min(data.get_dist(a, b));
+        }
+    }
+    finite_or_zero(min)
+}
+
+/// Pairwise sum of `selected` divided by its size (0 for an empty selection).
+pub fn calculate_mean_dispersion(selected: &[usize], data: &MdpData) -> f64 {
+    if selected.is_empty() {
+        return 0.0;
+    }
+    calculate_diversity(selected, data) / selected.len() as f64
+}
+
+/// Smallest and largest per-element sum `s(u) = sum_{w in selected} d_uw` over
+/// `selected`, in O(k²); (0, 0) for an empty selection.
+pub fn calculate_sum_range(selected: &[usize], data: &MdpData) -> (f64, f64) {
+    let sums = selected
+        .iter()
+        .map(|&u| selected.iter().filter(|&&w| w != u).map(|&w| data.get_dist(u, w)).sum::<f64>());
+    range_or_zero(sums)
+}
+
+/// A selection scored on both objectives of the bi-objective MDP: its pairwise
+/// sum and its smallest pairwise distance, both maximized.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParetoPoint {
+    pub selected: Vec<usize>, // Sorted
+    pub sum: f64,
+    pub min: f64,
+}
+
+impl ParetoPoint {
+    pub fn new(mut selected: Vec<usize>, data: &MdpData) -> Self {
+        // Sorted, so that the same selection always sums up to the same value
+        selected.sort_unstable();
+        let sum = calculate_diversity(&selected, data);
+        let min = calculate_min_distance(&selected, data);
+        ParetoPoint { selected, sum, min }
+    }
+
+    /// Whether `self` is at least as good on both objectives and better on one.
+    pub fn dominates(&self, other: &ParetoPoint) -> bool {
+        self.sum >= other.sum && self.min >= other.min && (self.sum > other.sum || self.min > other.min)
+    }
+}
+
+/// The non-dominated points of `points`, one per pair of values, by decreasing
+/// sum (and so increasing min).
+pub fn pareto_front(points: impl IntoIterator<Item = ParetoPoint>) -> Vec<ParetoPoint> {
+    let mut points: Vec<ParetoPoint> = points.into_iter().collect();
+    // After sorting by decreasing sum, then min, a point is dominated exactly
+    // when the last point kept has at least its min
+    points.sort_by(|a, b| b.sum.total_cmp(&a.sum).then(b.min.total_cmp(&a.min)));
+    let mut front: Vec<ParetoPoint> = Vec::new();
+    for point in points {
+        if front.last().is_none_or(|last| point.min > last.min) {
+            front.push(point);
+        }
+    }
+    front
+}
+
+/// Slack allowed on capacity constraints for rounding in the weight sums.
+pub const CAPACITY_TOLERANCE: f64 = 1e-9;
+
+// Smallest selection the max-mean objective accepts: a single element has no pairs
+pub const MAX_MEAN_MIN_SIZE: usize = 2;
+
+/// What a selection is scored by. All but max-mean select exactly k elements,
+/// and all but min-diff are maximized. The per-element sums of the equity
+/// objectives are `s(u) = sum_{w in S} d_uw` over the selected `u`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Objective {
+    #[default]
+    MaxSum,    // Sum of pairwise distances, the classic MDP
+    MaxMin,    // Smallest pairwise distance (max-min diversity, or dispersion)
+    MaxMean,   // Pairwise sum divided by the size, over subsets of any size >= 2
+    MaxMinSum, // Smallest per-element sum
+    MinDiff,   // Largest minus smallest per-element sum, minimized
+}
+
+impl Objective {
+    pub const ALL: [Objective; 5] = [
+        Objective::MaxSum,
+        Objective::MaxMin,
+        Objective::MaxMean,
+        Objective::MaxMinSum,
+        Objective::MinDiff,
+    ];
+
+    /// Name used by the `objective` parameter and in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Objective::MaxSum => "max-sum",
+            Objective::MaxMin => "max-min",
+            Objective::MaxMean => "max-mean",
+            Objective::MaxMinSum => "max-min-sum",
+            Objective::MinDiff => "min-diff",
+        }
+    }
+
+    pub fn minimized(self) -> bool {
+        self == Objective::MinDiff
+    }
+
+    /// Converts between an objective value and the score the searches
+    /// maximize: the value itself, or its negation for minimized objectives.
+    pub fn score(self, value: f64) -> f64 {
+        if self.minimized() {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Whether the instance's k fixes the selection size; max-mean leaves it free.
+    pub fn fixed_size(self) -> bool {
+        self != Objective::MaxMean
+    }
+
+    /// Value of `selected` under this objective, computed from scratch.
+    pub fn evaluate(self, selected: &[usize], data: &MdpData) -> f64 {
+        match self {
+            Objective::MaxSum => calculate_diversity(selected, data),
+            Objective::MaxMin => calculate_min_distance(selected, data),
+            Objective::MaxMean => calculate_mean_dispersion(selected, data),
+            Objective::MaxMinSum => calculate_sum_range(selected, data).0,
+            Objective::MinDiff => {
+                let (min, max) = calculate_sum_range(selected, data);
+                max - min
+            }
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Accepts the names with or without the dash ("max-min", "maxmin")
+impl FromStr for Objective {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let s = s.to_ascii_lowercase().replace(['-', '_'], "");
+        Objective::ALL
+            .into_iter()
+            .find(|objective| objective.name().replace('-', "") == s)
+            .ok_or(())
+    }
+}
+
+/// One reason a selection is not a feasible solution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    IndexOutOfRange { index: usize },
+    Duplicate { index: usize },
+    WrongCardinality { expected: usize, found: usize },
+    TooFewElements { minimum: usize, found: usize },
+    SizeOutOfRange { min_size: usize, max_size: usize, found: usize },
+    OverCapacity { capacity: f64, weight: f64 },
+    ExcludedSelected { index: usize },
+    IncludedMissing { index: usize },
+    QuotaViolated { category: usize, min: usize, max: usize, found: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::IndexOutOfRange { index } => write!(f, "index {} is out of range", index),
+            Violation::Duplicate { index } => write!(f, "index {} is selected more than once", index),
+            Violation::WrongCardinality { expected, found } => {
+                write!(f, "{} elements selected, expected {}", found, expected)
+            }
+            Violation::TooFewElements { minimum, found } => {
+                write!(f, "{} elements selected, expected at least {}", found, minimum)
+            }
+            Violation::SizeOutOfRange { min_size, max_size, found } => {
+                write!(f, "{} elements selected, expected {}..={}", found, min_size, max_size)
+            }
+            Violation::OverCapacity { capacity, weight } => {
+                write!(f, "total weight {} exceeds the capacity {}", weight, capacity)
+            }
+            Violation::ExcludedSelected { index } => write!(f, "index {} is excluded but selected", index),
+            Violation::IncludedMissing { index } => write!(f, "index {} must be selected", index),
+            Violation::QuotaViolated { category, min, max, found } => write!(
+                f,
+                "{} elements of category {} selected, expected {}..={}",
+                found, category, min, max
+            ),
+        }
+    }
+}
+
+/// Every reason `selected` is not a feasible solution of `data` (empty if it is).
+pub fn check_selection(selected: &[usize], data: &MdpData) -> Vec<Violation> {
+    check_selection_for(selected, data, Objective::MaxSum)
+}
+
+/// Like `check_selection`, with the size rule of `objective`. Capacitated
+/// instances replace it by their cardinality limits and capacity; side
+/// constraints are checked on top of either.
+pub fn check_selection_for(selected: &[usize], data: &MdpData, objective: Objective) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut seen = vec![false; data.n];
+
+    for &index in selected {
+        if index >= data.n {
+            violations.push(Violation::IndexOutOfRange { index });
+        } else if seen[index] {
+            violations.push(Violation::Duplicate { index });
+        } else {
+            seen[index] = true;
+        }
+    }
+    if let Some(spec) = &data.capacity {
+        let found = selected.len();
+        if found < spec.min_size || found > spec.max_size {
+            violations.push(Violation::SizeOutOfRange { min_size: spec.min_size, max_size: spec.max_size, found });
+        }
+        let weight: f64 = selected.iter().filter(|&&i| i < data.n).map(|&i| spec.weights[i]).sum();
+        if weight > spec.capacity + CAPACITY_TOLERANCE {
+            violations.push(Violation::OverCapacity { capacity: spec.capacity, weight });
+        }
+    } else if objective.fixed_size() && selected.len() != data.k {
+        violations.push(Violation::WrongCardinality { expected: data.k, found: selected.len() });
+    } else if !objective.fixed_size() && selected.len() < MAX_MEAN_MIN_SIZE {
+        violations.push(Violation::TooFewElements { minimum: MAX_MEAN_MIN_SIZE, found: selected.len() });
+    }
+    if let Some(spec) = &data.constraints {
+        check_side_constraints(&seen, spec, &mut violations);
+    }
+
+    violations
+}
+
+// `seen` marks the distinct in-range selected elements
+fn check_side_constraints(seen: &[bool], spec: &SideConstraints, violations: &mut Vec<Violation>) {
+    let mut counts = vec![0; spec.quotas.len()];
+    for (index, &selected) in seen.iter().enumerate() {
+        if selected && spec.forbidden[index] {
+            violations.push(Violation::ExcludedSelected { index });
+        }
+        if !selected && spec.required[index] {
+            violations.push(Violation::IncludedMissing { index });
+        }
+        if let (true, Some(c)) = (selected, spec.category[index]) {
+            counts[c] += 1;
+        }
+    }
+    for (category, (&(min, max), &found)) in spec.quotas.iter().zip(&counts).enumerate() {
+        if found < min || found > max {
+            violations.push(Violation::QuotaViolated { category, min, max, found });
+        }
+    }
+}
+
+/// Per-category counts of a selection, so moves can be checked against the
+/// side constraints of the instance in O(1). Without side constraints every
+/// move is admissible.
+#[derive(Clone)]
+pub struct ConstraintCounts<'a> {
+    constraints: Option<&'a SideConstraints>,
+    counts: Vec<usize>,
+    deficit: usize, // Elements still missing from the categories below their minimum
+}
+
+impl<'a> ConstraintCounts<'a> {
+    pub fn new(data: &'a MdpData, selected: &[usize]) -> Self {
+        let constraints = data.constraints.as_ref();
+        let quotas = constraints.map_or(&[][..], |spec| &spec.quotas);
+        let mut counts = ConstraintCounts {
+            constraints,
+            counts: vec![0; quotas.len()],
+            deficit: quotas.iter().map(|&(min, _)| min).sum(),
+        };
+        for &v in selected {
+            counts.add(v);
+        }
+        counts
+    }
+
+    /// Elements that must be added before every category reaches its minimum.
+    pub fn deficit(&self) -> usize {
+        self.deficit
+    }
+
+    /// Records that `v` joined the selection.
+    pub fn add(&mut self, v: usize) {
+        if let Some(c) = self.category(v) {
+            if self.counts[c] < self.quota(c).0 {
+                self.deficit -= 1;
+            }
+            self.counts[c] += 1;
+        }
+    }
+
+    /// Records that `v` left the selection.
+    pub fn remove(&mut self, v: usize) {
+        if let Some(c) = self.category(v) {
+            self.counts[c] -= 1;
+            if self.counts[c] < self.quota(c).0 {
+                self.deficit += 1;
+            }
+        }
+    }
+
+    /// Whether `v` belongs to a category below its minimum.
+    pub fn fills_deficit(&self, v: usize) -> bool {
+        self.category(v).is_some_and(|c| self.counts[c] < self.quota(c).0)
+    }
+
+    /// Whether adding unselected `v` keeps it out of the excluded elements and
+    /// its category within its maximum.
+    pub fn can_add(&self, v: usize) -> bool {
+        let Some(spec) = self.constraints else { return true };
+        !spec.forbidden[v] && self.category(v).is_none_or(|c| self.counts[c] < self.quota(c).1)
+    }
+
+    /// Whether dropping selected `v` keeps the included elements and its
+    /// category's minimum.
+    pub fn can_drop(&self, v: usize) -> bool {
+        let Some(spec) = self.constraints else { return true };
+        !spec.required[v] && self.category(v).is_none_or(|c| self.counts[c] > self.quota(c).0)
+    }
+
+    /// Whether replacing selected `out` by unselected `inc` keeps every constraint
+    /// that holds; a swap within a category leaves its count unchanged.
+    pub fn can_swap(&self, out: usize, inc: usize) -> bool {
+        let Some(spec) = self.constraints else { return true };
+        if spec.required[out] || spec.forbidden[inc] {
+            return false;
+        }
+        let (from, to) = (self.category(out), self.category(inc));
+        from == to
+            || (from.is_none_or(|c| self.counts[c] > self.quota(c).0)
+                && to.is_none_or(|c| self.counts[c] < self.quota(c).1))
+    }
+
+    /// Whether unselected `v` can be added to a selection that will grow by
+    /// `remaining` more elements afterwards, still leaving room to reach every
+    /// category's minimum.
+    pub fn can_extend(&self, v: usize, remaining: usize) -> bool {
+        self.can_add(v) && self.deficit - self.fills_deficit(v) as usize <= remaining
+    }
+
+    fn category(&self, v: usize) -> Option<usize> {
+        self.constraints.and_then(|spec| spec.category[v])
+    }
+
+    fn quota(&self, c: usize) -> (usize, usize) {
+        self.constraints.map_or((0, usize::MAX), |spec| spec.quotas[c])
+    }
+}
+
+/// A random selection of `size` elements meeting the side constraints of
+/// `data`: the included elements, then random admissible ones. It falls short
+/// of `size` only if no selection of that size meets them.
+pub fn random_selection(data: &MdpData, size: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..data.n).collect();
+    order.shuffle(rng);
+    let Some(spec) = &data.constraints else {
+        order.truncate(size);
+        return order;
+    };
+
+    let mut selected = spec.required_elements();
+    let mut counts = ConstraintCounts::new(data, &selected);
+    for v in order {
+        if selected.len() >= size {
+            break;
+        }
+        if !spec.required[v] && counts.can_extend(v, size - selected.len() - 1) {
+            counts.add(v);
+            selected.push(v);
+        }
+    }
+    selected
+}
+
+/// Greedily fixes the cardinality of `selected`: while it has too many
+/// elements the one contributing least is dropped, while it has too few the
+/// one adding most is added. Out-of-range and repeated indices are ignored.
+/// Side constraints are restored first: the included elements are kept,
+/// then the elements of `selected` in order as long as they are not excluded
+/// and their category has room, then the best elements of categories below
+/// their minimum are added. The size is fixed only through admissible moves.
+pub fn repair_selection(selected: &[usize], data: &MdpData) -> Vec<usize> {
+    let mut state = SolutionState::empty(data);
+    let required = data.constraints.as_ref().map(|spec| spec.required_elements()).unwrap_or_default();
+    let mut counts = ConstraintCounts::new(data, &required);
+    for &v in &required {
+        state.add(v);
+    }
+    for &v in selected {
+        if v < data.n && !state.contains(v) && counts.can_add(v) {
+            state.add(v);
+            counts.add(v);
+        }
+    }
+
+    // `can_add` kept every category within its maximum; fill the minimums next
+    while counts.deficit() > 0 {
+        let best = state
+            .unselected()
+            .iter()
+            .copied()
+            .filter(|&v| counts.fills_deficit(v) && counts.can_add(v))
+            .max_by(|&a, &b| state.contribution(a).total_cmp(&state.contribution(b)));
+        let Some(best) = best else { break };
+        state.add(best);
+        counts.add(best);
+    }
+    while state.selected().len() > data.k {
+        let worst = state
+            .selected()
+            .iter()
+            .copied()
+            .filter(|&v| counts.can_drop(v))
+            .min_by(|&a, &b| state.contribution(a).total_cmp(&state.contribution(b)));
+        let Some(worst) = worst else { break };
+        state.remove(worst);
+        counts.remove(worst);
+    }
+    while state.selected().len() < data.k {
+        let remaining = data.k - state.selected().len() - 1;
+        let best = state
+            .unselected()
+            .iter()
+            .copied()
+            .filter(|&v| counts.can_extend(v, remaining))
+            .max_by(|&a, &b| state.contribution(a).total_cmp(&state.contribution(b)));
+        let Some(best) = best else { break };
+        state.add(best);
+        counts.add(best);
+    }
+
+    state.into_selected()
+}
+
+/// Incremental evaluation of a selection under one objective, so the greedy
+/// constructions and swap-based searches can run on any objective that
+/// implements it. Values are scores (see `Objective::score`): higher is better.
+pub trait IncrementalState<'a>: Sized {
+    /// Starts from an empty selection, with every element unselected.
+    fn empty(data: &'a MdpData) -> Self;
+
+    /// Builds the state for `selected` by adding its elements one by one.
+    fn new(data: &'a MdpData, selected: &[usize]) -> Self {
+        let mut state = Self::empty(data);
+        for &v in selected {
+            state.add(v);
+        }
+        state
+    }
+
+    fn value(&self) -> f64;
+    fn selected(&self) -> &[usize];
+    fn unselected(&self) -> &[usize];
+
+    /// How attractive unselected `v` is to a greedy construction.
+    fn greedy_score(&self, v: usize) -> f64;
+
+    /// Change in value if selected `out` is replaced by unselected `inc`.
+    fn swap_gain(&self, out: usize, inc: usize) -> f64;
+
+    /// Change in value if unselected `v` is added, or `None` when the
+    /// objective fixes the selection size.
+    fn add_gain(&self, _v: usize) -> Option<f64> {
+        None
+    }
+
+    /// Change in value if selected `v` is dropped, or `None` when the
+    /// objective does not allow it.
+    fn drop_gain(&self, _v: usize) -> Option<f64> {
+        None
+    }
+
+    fn add(&mut self, v: usize);
+    fn remove(&mut self, v: usize);
+    fn apply_swap(&mut self, out: usize, inc: usize);
+    fn into_selected(self) -> Vec<usize>;
+}
+
+/// A selection together with, for every element, its summed distance to the
+/// current selection. Swap gains are O(1) and applying a move is O(n).
+#[derive(Clone)]
+pub struct SolutionState<'a> {
+    data: &'a MdpData,
+    selected: Vec<usize>,
+    unselected: Vec<usize>,
+    in_solution: Vec<bool>,
+    position: Vec<usize>, // Index of each element in `selected` or `unselected`
+    contrib: Vec<f64>,
+    value: f64,
+}
+
+impl<'a> SolutionState<'a> {
+    /// Starts from an empty selection, with every element unselected.
+    pub fn empty(data: &'a MdpData) -> Self {
+        SolutionState {
+            data,
+            selected: Vec::with_capacity(data.k),
+            unselected: (0..data.n).collect(),
+            in_solution: vec![false; data.n],
+            position: (0..data.n).collect(),
+            contrib: vec![0.0; data.n],
+            value: 0.0,
+        }
+    }
+
+    /// Builds the state for `selected` in O(nk).
+    pub fn new(data: &'a MdpData, selected: &[usize]) -> Self {
+        let mut state = SolutionState::empty(data);
+        for &v in selected {
+            state.add(v);
+        }
+        state
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn selected(&self) -> &[usize] {
+        &self.selected
+    }
+
+    pub fn unselected(&self) -> &[usize] {
+        &self.unselected
+    }
+
+    pub fn contains(&self, v: usize) -> bool {
+        self.in_solution[v]
+    }
+
+    /// Summed distance from `v` to every selected element (excluding `v` itself).
+    pub fn contribution(&self, v: usize) -> f64 {
+        self.contrib[v]
+    }
+
+    /// Change in diversity if selected `out` is replaced by unselected `inc`.
+    pub fn swap_gain(&self, out: usize, inc: usize) -> f64 {
+        self.contrib[inc] - self.contrib[out] - self.data.get_dist(inc, out)
+    }
+
+    /// Adds unselected `v` to the selection.
+    pub fn add(&mut self, v: usize) {
+        debug_assert!(!self.in_solution[v]);
+        self.value += self.contrib[v];
+        self.detach(v);
+        self.position[v] = self.selected.len();
+        self.selected.push(v);
+        self.in_solution[v] = true;
+        self.update_contributions(v, 1.0);
+    }
+
+    /// Drops selected `v` from the selection.
+    pub fn remove(&mut self, v: usize) {
+        debug_assert!(self.in_solution[v]);
+        self.detach(v);
+        self.position[v] = self.unselected.len();
+        self.unselected.push(v);
+        self.in_solution[v] = false;
+        self.update_contributions(v, -1.0);
+        self.value -= self.contrib[v];
+    }
+
+    /// Replaces selected `out` by unselected `inc`; each takes the other's list slot.
+    pub fn apply_swap(&mut self, out: usize, inc: usize) {
+        debug_assert!(self.in_solution[out] && !self.in_solution[inc]);
+        self.value += self.swap_gain(out, inc);
+
+        let (out_pos, inc_pos) = (self.position[out], self.position[inc]);
+        self.selected[out_pos] = inc;
+        self.unselected[inc_pos] = out;
+        self.position[inc] = out_pos;
+        self.position[out] = inc_pos;
+        self.in_solution[out] = false;
+        self.in_solution[inc] = true;
+
+        self.update_contributions(inc, 1.0);
+        self.update_contributions(out, -1.0);
+    }
+
+    /// Consumes the state, returning the selected elements.
+    pub fn into_selected(self) -> Vec<usize> {
+        self.selected
+    }
+
+    // Removes `v` from whichever list holds it, keeping `position` consistent
+    fn detach(&mut self, v: usize) {
+        let pos = self.position[v];
+        let list = if self.in_solution[v] { &mut self.selected } else { &mut self.unselected };
+        list.swap_remove(pos);
+        if let Some(&moved) = list.get(pos) {
+            self.position[moved] = pos;
+        }
+        self.position[v] = NOT_LISTED;
+    }
+
+    // The diagonal is skipped so a stray self-distance never leaks into the sums
+    fn update_contributions(&mut self, v: usize, sign: f64) {
+        let row = &self.data.distances[v * self.data.n..(v + 1) * self.data.n];
+        for (u, (c, &d)) in self.contrib.iter_mut().zip(row).enumerate() {
+            if u != v {
+                *c += sign * d;
+            }
+        }
+    }
+}
+
+impl<'a> IncrementalState<'a> for SolutionState<'a> {
+    fn empty(data: &'a MdpData) -> Self {
+        SolutionState::empty(data)
+    }
+
+    fn value(&self) -> f64 {
+        SolutionState::value(self)
+    }
+
+    fn selected(&self) -> &[usize] {
+        SolutionState::selected(self)
+    }
+
+    fn unselected(&self) -> &[usize] {
+        SolutionState::unselected(self)
+    }
+
+    fn greedy_score(&self, v: usize) -> f64 {
+        self.contribution(v)
+    }
+
+    fn swap_gain(&self, out: usize, inc: usize) -> f64 {
+        SolutionState::swap_gain(self, out, inc)
+    }
+
+    fn add(&mut self, v: usize) {
+        SolutionState::add(self, v)
+    }
+
+    fn remove(&mut self, v: usize) {
+        SolutionState::remove(self, v)
+    }
+
+    fn apply_swap(&mut self, out: usize, inc: usize) {
+        SolutionState::apply_swap(self, out, inc)
+    }
+
+    fn into_selected(self) -> Vec<usize> {
+        SolutionState::into_selected(self)
+    }
+}
+
+/// A selection scored by its smallest pairwise distance. Every element keeps
+/// its nearest and second-nearest selected distance, so a swap is evaluated
+/// in O(1); applying a move costs O(n) plus a rescan of the elements whose
+/// nearest neighbours left, and an O(k²) refresh of the removal values.
+#[derive(Clone)]
+pub struct MaxMinState<'a> {
+    base: SolutionState<'a>,   // Selection lists (its sum contributions go unused)
+    nearest: Vec<(f64, usize)>, // Distance to, and index of, the closest selected element
+    second: Vec<f64>,           // Distance to the second closest selected element
+    without: Vec<f64>,          // For selected elements: the selection's minimum once it leaves
+    value: f64,
+}
+
+impl<'a> MaxMinState<'a> {
+    // Records that `v` joined the selection
+    fn note_added(&mut self, v: usize) {
+        let data = self.base.data;
+        for u in (0..data.n).filter(|&u| u != v) {
+            let d = data.get_dist(u, v);
+            if d < self.nearest[u].0 {
+                self.second[u] = self.nearest[u].0;
+                self.nearest[u] = (d, v);
+            } else if d < self.second[u] {
+                self.second[u] = d;
+            }
+        }
+    }
+
+    // Records that `v` left the selection; only elements that had it as one
+    // of their two nearest need a rescan
+    fn note_removed(&mut self, v: usize) {
+        let data = self.base.data;
+        for u in (0..data.n).filter(|&u| u != v) {
+            if self.nearest[u].1 == v || data.get_dist(u, v) <= self.second[u] {
+                self.rescan(u);
+            }
+        }
+    }
+
+    fn rescan(&mut self, u: usize) {
+        let mut nearest = (f64::INFINITY, NOT_LISTED);
+        let mut second = f64::INFINITY;
+        for &w in self.base.selected().iter().filter(|&&w| w != u) {
+            let d = self.base.data.get_dist(u, w);
+            if d < nearest.0 {
+                second = nearest.0;
+                nearest = (d, w);
+            } else if d < second {
+                second = d;
+            }
+        }
+        self.nearest[u] = nearest;
+        self.second[u] = second;
+    }
+
+    // Nearest selected distance of `u` once `out` is gone
+    fn nearest_without(&self, u: usize, out: usize) -> f64 {
+        if self.nearest[u].1 == out {
+            self.second[u]
+        } else {
+            self.nearest[u].0
+        }
+    }
+
+    fn refresh(&mut self) {
+        let selected = self.base.selected();
+        self.value = finite_or_zero(selected.iter().map(|&u| self.nearest[u].0).fold(f64::INFINITY, f64::min));
+        for &out in selected {
+            self.without[out] = selected
+                .iter()
+                .filter(|&&u| u != out)
+                .map(|&u| self.nearest_without(u, out))
+                .fold(f64::INFINITY, f64::min);
+        }
+    }
+}
+
+impl<'a> IncrementalState<'a> for MaxMinState<'a> {
+    fn empty(data: &'a MdpData) -> Self {
+        MaxMinState {
+            base: SolutionState::empty(data),
+            nearest: vec![(f64::INFINITY, NOT_LISTED); data.n],
+            second: vec![f64::INFINITY; data.n],
+            without: vec![f64::INFINITY; data.n],
+            value: 0.0,
+        }
+    }
+
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn selected(&self) -> &[usize] {
+        self.base.selected()
+    }
+
+    fn unselected(&self) -> &[usize] {
+        self.base.unselected()
+    }
+
+    // Distance to the closest selected element; every element ties while none is selected
+    fn greedy_score(&self, v: usize) -> f64 {
+        finite_or_zero(self.nearest[v].0)
+    }
+
+    fn swap_gain(&self, out: usize, inc: usize) -> f64 {
+        finite_or_zero(self.without[out].min(self.nearest_without(inc, out))) - self.value
+    }
+
+    fn add(&mut self, v: usize) {
+        self.base.add(v);
+        self.note_added(v);
+        self.refresh();
+    }
+
+    fn remove(&mut self, v: usize) {
+        self.base.remove(v);
+        self.note_removed(v);
+        self.refresh();
+    }
+
+    fn apply_swap(&mut self, out: usize, inc: usize) {
+        self.base.apply_swap(out, inc);
+        self.note_added(inc);
+        self.note_removed(out);
+        self.refresh();
+    }
+
+    fn into_selected(self) -> Vec<usize> {
+        self.base.into_selected()
+    }
+}
+
+/// A selection of free size scored by its pairwise sum divided by its size.
+/// The sum and contributions of `SolutionState` give every add, drop and
+/// swap in O(1).
+#[derive(Clone)]
+pub struct MaxMeanState<'a> {
+    base: SolutionState<'a>,
+}
+
+impl<'a> MaxMeanState<'a> {
+    fn size(&self) -> f64 {
+        self.base.selected().len() as f64
+    }
+}
+
+impl<'a> IncrementalState<'a> for MaxMeanState<'a> {
+    fn empty(data: &'a MdpData) -> Self {
+        MaxMeanState { base: SolutionState::empty(data) }
+    }
+
+    fn value(&self) -> f64 {
+        if self.base.selected().is_empty() {
+            0.0
+        } else {
+            self.base.value() / self.size()
+        }
+    }
+
+    fn selected(&self) -> &[usize] {
+        self.base.selected()
+    }
+
+    fn unselected(&self) -> &[usize] {
+        self.base.unselected()
+    }
+
+    fn greedy_score(&self, v: usize) -> f64 {
+        self.base.contribution(v)
+    }
+
+    fn swap_gain(&self, out: usize, inc: usize) -> f64 {
+        self.base.swap_gain(out, inc) / self.size()
+    }
+
+    fn add_gain(&self, v: usize) -> Option<f64> {
+        Some((self.base.value() + self.base.contribution(v)) / (self.size() + 1.0) - self.value())
+    }
+
+    fn drop_gain(&self, v: usize) -> Option<f64> {
+        (self.base.selected().len() > MAX_MEAN_MIN_SIZE)
+            .then(|| (self.base.value() - self.base.contribution(v)) / (self.size() - 1.0) - self.value())
+    }
+
+    fn add(&mut self, v: usize) {
+        self.base.add(v)
+    }
+
+    fn remove(&mut self, v: usize) {
+        self.base.remove(v)
+    }
+
+    fn apply_swap(&mut self, out: usize, inc: usize) {
+        self.base.apply_swap(out, inc)
+    }
+
+    fn into_selected(self) -> Vec<usize> {
+        self.base.into_selected()
+    }
+}
+
+/// A selection scored by its smallest per-element sum (Max-MinSum). The sums
+/// are `SolutionState`'s contributions; a move is evaluated in O(k) by
+/// adjusting the k sums it touches.
+#[derive(Clone)]
+pub struct MaxMinSumState<'a> {
+    base: SolutionState<'a>,
+    range: (f64, f64), // Smallest and largest per-element sum of the selection
+}
+
+/// A selection scored by the negated spread `-(max s(u) - min s(u))` of its
+/// per-element sums (Min-Diff), with the same O(k) move evaluation as Max-MinSum.
+#[derive(Clone)]
+pub struct MinDiffState<'a> {
+    base: SolutionState<'a>,
+    range: (f64, f64),
+}
+
+// Per-element sums of the selection in `base` after adding `inc` and dropping
+// `out` (either optional); their smallest and largest
+fn sum_range_after(base: &SolutionState, out: Option<usize>, inc: Option<usize>) -> (f64, f64) {
+    let data = base.data;
+    let kept = base.selected().iter().copied().filter(|&u| Some(u) != out);
+    let kept_sums = kept.map(|u| {
+        let dropped = out.map_or(0.0, |out| data.get_dist(u, out));
+        let added = inc.map_or(0.0, |inc| data.get_dist(u, inc));
+        base.contribution(u) - dropped + added
+    });
+    let inc_sum = inc.map(|inc| base.contribution(inc) - out.map_or(0.0, |out| data.get_dist(inc, out)));
+    range_or_zero(kept_sums.chain(inc_sum))
+}
+
+// Implements `IncrementalState` for the equity states, which differ only in
+// how the sum range is scored
+macro_rules! equity_state {
+    ($state:ident, $score:expr) => {
+        impl<'a> $state<'a> {
+            fn score_of(range: (f64, f64)) -> f64 {
+                let score: fn((f64, f64)) -> f64 = $score;
+                score(range)
+            }
+
+            fn refresh(&mut self) {
+                self.range = sum_range_after(&self.base, None, None);
+            }
+        }
+
+        impl<'a> IncrementalState<'a> for $state<'a> {
+            fn empty(data: &'a MdpData) -> Self {
+                $state { base: SolutionState::empty(data), range: (0.0, 0.0) }
+            }
+
+            fn value(&self) -> f64 {
+                Self::score_of(self.range)
+            }
+
+            fn selected(&self) -> &[usize] {
+                self.base.selected()
+            }
+
+            fn unselected(&self) -> &[usize] {
+                self.base.unselected()
+            }
+
+            // Score of the partial selection once `v` joins it
+            fn greedy_score(&self, v: usize) -> f64 {
+                Self::score_of(sum_range_after(&self.base, None, Some(v)))
+            }
+
+            fn swap_gain(&self, out: usize, inc: usize) -> f64 {
+                Self::score_of(sum_range_after(&self.base, Some(out), Some(inc))) - self.value()
+            }
+
+            fn add(&mut self, v: usize) {
+                self.base.add(v);
+                self.refresh();
+            }
+
+            fn remove(&mut self, v: usize) {
+                self.base.remove(v);
+                self.refresh();
+            }
+
+            fn apply_swap(&mut self, out: usize, inc: usize) {
+                self.base.apply_swap(out, inc);
+                self.refresh();
+            }
+
+            fn into_selected(self) -> Vec<usize> {
+                self.base.into_selected()
+            }
+        }
+    };
+}
+
+equity_state!(MaxMinSumState, |(min, _)| min);
+equity_state!(MinDiffState, |(min, max)| min - max);
+
+// Smallest and largest of `values`, or (0, 0) when there are none
+fn range_or_zero(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold(None, |range: Option<(f64, f64)>, value| match range {
+        Some((min, max)) => Some((min.min(value), max.max(value))),
+        None => Some((value, value)),
+    })
+    .unwrap_or((0.0, 0.0))
+}
+
+// The minimum over no pairs is reported as 0, like the sum over no pairs
+fn finite_or_zero(value: f64) -> f64 {
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const MOVES: usize = 300;
+
+    fn instances(rng: &mut StdRng) -> Vec<MdpData> {
+        [InstanceKind::Euclidean, InstanceKind::Integer, InstanceKind::Signed]
+            .into_iter()
+            .map(|kind| generate_instance(&GeneratorConfig { n: 12, k: 5, kind, dims: 2 }, rng))
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64, context: &str) {
+        assert!((actual - expected).abs() < 1e-6, "{}: {} != {}", context, actual, expected);
+    }
+
+    // Runs random adds, drops and swaps on `S`, checking every predicted gain
+    // and every resulting value against `objective` evaluated from scratch
+    fn check_against_scratch<'a, S: IncrementalState<'a>>(data: &'a MdpData, objective: Objective, rng: &mut StdRng) {
+        let score = |selected: &[usize]| objective.score(objective.evaluate(selected, data));
+        let mut state = S::new(data, &random_selection(data, data.k, rng));
+        assert_close(state.value(), score(state.selected()), "initial selection");
+
+        for step in 0..MOVES {
+            let before = state.value();
+            let size = state.selected().len();
+            let out = *state.selected().choose(rng).unwrap();
+            let inc = *state.unselected().choose(rng).unwrap();
+            let (gain, context) = match rng.gen_range(0..3) {
+                0 if size < data.n - 1 => {
+                    let gain = state.add_gain(inc);
+                    state.add(inc);
+                    (gain, format!("step {}: add {}", step, inc))
+                }
+                1 if size > 1 => {
+                    let gain = state.drop_gain(out);
+                    state.remove(out);
+                    (gain, format!("step {}: drop {}", step, out))
+                }
+                _ => {
+                    let gain = state.swap_gain(out, inc);
+                    state.apply_swap(out, inc);
+                    (Some(gain), format!("step {}: swap {} for {}", step, out, inc))
+                }
+            };
+
+            let expected = score(state.selected());
+            assert_close(state.value(), expected, &context);
+            if let Some(gain) = gain {
+                assert_close(before + gain, expected, &format!("{} (gain)", context));
+            }
+            let mut all: Vec<usize> = state.selected().iter().chain(state.unselected()).copied().collect();
+            all.sort_unstable();
+            assert_eq!(all, (0..data.n).collect::<Vec<_>>(), "{}: selected and unselected split 0..n", context);
+        }
+        assert_close(state.value(), score(&state.into_selected()), "into_selected");
+    }
+
+    #[test]
+    fn solution_state_matches_scratch_evaluation() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for data in instances(&mut rng) {
+            check_against_scratch::<SolutionState>(&data, Objective::MaxSum, &mut rng);
+        }
+    }
+
+    #[test]
+    fn max_min_state_matches_scratch_evaluation() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for data in instances(&mut rng) {
+            check_against_scratch::<MaxMinState>(&data, Objective::MaxMin, &mut rng);
+        }
+    }
+
+    #[test]
+    fn max_mean_state_matches_scratch_evaluation() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for data in instances(&mut rng) {
+            check_against_scratch::<MaxMeanState>(&data, Objective::MaxMean, &mut rng);
+        }
+    }
+
+    #[test]
+    fn max_min_sum_state_matches_scratch_evaluation() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for data in instances(&mut rng) {
+            check_against_scratch::<MaxMinSumState>(&data, Objective::MaxMinSum, &mut rng);
+        }
+    }
+
+    #[test]
+    fn min_diff_state_matches_scratch_evaluation() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for data in instances(&mut rng) {
+            check_against_scratch::<MinDiffState>(&data, Objective::MinDiff, &mut rng);
+        }
+    }
+
+    #[test]
+    fn max_mean_keeps_at_least_two_elements() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let data = generate_instance(&GeneratorConfig { n: 8, k: 2, kind: InstanceKind::Signed, dims: 2 }, &mut rng);
+        let state = MaxMeanState::new(&data, &[0, 1]);
+        assert_eq!(state.drop_gain(0), None);
+        assert!(state.add_gain(2).is_some());
+    }
+}
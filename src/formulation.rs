@@ -0,0 +1,688 @@
+//! MIP formulations of the MDP as plain data, with LP and MPS export. The
+//! Gurobi solvers build their models from these, so an exported file is
+//! exactly the model that `solver_mip` solves.
+
+use crate::evaluation::{Objective, MAX_MEAN_MIN_SIZE};
+use crate::parser::{CapacitySpec, MdpData, SideConstraints};
+use std::io::{self, BufWriter, Write};
+use std::str::FromStr;
+
+// Terms per line in LP files, well under the line length limits of LP readers
+const LP_TERMS_PER_LINE: usize = 6;
+
+/// Model handed to the MIP solver. All three enforce `sum x_i = k`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipFormulation {
+    Quadratic,      // max sum d_ij x_i x_j, left to the solver's own linearization
+    Glover,         // Glover's compact linearization: one continuous w_i per element
+    KuoGloverDhir,  // y_ij = x_i x_j for every pair (Kuo, Glover and Dhir, 1993)
+}
+
+impl MipFormulation {
+    pub const ALL: [MipFormulation; 3] = [
+        MipFormulation::Quadratic,
+        MipFormulation::Glover,
+        MipFormulation::KuoGloverDhir,
+    ];
+
+    /// Short name used in solver names and the `formulation` parameter.
+    pub fn name(self) -> &'static str {
+        match self {
+            MipFormulation::Quadratic => "Quad",
+            MipFormulation::Glover => "Glover",
+            MipFormulation::KuoGloverDhir => "KGD",
+        }
+    }
+}
+
+impl FromStr for MipFormulation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        MipFormulation::ALL
+            .into_iter()
+            .find(|formulation| formulation.name().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VarKind {
+    Binary,
+    Continuous,
+}
+
+pub struct Variable {
+    pub name: String,
+    pub kind: VarKind,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Variable {
+    pub fn is_fixed(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sense {
+    LessEqual,
+    Equal,
+    GreaterEqual,
+}
+
+/// `sum terms (sense) rhs`, with terms as (variable index, coefficient).
+pub struct Constraint {
+    pub name: String,
+    pub terms: Vec<(usize, f64)>,
+    pub sense: Sense,
+    pub rhs: f64,
+}
+
+/// A maximization MIP with an optional quadratic objective. The first `n`
+/// variables are the selection variables `x_i`.
+pub struct MipModel {
+    pub name: String,
+    pub variables: Vec<Variable>,
+    pub objective: Vec<(usize, f64)>,
+    pub quadratic_objective: Vec<(usize, usize, f64)>,
+    pub constraints: Vec<Constraint>,
+}
+
+impl MipModel {
+    /// Builds `formulation` for `data`.
+    pub fn mdp(data: &MdpData, formulation: MipFormulation) -> Self {
+        let size = (Sense::Equal, data.k as f64);
+        let mut model = MipModel::with_selection(format!("MDP_{}", formulation.name()), data, size);
+        match formulation {
+            MipFormulation::Quadratic => model.build_quadratic(data),
+            MipFormulation::Glover => model.build_glover(data),
+            MipFormulation::KuoGloverDhir => model.build_kuo_glover_dhir(data),
+        }
+        model
+    }
+
+    /// The model for `objective`; `formulation` only applies to the max-sum
+    /// objective. Max-mean gives its first Dinkelbach subproblem, `max_mean(data, 0.0)`.
+    pub fn for_objective(data: &MdpData, objective: Objective, formulation: MipFormulation) -> Self {
+        match objective {
+            Objective::MaxSum => MipModel::mdp(data, formulation),
+            Objective::MaxMin => MipModel::max_min(data),
+            Objective::MaxMean => MipModel::max_mean(data, 0.0),
+            Objective::MaxMinSum => MipModel::max_min_sum(data),
+            Objective::MinDiff => MipModel::min_diff(data),
+        }
+    }
+
+    /// Dinkelbach subproblem of max-mean dispersion at ratio `lambda`:
+    /// maximize `sum_{i<j} d_ij x_i x_j - lambda sum_i x_i` over selections of at
+    /// least two elements. Its optimum is 0 exactly when `lambda` is the best mean,
+    /// and otherwise a selection with a larger mean.
+    pub fn max_mean(data: &MdpData, lambda: f64) -> Self {
+        let size = (Sense::GreaterEqual, MAX_MEAN_MIN_SIZE as f64);
+        let mut model = MipModel::with_selection("MDP_MaxMean".to_string(), data, size);
+        model.build_quadratic(data);
+        if lambda != 0.0 {
+            model.objective.extend((0..data.n).map(|i| (i, -lambda)));
+        }
+        model
+    }
+
+    /// Max-min diversity: maximize z subject to
+    /// `z <= d_ij + M_ij (2 - x_i - x_j)` for every pair, with `M_ij = d_max - d_ij`
+    /// so the constraint only binds when both ends are selected.
+    pub fn max_min(data: &MdpData) -> Self {
+        let mut model = MipModel::with_selection("MDP_MaxMin".to_string(), data, (Sense::Equal, data.k as f64));
+        let pairs = || (0..data.n).flat_map(|i| ((i + 1)..data.n).map(move |j| (i, j)));
+        let max_dist = pairs().map(|(i, j)| data.get_dist(i, j)).fold(f64::NEG_INFINITY, f64::max);
+        let min_dist = pairs().map(|(i, j)| data.get_dist(i, j)).fold(f64::INFINITY, f64::min);
+        // Fewer than two elements leave no pair: z is then held at 0 like `calculate_min_distance`
+        let (lower, upper) = if data.k < 2 || data.n < 2 { (0.0, 0.0) } else { (min_dist, max_dist) };
+
+        let z = model.add_variable("z".to_string(), VarKind::Continuous, lower, upper);
+        model.objective.push((z, 1.0));
+        if data.k < 2 {
+            return model;
+        }
+        for (i, j) in pairs() {
+            // z + M x_i + M x_j <= d_ij + 2M; pairs at the largest distance are covered by z's bound
+            let dist = data.get_dist(i, j);
+            let big_m = max_dist - dist;
+            if big_m > 0.0 {
+                let terms = vec![(z, 1.0), (i, big_m), (j, big_m)];
+                model.add_constraint(format!("min{}_{}", i, j), terms, Sense::LessEqual, dist + 2.0 * big_m);
+            }
+        }
+        model
+    }
+
+    /// Max-MinSum: maximize t subject to `t <= s_i + M_i (1 - x_i)` for every i,
+    /// where `s_i = sum_{j != i} d_ij x_j` is i's sum over the selection.
+    pub fn max_min_sum(data: &MdpData) -> Self {
+        let mut model = MipModel::with_selection("MDP_MaxMinSum".to_string(), data, (Sense::Equal, data.k as f64));
+        let (smallest, _) = model.build_sum_range(data, false);
+        model.objective.push((smallest, 1.0));
+        model
+    }
+
+    /// Min-Diff: maximize `t_min - t_max` subject to
+    /// `t_min <= s_i + M_i (1 - x_i)` and `t_max >= s_i - M'_i (1 - x_i)` for every i.
+    pub fn min_diff(data: &MdpData) -> Self {
+        let mut model = MipModel::with_selection("MDP_MinDiff".to_string(), data, (Sense::Equal, data.k as f64));
+        let (smallest, largest) = model.build_sum_range(data, true);
+        model.objective.push((smallest, 1.0));
+        model.objective.extend(largest.map(|largest| (largest, -1.0)));
+        model
+    }
+
+    /// Epsilon-constraint model of the bi-objective MDP: the quadratic max-sum
+    /// objective over selections whose smallest distance is at least `epsilon`,
+    /// with `x_i + x_j <= 1` for every pair closer than that.
+    pub fn min_separated(data: &MdpData, epsilon: f64) -> Self {
+        let size = (Sense::Equal, data.k as f64);
+        let mut model = MipModel::with_selection("MDP_MinSeparated".to_string(), data, size);
+        model.build_quadratic(data);
+        for i in 0..data.n {
+            for j in (i + 1)..data.n {
+                if data.get_dist(i, j) < epsilon {
+                    let terms = vec![(i, 1.0), (j, 1.0)];
+                    model.add_constraint(format!("sep{}_{}", i, j), terms, Sense::LessEqual, 1.0);
+                }
+            }
+        }
+        model
+    }
+
+    /// Capacitated MDP: the quadratic objective over selections of
+    /// `min_size..=max_size` elements with `sum w_i x_i <= C`.
+    pub fn capacitated(data: &MdpData, spec: &CapacitySpec) -> Self {
+        let mut model =
+            MipModel::with_selection("MDP_Capacitated".to_string(), data, (Sense::GreaterEqual, spec.min_size as f64));
+        if spec.max_size < data.n {
+            let all: Vec<(usize, f64)> = (0..data.n).map(|i| (i, 1.0)).collect();
+            model.add_constraint("max_cardinality", all, Sense::LessEqual, spec.max_size as f64);
+        }
+        if spec.capacity.is_finite() {
+            let weights: Vec<(usize, f64)> = spec.weights.iter().enumerate().map(|(i, &w)| (i, w)).collect();
+            model.add_constraint("capacity", weights, Sense::LessEqual, spec.capacity);
+        }
+        model.build_quadratic(data);
+        model
+    }
+
+    // Variables bounding the per-element sums s_i of the selected elements from
+    // below (t_min) and, if `with_largest`, from above (t_max). A selected
+    // element's sum covers k - 1 others and an unselected one's covers k, so
+    // the big-Ms come from the sums of the k - 1 and k smallest / largest
+    // distances in each row
+    fn build_sum_range(&mut self, data: &MdpData, with_largest: bool) -> (usize, Option<usize>) {
+        let n = data.n;
+        let k = data.k.min(n);
+        let rows: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                let mut row: Vec<f64> = (0..n).filter(|&j| j != i).map(|j| data.get_dist(i, j)).collect();
+                row.sort_by(|a, b| a.total_cmp(b));
+                row
+            })
+            .collect();
+        let smallest_of = |row: &[f64], count: usize| -> f64 { row[..count.min(row.len())].iter().sum() };
+        let largest_of = |row: &[f64], count: usize| -> f64 { row[row.len() - count.min(row.len())..].iter().sum() };
+        // Every selected element's sum lies in [lower, upper]; with fewer than
+        // two selected elements all sums are 0
+        let (lower, upper) = if k < 2 {
+            (0.0, 0.0)
+        } else {
+            let lower = rows.iter().map(|row| smallest_of(row, k - 1)).fold(f64::INFINITY, f64::min);
+            let upper = rows.iter().map(|row| largest_of(row, k - 1)).fold(f64::NEG_INFINITY, f64::max);
+            (lower, upper)
+        };
+
+        let smallest = self.add_variable("t_min".to_string(), VarKind::Continuous, lower, upper);
+        let largest = with_largest.then(|| self.add_variable("t_max".to_string(), VarKind::Continuous, lower, upper));
+        if k < 2 {
+            return (smallest, largest);
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let sum: Vec<(usize, f64)> = (0..n).filter(|&j| j != i).map(|j| (j, data.get_dist(i, j))).collect();
+            // t_min - sum_j d_ij x_j + M x_i <= M, with M large enough once i is unselected
+            let big_m = (upper - smallest_of(row, k)).max(0.0);
+            let mut terms = vec![(smallest, 1.0)];
+            terms.extend(sum.iter().map(|&(j, d)| (j, -d)));
+            terms.push((i, big_m));
+            self.add_constraint(format!("min_sum{}", i), terms, Sense::LessEqual, big_m);
+            if let Some(largest) = largest {
+                // t_max - sum_j d_ij x_j - M x_i >= -M
+                let big_m = (largest_of(row, k) - lower).max(0.0);
+                let mut terms = vec![(largest, 1.0)];
+                terms.extend(sum.iter().map(|&(j, d)| (j, -d)));
+                terms.push((i, -big_m));
+                self.add_constraint(format!("max_sum{}", i), terms, Sense::GreaterEqual, -big_m);
+            }
+        }
+        (smallest, largest)
+    }
+
+    // Selection variables x_0..x_{n-1} and the cardinality constraint on sum x_i,
+    // plus the side constraints: included and excluded elements fix the bounds
+    // of their x_i, and every category quota adds `min <= sum_{i in c} x_i <= max`
+    fn with_selection(name: String, data: &MdpData, (sense, size): (Sense, f64)) -> Self {
+        let mut model = MipModel {
+            name,
+            variables: Vec::new(),
+            objective: Vec::new(),
+            quadratic_objective: Vec::new(),
+            constraints: Vec::new(),
+        };
+        for i in 0..data.n {
+            model.add_variable(format!("x{}", i), VarKind::Binary, 0.0, 1.0);
+        }
+        let all: Vec<(usize, f64)> = (0..data.n).map(|i| (i, 1.0)).collect();
+        model.add_constraint("cardinality", all, sense, size);
+        if let Some(spec) = &data.constraints {
+            model.add_side_constraints(spec);
+        }
+        model
+    }
+
+    fn add_side_constraints(&mut self, spec: &SideConstraints) {
+        for (i, variable) in self.variables.iter_mut().enumerate().take(spec.required.len()) {
+            if spec.required[i] {
+                variable.lower = 1.0;
+            }
+            if spec.forbidden[i] {
+                variable.upper = 0.0;
+            }
+        }
+        for (c, members) in spec.members().into_iter().enumerate() {
+            let (min, max) = spec.quotas[c];
+            let size = members.len();
+            let terms: Vec<(usize, f64)> = members.into_iter().map(|i| (i, 1.0)).collect();
+            if min > 0 {
+                self.add_constraint(format!("quota_min{}", c), terms.clone(), Sense::GreaterEqual, min as f64);
+            }
+            if max < size {
+                self.add_constraint(format!("quota_max{}", c), terms, Sense::LessEqual, max as f64);
+            }
+        }
+    }
+
+    pub fn add_variable(&mut self, name: String, kind: VarKind, lower: f64, upper: f64) -> usize {
+        self.variables.push(Variable { name, kind, lower, upper });
+        self.variables.len() - 1
+    }
+
+    pub fn add_constraint(&mut self, name: impl Into<String>, terms: Vec<(usize, f64)>, sense: Sense, rhs: f64) {
+        self.constraints.push(Constraint { name: name.into(), terms, sense, rhs });
+    }
+
+    // max sum_{i<j} d_ij x_i x_j
+    fn build_quadratic(&mut self, data: &MdpData) {
+        for i in 0..data.n {
+            for j in (i + 1)..data.n {
+                let dist = data.get_dist(i, j);
+                if dist != 0.0 {
+                    self.quadratic_objective.push((i, j, dist));
+                }
+            }
+        }
+    }
+
+    // max 1/2 sum_i w_i
+    // s.t. w_i <= U_i x_i
+    //      w_i <= sum_{j != i} d_ij x_j - L_i (1 - x_i)
+    // where U_i is the sum of the k-1 largest distances from i and L_i bounds
+    // sum_j d_ij x_j from below whether or not i is selected: the sum of the k-1
+    // smallest distances, plus the k-th when it is negative, as the k elements
+    // selected besides an unselected i may add it. So w_i equals
+    // x_i * sum_j d_ij x_j at every integer point, for signed distances too
+    fn build_glover(&mut self, data: &MdpData) {
+        let n = data.n;
+        let others = data.k.saturating_sub(1);
+
+        for i in 0..n {
+            let mut row: Vec<f64> = (0..n).filter(|&j| j != i).map(|j| data.get_dist(i, j)).collect();
+            row.sort_by(|a, b| a.total_cmp(b));
+            let lower: f64 = row[..others].iter().sum::<f64>() + row.get(others).map_or(0.0, |d| d.min(0.0));
+            let upper: f64 = row[row.len() - others..].iter().sum();
+
+            let w = self.add_variable(format!("w{}", i), VarKind::Continuous, lower.min(0.0), upper.max(0.0));
+            self.objective.push((w, 0.5));
+
+            self.add_constraint(format!("w_active{}", i), vec![(w, 1.0), (i, -upper)], Sense::LessEqual, 0.0);
+            // w_i - sum_j d_ij x_j - L_i x_i <= -L_i
+            let mut terms = vec![(w, 1.0)];
+            terms.extend((0..n).filter(|&j| j != i).map(|j| (j, -data.get_dist(i, j))));
+            terms.push((i, -lower));
+            self.add_constraint(format!("w_sum{}", i), terms, Sense::LessEqual, -lower);
+        }
+    }
+
+    // max sum_{i<j} d_ij y_ij
+    // s.t. y_ij <= x_i, y_ij <= x_j, x_i + x_j - y_ij <= 1, 0 <= y_ij <= 1
+    fn build_kuo_glover_dhir(&mut self, data: &MdpData) {
+        for i in 0..data.n {
+            for j in (i + 1)..data.n {
+                let dist = data.get_dist(i, j);
+                if dist == 0.0 {
+                    continue;
+                }
+                let y = self.add_variable(format!("y{}_{}", i, j), VarKind::Continuous, 0.0, 1.0);
+                self.objective.push((y, dist));
+                self.add_constraint(format!("y_le_x{}_{}", i, j), vec![(y, 1.0), (i, -1.0)], Sense::LessEqual, 0.0);
+                self.add_constraint(format!("y_le_x{}_{}", j, i), vec![(y, 1.0), (j, -1.0)], Sense::LessEqual, 0.0);
+                // Only binds for negative distances, where the objective would push y down
+                if dist < 0.0 {
+                    let terms = vec![(i, 1.0), (j, 1.0), (y, -1.0)];
+                    self.add_constraint(format!("y_ge{}_{}", i, j), terms, Sense::LessEqual, 1.0);
+                }
+            }
+        }
+    }
+
+    /// Writes the model in CPLEX LP format, as read by Gurobi, CPLEX, HiGHS and SCIP.
+    pub fn write_lp(&self, out: impl Write) -> io::Result<()> {
+        let mut out = BufWriter::new(out);
+        writeln!(out, "\\ {}", self.name)?;
+        writeln!(out, "Maximize")?;
+        write!(out, " obj:")?;
+        let mut terms: Vec<String> = self.objective.iter().map(|&(v, c)| self.lp_term(c, v)).collect();
+        if !self.quadratic_objective.is_empty() {
+            // LP files state quadratic objectives as [ ... ] / 2
+            terms.push("+ [".to_string());
+            terms.extend(self.quadratic_objective.iter().map(|&(a, b, c)| {
+                format!("{} {} * {}", signed(2.0 * c), self.variables[a].name, self.variables[b].name)
+            }));
+            terms.push("] / 2".to_string());
+        }
+        write_lp_terms(&mut out, &terms)?;
+
+        writeln!(out, "Subject To")?;
+        for constraint in &self.constraints {
+            write!(out, " {}:", constraint.name)?;
+            let mut terms: Vec<String> = constraint.terms.iter().map(|&(v, c)| self.lp_term(c, v)).collect();
+            let sense = match constraint.sense {
+                Sense::LessEqual => "<=",
+                Sense::Equal => "=",
+                Sense::GreaterEqual => ">=",
+            };
+            terms.push(format!("{} {}", sense, constraint.rhs));
+            write_lp_terms(&mut out, &terms)?;
+        }
+
+        writeln!(out, "Bounds")?;
+        // Binaries only need bounds when fixed by an inclusion or exclusion
+        for variable in self.variables.iter().filter(|v| v.kind == VarKind::Continuous || v.is_fixed()) {
+            writeln!(out, " {} <= {} <= {}", variable.lower, variable.name, variable.upper)?;
+        }
+        writeln!(out, "Binaries")?;
+        let binaries: Vec<String> = self
+            .variables
+            .iter()
+            .filter(|v| v.kind == VarKind::Binary)
+            .map(|v| v.name.clone())
+            .collect();
+        for chunk in binaries.chunks(LP_TERMS_PER_LINE * 2) {
+            writeln!(out, " {}", chunk.join(" "))?;
+        }
+        writeln!(out, "End")?;
+        out.flush()
+    }
+
+    /// Writes the model in free MPS format, with a QUADOBJ section for the quadratic objective.
+    pub fn write_mps(&self, out: impl Write) -> io::Result<()> {
+        let mut out = BufWriter::new(out);
+        writeln!(out, "NAME {}", self.name)?;
+        writeln!(out, "OBJSENSE")?;
+        writeln!(out, "    MAX")?;
+
+        writeln!(out, "ROWS")?;
+        writeln!(out, " N obj")?;
+        for constraint in &self.constraints {
+            let sense = match constraint.sense {
+                Sense::LessEqual => "L",
+                Sense::Equal => "E",
+                Sense::GreaterEqual => "G",
+            };
+            writeln!(out, " {} {}", sense, constraint.name)?;
+        }
+
+        // MPS is column-major: gather every coefficient by variable
+        let mut columns: Vec<Vec<(&str, f64)>> = vec![Vec::new(); self.variables.len()];
+        for &(v, c) in &self.objective {
+            columns[v].push(("obj", c));
+        }
+        for constraint in &self.constraints {
+            for &(v, c) in &constraint.terms {
+                columns[v].push((&constraint.name, c));
+            }
+        }
+        writeln!(out, "COLUMNS")?;
+        for (variable, entries) in self.variables.iter().zip(&columns) {
+            for (row, c) in entries {
+                writeln!(out, "    {} {} {}", variable.name, row, c)?;
+            }
+        }
+
+        writeln!(out, "RHS")?;
+        for constraint in self.constraints.iter().filter(|c| c.rhs != 0.0) {
+            writeln!(out, "    RHS {} {}", constraint.name, constraint.rhs)?;
+        }
+
+        writeln!(out, "BOUNDS")?;
+        for variable in &self.variables {
+            match variable.kind {
+                VarKind::Binary if variable.is_fixed() => {
+                    writeln!(out, " FX BND {} {}", variable.name, variable.lower)?
+                }
+                VarKind::Binary => writeln!(out, " BV BND {}", variable.name)?,
+                VarKind::Continuous => {
+                    writeln!(out, " LO BND {} {}", variable.name, variable.lower)?;
+                    writeln!(out, " UP BND {} {}", variable.name, variable.upper)?;
+                }
+            }
+        }
+
+        if !self.quadratic_objective.is_empty() {
+            // QUADOBJ holds each off-diagonal entry of Q once, for the objective 1/2 x'Qx
+            writeln!(out, "QUADOBJ")?;
+            for &(a, b, c) in &self.quadratic_objective {
+                writeln!(out, "    {} {} {}", self.variables[a].name, self.variables[b].name, c)?;
+            }
+        }
+        writeln!(out, "ENDATA")?;
+        out.flush()
+    }
+
+    fn lp_term(&self, coefficient: f64, variable: usize) -> String {
+        format!("{} {}", signed(coefficient), self.variables[variable].name)
+    }
+}
+
+// Coefficient with an explicit sign, as LP expressions need between terms
+fn signed(value: f64) -> String {
+    if value < 0.0 {
+        format!("- {}", -value)
+    } else {
+        format!("+ {}", value)
+    }
+}
+
+fn write_lp_terms(out: &mut impl Write, terms: &[String]) -> io::Result<()> {
+    for (idx, chunk) in terms.chunks(LP_TERMS_PER_LINE).enumerate() {
+        let indent = if idx == 0 { " " } else { "   " };
+        writeln!(out, "{}{}", indent, chunk.join(" "))?;
+    }
+    if terms.is_empty() {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{calculate_diversity, calculate_min_distance, calculate_sum_range, check_selection};
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Optimal objective of `model` with the selection variables fixed to
+    // `selected`, or `None` if that violates a bound or a row. Every auxiliary
+    // variable only shares rows with x, so each is set on its own
+    fn model_value(model: &MipModel, n: usize, selected: &[usize]) -> Option<f64> {
+        let mut x = vec![0.0; n];
+        for &i in selected {
+            x[i] = 1.0;
+        }
+        if model.variables[..n].iter().zip(&x).any(|(variable, &x)| x < variable.lower || x > variable.upper) {
+            return None;
+        }
+        let holds = |lhs: f64, constraint: &Constraint| match constraint.sense {
+            Sense::LessEqual => lhs <= constraint.rhs + 1e-9,
+            Sense::Equal => (lhs - constraint.rhs).abs() <= 1e-9,
+            Sense::GreaterEqual => lhs >= constraint.rhs - 1e-9,
+        };
+        for constraint in model.constraints.iter().filter(|c| c.terms.iter().all(|&(t, _)| t < n)) {
+            if !holds(constraint.terms.iter().map(|&(t, c)| c * x[t]).sum(), constraint) {
+                return None;
+            }
+        }
+
+        let mut value: f64 = model.objective.iter().filter(|&&(v, _)| v < n).map(|&(v, c)| c * x[v]).sum();
+        value += model.quadratic_objective.iter().map(|&(a, b, c)| c * x[a] * x[b]).sum::<f64>();
+        for (v, variable) in model.variables.iter().enumerate().skip(n) {
+            let (mut lower, mut upper) = (variable.lower, variable.upper);
+            for constraint in model.constraints.iter().filter(|c| c.terms.iter().any(|&(t, _)| t == v)) {
+                let coef: f64 = constraint.terms.iter().filter(|&&(t, _)| t == v).map(|&(_, c)| c).sum();
+                let rest: f64 = constraint.terms.iter().filter(|&&(t, _)| t != v).map(|&(t, c)| c * x[t]).sum();
+                let bound = (constraint.rhs - rest) / coef;
+                match (constraint.sense, coef > 0.0) {
+                    (Sense::LessEqual, true) | (Sense::GreaterEqual, false) => upper = upper.min(bound),
+                    (Sense::LessEqual, false) | (Sense::GreaterEqual, true) => lower = lower.max(bound),
+                    (Sense::Equal, _) => (lower, upper) = (lower.max(bound), upper.min(bound)),
+                }
+            }
+            if lower > upper + 1e-9 {
+                return None;
+            }
+            let coef: f64 = model.objective.iter().filter(|&&(t, _)| t == v).map(|&(_, c)| c).sum();
+            value += coef * if coef >= 0.0 { upper } else { lower };
+        }
+        Some(value)
+    }
+
+    // Checks `model` against `expected` (None = infeasible) on every subset of 0..n
+    fn assert_matches_every_selection(model: &MipModel, data: &MdpData, expected: impl Fn(&[usize]) -> Option<f64>) {
+        for mask in 0u32..1 << data.n {
+            let selected: Vec<usize> = (0..data.n).filter(|&i| mask & (1 << i) != 0).collect();
+            let (value, expected) = (model_value(model, data.n, &selected), expected(&selected));
+            let matches = match (value, expected) {
+                (Some(value), Some(expected)) => (value - expected).abs() < 1e-6,
+                (value, expected) => value.is_none() && expected.is_none(),
+            };
+            assert!(matches, "{} k={} {:?}: {:?} != {:?}", model.name, data.k, selected, value, expected);
+        }
+    }
+
+    fn instances(kinds: &[InstanceKind], rng: &mut StdRng) -> Vec<MdpData> {
+        let mut instances = Vec::new();
+        for &kind in kinds {
+            for k in 1..=4 {
+                instances.push(generate_instance(&GeneratorConfig { n: 8, k, kind, dims: 2 }, rng));
+            }
+        }
+        instances
+    }
+
+    #[test]
+    fn linearizations_match_the_diversity_of_every_selection() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for data in instances(&[InstanceKind::Integer, InstanceKind::Signed], &mut rng) {
+            for formulation in MipFormulation::ALL {
+                let model = MipModel::mdp(&data, formulation);
+                assert_matches_every_selection(&model, &data, |selected| {
+                    (selected.len() == data.k).then(|| calculate_diversity(selected, &data))
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn max_mean_subproblem_is_the_sum_less_lambda_per_element() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for data in instances(&[InstanceKind::Signed], &mut rng) {
+            for lambda in [0.0, 1.5, -2.0, rng.gen_range(0.0..50.0)] {
+                let model = MipModel::max_mean(&data, lambda);
+                assert_matches_every_selection(&model, &data, |selected| {
+                    let size = selected.len();
+                    (size >= MAX_MEAN_MIN_SIZE).then(|| calculate_diversity(selected, &data) - lambda * size as f64)
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn max_min_model_matches_the_smallest_distance_of_every_selection() {
+        let mut rng = StdRng::seed_from_u64(6);
+        for data in instances(&[InstanceKind::Integer, InstanceKind::Signed], &mut rng) {
+            let model = MipModel::max_min(&data);
+            assert_matches_every_selection(&model, &data, |selected| {
+                (selected.len() == data.k).then(|| calculate_min_distance(selected, &data))
+            });
+        }
+    }
+
+    #[test]
+    fn sum_range_models_match_the_per_element_sums_of_every_selection() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for data in instances(&[InstanceKind::Integer, InstanceKind::Signed], &mut rng) {
+            let sums = |selected: &[usize]| (selected.len() == data.k).then(|| calculate_sum_range(selected, &data));
+            assert_matches_every_selection(&MipModel::max_min_sum(&data), &data, |selected| {
+                sums(selected).map(|(min, _)| min)
+            });
+            // Min-Diff maximizes the negated range
+            assert_matches_every_selection(&MipModel::min_diff(&data), &data, |selected| {
+                sums(selected).map(|(min, max)| min - max)
+            });
+        }
+    }
+
+    #[test]
+    fn min_separated_model_keeps_exactly_the_selections_at_least_epsilon_apart() {
+        let mut rng = StdRng::seed_from_u64(8);
+        for data in instances(&[InstanceKind::Integer, InstanceKind::Signed], &mut rng) {
+            let pairs = (0..data.n).flat_map(|i| ((i + 1)..data.n).map(move |j| (i, j)));
+            let mut distances: Vec<f64> = pairs.map(|(i, j)| data.get_dist(i, j)).collect();
+            distances.sort_by(f64::total_cmp);
+            // The smallest, the largest and two inner distances, so selections exactly
+            // epsilon apart are covered, and a value between two of them
+            let levels = [0, 1, 2, 3].map(|t| distances[t * (distances.len() - 1) / 3]);
+            for epsilon in levels.into_iter().chain([0.5 * (levels[1] + levels[2])]) {
+                let model = MipModel::min_separated(&data, epsilon);
+                assert_matches_every_selection(&model, &data, |selected| {
+                    // A selection of one element has no pair to separate
+                    let separated = selected.len() < 2 || calculate_min_distance(selected, &data) >= epsilon;
+                    (selected.len() == data.k && separated).then(|| calculate_diversity(selected, &data))
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn capacitated_model_keeps_exactly_the_feasible_selections() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for (sizes, fraction) in [((0, 8), 0.3), ((2, 5), 0.5), ((3, 3), 1.0)] {
+            let config = GeneratorConfig { n: 8, k: 3, kind: InstanceKind::Signed, dims: 2 };
+            let mut data = generate_instance(&config, &mut rng);
+            let weights: Vec<f64> = (0..data.n).map(|_| rng.gen_range(1..=10) as f64).collect();
+            let capacity = (fraction * weights.iter().sum::<f64>()).floor();
+            let spec = CapacitySpec { weights, capacity, min_size: sizes.0, max_size: sizes.1 };
+            data.capacity = Some(spec.clone());
+            let model = MipModel::capacitated(&data, &spec);
+            assert_matches_every_selection(&model, &data, |selected| {
+                check_selection(selected, &data).is_empty().then(|| calculate_diversity(selected, &data))
+            });
+        }
+    }
+}
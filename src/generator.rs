@@ -0,0 +1,72 @@
+//! Random instances resembling the MDPLIB families.
+
+use crate::parser::MdpData;
+use rand::Rng;
+
+/// Distance models of the MDPLIB families.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstanceKind {
+    Euclidean, // GKD: Euclidean distances between random points in [0, 10]^dims
+    Uniform,   // MDG-a: real distances drawn uniformly from [0, 10]
+    Integer,   // SOM: integer distances drawn uniformly from 0..=9
+    Signed,    // Max-mean type I: real distances drawn uniformly from [-10, 10]
+}
+
+/// Size and distance model of a generated instance.
+pub struct GeneratorConfig {
+    pub n: usize,
+    pub k: usize,
+    pub kind: InstanceKind,
+    pub dims: usize, // Only used by Euclidean instances
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            n: 100,
+            k: 10,
+            kind: InstanceKind::Euclidean,
+            dims: 2,
+        }
+    }
+}
+
+/// Draws a random instance; the same `rng` state always yields the same instance.
+pub fn generate_instance(config: &GeneratorConfig, rng: &mut impl Rng) -> MdpData {
+    let n = config.n;
+    let mut distances = vec![0.0; n * n];
+
+    let points: Vec<Vec<f64>> = match config.kind {
+        InstanceKind::Euclidean => (0..n)
+            .map(|_| (0..config.dims).map(|_| rng.gen_range(0.0..10.0)).collect())
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d = match config.kind {
+                InstanceKind::Euclidean => points[i]
+                    .iter()
+                    .zip(&points[j])
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum::<f64>()
+                    .sqrt(),
+                InstanceKind::Uniform => rng.gen_range(0.0..10.0),
+                InstanceKind::Integer => rng.gen_range(0..10) as f64,
+                InstanceKind::Signed => rng.gen_range(-10.0..=10.0),
+            };
+            distances[i * n + j] = d;
+            distances[j * n + i] = d;
+        }
+    }
+
+    MdpData {
+        n,
+        k: config.k,
+        distances,
+        groups: None,
+        capacity: None,
+        constraints: None,
+    }
+}
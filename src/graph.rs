@@ -0,0 +1,185 @@
+//! Weighted undirected graphs for MaxCut, read from edge lists.
+
+use crate::parser::{parse_token, MdpData, ParseError};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// How the vertex indices of an edge list are numbered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexBase {
+    Zero,
+    One,
+}
+
+/// A weighted undirected graph on vertices `0..n`, stored as edge and adjacency lists.
+pub struct Graph {
+    pub n: usize,
+    pub edges: Vec<(usize, usize, f64)>, // i < j, one entry per edge
+    adjacency: Vec<Vec<(usize, f64)>>,
+}
+
+impl Graph {
+    /// Builds a graph from `(i, j, w)` edges; self-loops never cross a cut and are dropped.
+    pub fn new(n: usize, edges: impl IntoIterator<Item = (usize, usize, f64)>) -> Self {
+        let mut adjacency = vec![Vec::new(); n];
+        let mut kept = Vec::new();
+        for (i, j, w) in edges {
+            if i == j {
+                continue;
+            }
+            let (i, j) = if i < j { (i, j) } else { (j, i) };
+            adjacency[i].push((j, w));
+            adjacency[j].push((i, w));
+            kept.push((i, j, w));
+        }
+        Graph { n, edges: kept, adjacency }
+    }
+
+    /// The complete graph of an MDP instance, with the nonzero distances as weights.
+    pub fn from_mdp(data: &MdpData) -> Self {
+        let edges = (0..data.n).flat_map(|i| ((i + 1)..data.n).map(move |j| (i, j, data.get_dist(i, j))));
+        Graph::new(data.n, edges.filter(|&(_, _, w)| w != 0.0))
+    }
+
+    /// Reads an edge list from a file; see [`Graph::read_from`].
+    pub fn load(path: &str, base: Option<IndexBase>) -> Result<Self, ParseError> {
+        Graph::read_from(BufReader::new(File::open(path)?), base)
+    }
+
+    /// Reads an edge list: an `n m` header, then one `i j [w]` line per edge
+    /// (weight 1 when omitted), so MDPLIB instances load as complete graphs.
+    /// Without an explicit `base`, indices are 0-based if a 0 appears and
+    /// 1-based (as in the G-set and rudy files) if `n` or more appears; a file
+    /// using only `1..n-1` could be either and is rejected.
+    pub fn read_from(reader: impl BufRead, base: Option<IndexBase>) -> Result<Self, ParseError> {
+        let mut lines = reader.lines().enumerate();
+
+        // The edge count of the header is not checked: MDPLIB headers hold k there
+        let n = loop {
+            let (idx, line) = lines.next().ok_or(ParseError::MissingHeader)?;
+            let line = line?;
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() {
+                continue;
+            }
+            if parts.len() < 2 {
+                return Err(ParseError::MissingHeader);
+            }
+            break parse_token(parts[0], idx + 1)?;
+        };
+
+        // (line, i, j, w), indices as written
+        let mut raw: Vec<(usize, usize, usize, f64)> = Vec::new();
+        for (idx, line) in lines {
+            let line_no = idx + 1;
+            let l = line?;
+            let parts: Vec<&str> = l.split_whitespace().collect();
+            match parts.len() {
+                0 => continue,
+                // MDPLIB directives such as `groups` are not part of the graph
+                _ if parts[0].starts_with(|c: char| c.is_ascii_alphabetic()) => continue,
+                1 => return Err(ParseError::BadToken { line: line_no, token: l.trim().to_string() }),
+                _ => {}
+            }
+            let i = parse_token(parts[0], line_no)?;
+            let j = parse_token(parts[1], line_no)?;
+            let w = match parts.get(2) {
+                Some(token) => parse_token(token, line_no)?,
+                None => 1.0,
+            };
+            raw.push((line_no, i, j, w));
+        }
+
+        let base = match base {
+            Some(base) => base,
+            None if raw.iter().any(|&(_, i, j, _)| i == 0 || j == 0) => IndexBase::Zero,
+            None if raw.is_empty() || raw.iter().any(|&(_, i, j, _)| i.max(j) >= n) => IndexBase::One,
+            None => return Err(ParseError::AmbiguousIndexBase { n }),
+        };
+        let offset = match base {
+            IndexBase::Zero => 0,
+            IndexBase::One => 1,
+        };
+        let mut edges = Vec::with_capacity(raw.len());
+        for (line, i, j, w) in raw {
+            let mut shifted = [0; 2];
+            for (slot, index) in shifted.iter_mut().zip([i, j]) {
+                *slot = match index.checked_sub(offset) {
+                    Some(v) if v < n => v,
+                    _ => return Err(ParseError::IndexOutOfRange { line, index, n }),
+                };
+            }
+            edges.push((shifted[0], shifted[1], w));
+        }
+        Ok(Graph::new(n, edges))
+    }
+
+    /// Neighbors of `v` with the weights of the connecting edges.
+    pub fn neighbors(&self, v: usize) -> &[(usize, f64)] {
+        &self.adjacency[v]
+    }
+
+    /// Total weight of the edges between `side` and the other vertices.
+    pub fn cut_value(&self, side: &[bool]) -> f64 {
+        self.edges.iter().filter(|&&(i, j, _)| side[i] != side[j]).map(|&(_, _, w)| w).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, base: Option<IndexBase>) -> Result<Graph, ParseError> {
+        Graph::read_from(text.as_bytes(), base)
+    }
+
+    #[test]
+    fn reads_a_one_based_gset_file() {
+        let graph = parse("4 3\n1 2 1\n2 4 -1\n3 4\n", None).unwrap();
+        assert_eq!(graph.n, 4);
+        assert_eq!(graph.edges, vec![(0, 1, 1.0), (1, 3, -1.0), (2, 3, 1.0)]);
+        assert_eq!(graph.neighbors(3), &[(1, -1.0), (2, 1.0)]);
+    }
+
+    #[test]
+    fn reads_a_zero_based_file() {
+        let graph = parse("4 2\n0 1 2.5\n2 3 1.5\n", None).unwrap();
+        assert_eq!(graph.edges, vec![(0, 1, 2.5), (2, 3, 1.5)]);
+        assert_eq!(graph.cut_value(&[true, false, true, false]), 4.0);
+    }
+
+    #[test]
+    fn reads_an_mdplib_file_as_a_complete_graph() {
+        let graph = parse("3 2\n0 1 4.0\n0 2 0.5\n1 2 3.0\ngroups 1\n", None).unwrap();
+        assert_eq!(graph.n, 3);
+        assert_eq!(graph.edges, vec![(0, 1, 4.0), (0, 2, 0.5), (1, 2, 3.0)]);
+    }
+
+    #[test]
+    fn asks_for_the_base_only_when_the_file_does_not_settle_it() {
+        // Vertex 0 of a 0-based file (or vertex 4 of a 1-based one) is isolated
+        let text = "4 2\n1 2 1\n2 3 1\n";
+        assert!(matches!(parse(text, None), Err(ParseError::AmbiguousIndexBase { n: 4 })));
+        let zero = parse(text, Some(IndexBase::Zero)).unwrap();
+        assert_eq!(zero.edges, vec![(1, 2, 1.0), (2, 3, 1.0)]);
+        let one = parse(text, Some(IndexBase::One)).unwrap();
+        assert_eq!(one.edges, vec![(0, 1, 1.0), (1, 2, 1.0)]);
+        assert_eq!(parse("4 0\n", None).unwrap().edges, vec![]);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(matches!(parse("", None), Err(ParseError::MissingHeader)));
+        assert!(matches!(parse("4\n1 2\n", None), Err(ParseError::MissingHeader)));
+        assert!(matches!(parse("4 1\n1 x\n", None), Err(ParseError::BadToken { line: 2, .. })));
+        assert!(matches!(parse("4 1\n3\n", None), Err(ParseError::BadToken { line: 2, .. })));
+        // A 0 makes the file 0-based, so vertex 4 does not exist
+        let mixed = parse("4 2\n0 1\n1 4\n", None);
+        assert!(matches!(mixed, Err(ParseError::IndexOutOfRange { line: 3, index: 4, n: 4 })));
+        assert!(matches!(parse("4 1\n2 5\n", None), Err(ParseError::IndexOutOfRange { line: 2, index: 5, n: 4 })));
+        let zero_in_one_based = parse("4 1\n0 1\n", Some(IndexBase::One));
+        assert!(matches!(zero_in_one_based, Err(ParseError::IndexOutOfRange { line: 2, index: 0, n: 4 })));
+        let n_in_zero_based = parse("4 1\n1 4\n", Some(IndexBase::Zero));
+        assert!(matches!(n_in_zero_based, Err(ParseError::IndexOutOfRange { line: 2, index: 4, n: 4 })));
+    }
+}
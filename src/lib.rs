@@ -0,0 +1,54 @@
+//! Solvers for the Maximum Diversity Problem (MDP): choose `k` of `n` elements
+//! so that the sum of pairwise distances among the chosen elements is maximal.
+//!
+//! Instances are read in MDPLIB format with [`MdpData::load`]. Every algorithm
+//! implements the [`Solver`] trait and can be created by name from the registry:
+//!
+//! ```no_run
+//! use mdp_project::{create_solver, Budget, MdpData};
+//!
+//! let data = MdpData::load("examples_from_mdp/GKD-b/GKD-b_11_n50_m5.txt").unwrap();
+//! let mut tabu = create_solver("Tabu").unwrap();
+//! tabu.set_param("seed", "1").unwrap();
+//! let outcome = tabu.solve(&data, &Budget::with_time_limit(5.0));
+//! println!("{:.2} {:?}", outcome.diversity, outcome.selected);
+//! ```
+//!
+//! Solvers backed by Gurobi (`solver_qubo`, `solver_mip`) are only built with the `gurobi`
+//! feature; everything else is pure Rust, including the QUBO heuristics of
+//! `solver_qubo_heuristic`.
+//!
+//! `solver_grouping` solves the related Maximally Diverse Grouping Problem on the
+//! same instances, given a `groups` line (see [`MdpData::load`]), and
+//! `solver_maxcut` solves MaxCut on weighted graphs ([`graph::Graph`]).
+//! `solver_capacitated` handles instances with node weights and a capacity
+//! (see [`parser::CapacitySpec`]). `solver_pareto` approximates the Pareto
+//! front of the max-sum and max-min objectives.
+
+pub mod evaluation;
+pub mod formulation;
+pub mod generator;
+pub mod graph;
+pub mod parser;
+pub mod qubo;
+pub mod solver;
+pub mod solver_branch_bound;
+pub mod solver_capacitated;
+pub mod solver_direct;
+pub mod solver_grasp;
+pub mod solver_grouping;
+pub mod solver_local_search;
+pub mod solver_maxcut;
+pub mod solver_pareto;
+pub mod solver_pipeline;
+pub mod solver_population;
+pub mod solver_qubo_heuristic;
+pub mod solver_vns;
+#[cfg(feature = "gurobi")]
+pub mod solver_mip;
+#[cfg(feature = "gurobi")]
+pub mod solver_qubo;
+
+pub use evaluation::{calculate_diversity, SolutionState};
+pub use parser::{GroupSpec, MdpData, ParseError};
+pub use solver::{create_solver, solver_names, Budget, SolveOutcome, Solver};
@@ -1,584 +1,143 @@
+mod cli;
 
-// // TODO (Performance Analysis Enhancements)
-// // Add more metrics beyond just diversity score and time:
-//     // Solution quality gap: Compare heuristic solutions to the optimal/best known solution
-//     // Time-to-target: How long to reach X% of best solution
-//     // Convergence plots: Show solution quality over time/iterations
-//     // Memory usage: Track RAM consumption
-//     // Scalability analysis: Plot time/quality vs. instance size
-//     // Success rate: Run multiple times with different seeds, report % of runs finding optimal
-// // Statistical rigor:
-//     // Run each solver multiple times (10-30 runs) with different random seeds
-//     // Report mean, median, std dev, min, max
-//     // Add confidence intervals
-
-// // TODO!! (Add MaxCut)
-// // It's also NP-hard and has similar algorithmic approaches
-// // You can use the same heuristics (GRASP, Tabu, GA, Local Search)
-// // Gurobi can solve MaxCut via QUBO or ILP formulation
-// // Shows your framework generalizes beyond MDP
-// // ./your_program --problem maxcut --solver grasp --input graph.txt
-
-// // TODO (Instance Analysis) - Characterize why certain solvers work better on certain instances:
-// // Instance features: Size, density, structure
-// // Clustering analysis: Group similar instances
-// // Performance profiles: Which solver is best on which type?
-// // Hardness prediction: Can you predict which instances will be hard?
-
-// // TODO!! (Visualization & Reporting)
-// // Performance profiles (like in optimization papers)
-// // Scatter plots: Quality vs Time for each solver
-// // Heatmaps: Solver performance across instance types
-// // Pareto frontier: Time-quality tradeoffs
-// // Solution visualization: Show selected subset graphically
-
-// // TODO!! (Practical Recommendations Section)
-// // End with guidance like:
-// // "Use Gurobi for instances n < 50 when optimality is critical"
-// // "Use GRASP for instances 50 < n < 500 for best quality/time tradeoff"
-// // "Use First Improvement LS for real-time applications (sub-millisecond)")
-
-
-
-// mod parser;
-// mod solver_qubo;
-// mod solver_grasp;
-// mod solver_local_search;
-// mod solver_population;
-
-// use std::time::{Instant, Duration};
-// use std::fs;
-// use std::path::Path;
-// use std::collections::HashMap;
-// use solver_local_search::{LocalSearchConfig, LocalSearchMethod};
-// use solver_grasp::GraspConfig;
-// use solver_population::GeneticConfig;
-
-// #[derive(Clone)]
-// struct SolverResult {
-//     name: String,
-//     diversity: f64,
-//     time: Duration,
-//     success: bool,
-// }
-
-// struct InstanceResults {
-//     filename: String,
-//     n: usize,
-//     k: usize,
-//     results: Vec<SolverResult>,
-// }
-
-// fn main() -> Result<(), Box<dyn std::error::Error>> {
-//     println!("\n{:=<80}", "");
-//     println!("MAXIMUM DIVERSITY PROBLEM - COMPREHENSIVE SOLVER COMPARISON");
-//     println!("{:=<80}\n", "");
-
-//     // Discover all test files
-//     let base_dir = "examples_from_mdp";
-//     let subdirs = vec!["GKD-a", "GKD-b", "MDG-a", "MDG-b", "MDG-c", "SOM-a", "SOM-b"];
-    
-//     let mut all_results: HashMap<String, Vec<InstanceResults>> = HashMap::new();
-    
-//     for subdir in subdirs {
-//         let dir_path = format!("{}/{}", base_dir, subdir);
-        
-//         if !Path::new(&dir_path).exists() {
-//             println!("Skipping {} (directory not found)", dir_path);
-//             continue;
-//         }
-        
-//         println!("\n{:-<80}", "");
-//         println!("Processing directory: {}", dir_path);
-//         println!("{:-<80}", "");
-        
-//         let files = discover_test_files(&dir_path)?;
-//         println!("Found {} files\n", files.len());
-        
-//         let category = subdir.split('-').next().unwrap().to_string();
-//         all_results.entry(category.clone()).or_insert_with(Vec::new);
-        
-//         for (idx, path) in files.iter().enumerate() {
-//             println!("[{}/{}] Testing: {}", idx + 1, files.len(), path);
-            
-//             match test_single_file(path) {
-//                 Ok(result) => {
-//                     all_results.get_mut(&category).unwrap().push(result);
-//                 }
-//                 Err(_e) => {
-//                     println!("  ERROR: Failed to process file\n");
-//                 }
-//             }
-//         }
-//     }
-    
-//     // Print comprehensive summary
-//     print_comprehensive_summary(&all_results);
-    
-//     Ok(())
-// }
-
-// fn test_single_file(path: &str) -> Result<InstanceResults, Box<dyn std::error::Error>> {
-//     let data = parser::MdpData::load(path);
-//     println!("  Size: n={}, k={}", data.n, data.k);
-    
-//     let filename = Path::new(path)
-//         .file_name()
-//         .and_then(|s| s.to_str())
-//         .unwrap_or(path)
-//         .to_string();
-    
-//     let results = if data.n > 1000 {
-//         println!("  (Large instance - using fast solvers only)");
-//         run_fast_solvers(&data)
-//     } else if data.n > 500 {
-//         println!("  (Medium instance - reduced Gurobi time limit)");
-//         run_medium_solvers(&data)?
-//     } else {
-//         println!("  (Small instance - full solver suite)");
-//         run_all_solvers(&data)?
-//     };
-    
-//     Ok(InstanceResults {
-//         filename,
-//         n: data.n,
-//         k: data.k,
-//         results,
-//     })
-// }
-
-// fn run_all_solvers(data: &parser::MdpData) -> Result<Vec<SolverResult>, Box<dyn std::error::Error>> {
-//     let mut results = Vec::new();
-
-//     // 1. QUBO with time limit
-//     print!("  [1/6] QUBO... ");
-//     let start = Instant::now();
-//     match solver_qubo::solve_with_qubo(data, 1000.0, 300.0) { // 5 min limit
-//         Ok((_, div)) => {
-//             let time = start.elapsed();
-//             println!("✓ {:.2} ({:?})", div, time);
-//             results.push(SolverResult {
-//                 name: "QUBO (Gurobi)".to_string(),
-//                 diversity: div,
-//                 time,
-//                 success: true,
-//             });
-//         }
-//         Err(e) => {
-//             println!("✗ Timeout/Error");
-//             results.push(SolverResult {
-//                 name: "QUBO (Gurobi)".to_string(),
-//                 diversity: 0.0,
-//                 time: start.elapsed(),
-//                 success: false,
-//             });
-//         }
-//     }
-
-//     // 2. GRASP
-//     print!("  [2/6] GRASP... ");
-//     let config = GraspConfig {
-//         iterations: 50,
-//         alpha: 0.3,
-//         local_search_iters: 500,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_grasp::solve_grasp(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "GRASP".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // 3. First Improvement LS
-//     print!("  [3/6] LS: First... ");
-//     let config = LocalSearchConfig {
-//         method: LocalSearchMethod::FirstImprovement,
-//         max_iters: 5000,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_local_search::solve_local_search(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "LS: First".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // 4. Best Improvement LS
-//     print!("  [4/6] LS: Best... ");
-//     let config = LocalSearchConfig {
-//         method: LocalSearchMethod::BestImprovement,
-//         max_iters: 5000,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_local_search::solve_local_search(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "LS: Best".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // 5. Tabu Search
-//     print!("  [5/6] Tabu... ");
-//     let config = LocalSearchConfig {
-//         method: LocalSearchMethod::TabuSearch { tabu_tenure: 10 },
-//         max_iters: 1000,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_local_search::solve_local_search(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "Tabu".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // 6. Genetic Algorithm
-//     print!("  [6/6] GA... ");
-//     let config = GeneticConfig {
-//         population_size: 30,
-//         generations: 50,
-//         crossover_rate: 0.8,
-//         mutation_rate: 0.15,
-//         elite_size: 3,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_population::solve_genetic(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "GA".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     println!();
-//     Ok(results)
-// }
-
-// fn run_medium_solvers(data: &parser::MdpData) -> Result<Vec<SolverResult>, Box<dyn std::error::Error>> {
-//     let mut results = Vec::new();
-
-//     // QUBO with shorter time limit
-//     print!("  [1/4] QUBO... ");
-//     let start = Instant::now();
-//     match solver_qubo::solve_with_qubo(data, 1000.0, 120.0) { // 2 min limit
-//         Ok((_, div)) => {
-//             let time = start.elapsed();
-//             println!("✓ {:.2} ({:?})", div, time);
-//             results.push(SolverResult {
-//                 name: "QUBO (Gurobi)".to_string(),
-//                 diversity: div,
-//                 time,
-//                 success: true,
-//             });
-//         }
-//         Err(_) => {
-//             println!("✗ Timeout");
-//             results.push(SolverResult {
-//                 name: "QUBO (Gurobi)".to_string(),
-//                 diversity: 0.0,
-//                 time: start.elapsed(),
-//                 success: false,
-//             });
-//         }
-//     }
-
-//     // Reduced GRASP
-//     print!("  [2/4] GRASP... ");
-//     let config = GraspConfig {
-//         iterations: 30,
-//         alpha: 0.3,
-//         local_search_iters: 300,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_grasp::solve_grasp(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "GRASP".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // Best Improvement
-//     print!("  [3/4] LS: Best... ");
-//     let config = LocalSearchConfig {
-//         method: LocalSearchMethod::BestImprovement,
-//         max_iters: 2000,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_local_search::solve_local_search(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "LS: Best".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // GA
-//     print!("  [4/4] GA... ");
-//     let config = GeneticConfig {
-//         population_size: 20,
-//         generations: 30,
-//         crossover_rate: 0.8,
-//         mutation_rate: 0.15,
-//         elite_size: 2,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_population::solve_genetic(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "GA".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     println!();
-//     Ok(results)
-// }
-
-// fn run_fast_solvers(data: &parser::MdpData) -> Vec<SolverResult> {
-//     let mut results = Vec::new();
-
-//     // GRASP only
-//     print!("  [1/3] GRASP... ");
-//     let config = GraspConfig {
-//         iterations: 20,
-//         alpha: 0.3,
-//         local_search_iters: 200,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_grasp::solve_grasp(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "GRASP".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // First Improvement
-//     print!("  [2/3] LS: First... ");
-//     let config = LocalSearchConfig {
-//         method: LocalSearchMethod::FirstImprovement,
-//         max_iters: 1000,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_local_search::solve_local_search(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "LS: First".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     // GA
-//     print!("  [3/3] GA... ");
-//     let config = GeneticConfig {
-//         population_size: 15,
-//         generations: 20,
-//         crossover_rate: 0.8,
-//         mutation_rate: 0.15,
-//         elite_size: 2,
-//     };
-//     let start = Instant::now();
-//     let (_, div) = solver_population::solve_genetic(data, &config);
-//     let time = start.elapsed();
-//     println!("✓ {:.2} ({:?})", div, time);
-//     results.push(SolverResult {
-//         name: "GA".to_string(),
-//         diversity: div,
-//         time,
-//         success: true,
-//     });
-
-//     println!();
-//     results
-// }
-
-// fn discover_test_files(dir: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-//     let mut files = Vec::new();
-    
-//     for entry in fs::read_dir(dir)? {
-//         let entry = entry?;
-//         let path = entry.path();
-        
-//         if path.is_file() {
-//             if let Some(ext) = path.extension() {
-//                 if ext == "txt" {
-//                     if let Some(path_str) = path.to_str() {
-//                         files.push(path_str.to_string());
-//                     }
-//                 }
-//             }
-//         }
-//     }
-    
-//     files.sort();
-//     Ok(files)
-// }
-
-// fn print_comprehensive_summary(all_results: &HashMap<String, Vec<InstanceResults>>) {
-//     println!("\n\n{:=<100}", "");
-//     println!("COMPREHENSIVE RESULTS SUMMARY");
-//     println!("{:=<100}\n", "");
-
-//     for category in ["GKD", "MDG", "SOM"] {
-//         if let Some(instances) = all_results.get(category) {
-//             if instances.is_empty() {
-//                 continue;
-//             }
-
-//             println!("\n{:-<100}", "");
-//             println!("{} INSTANCES ({} files)", category, instances.len());
-//             println!("{:-<100}", "");
-            
-//             // Header
-//             println!("{:<30} {:>8} {:>6} | {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
-//                 "File", "n", "k", "QUBO", "GRASP", "LS:First", "LS:Best", "Tabu", "GA");
-//             println!("{:-<100}", "");
-
-//             // Results for each instance
-//             for inst in instances {
-//                 print!("{:<30} {:>8} {:>6} |", 
-//                     truncate_filename(&inst.filename, 30),
-//                     inst.n, 
-//                     inst.k
-//                 );
-                
-//                 // Find diversity for each solver
-//                 let solver_names = ["QUBO (Gurobi)", "GRASP", "LS: First", "LS: Best", "Tabu", "GA"];
-//                 for solver in &solver_names {
-//                     if let Some(result) = inst.results.iter().find(|r| r.name == *solver) {
-//                         if result.success {
-//                             print!(" {:>12.2}", result.diversity);
-//                         } else {
-//                             print!(" {:>12}", "TIMEOUT");
-//                         }
-//                     } else {
-//                         print!(" {:>12}", "-");
-//                     }
-//                 }
-//                 println!();
-//             }
-
-//             // Average times per solver
-//             println!("\n{:<30} {:>8} {:>6} | {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
-//                 "Average Time", "", "", "QUBO", "GRASP", "LS:First", "LS:Best", "Tabu", "GA");
-//             println!("{:-<100}", "");
-            
-//             let solver_names = ["QUBO (Gurobi)", "GRASP", "LS: First", "LS: Best", "Tabu", "GA"];
-//             print!("{:<30} {:>8} {:>6} |", "", "", "");
-            
-//             for solver in &solver_names {
-//                 let times: Vec<Duration> = instances.iter()
-//                     .flat_map(|inst| inst.results.iter())
-//                     .filter(|r| r.name == *solver && r.success)
-//                     .map(|r| r.time)
-//                     .collect();
-                
-//                 if !times.is_empty() {
-//                     let avg_ms = times.iter().map(|t| t.as_millis()).sum::<u128>() / times.len() as u128;
-//                     print!(" {:>10}ms", avg_ms);
-//                 } else {
-//                     print!(" {:>12}", "-");
-//                 }
-//             }
-//             println!("\n");
-//         }
-//     }
-
-//     // Overall statistics
-//     println!("\n{:=<100}", "");
-//     println!("OVERALL STATISTICS");
-//     println!("{:=<100}", "");
-    
-//     let total_instances: usize = all_results.values().map(|v| v.len()).sum();
-//     println!("Total instances tested: {}", total_instances);
-    
-//     // Best solver by category
-//     for category in ["GKD", "MDG", "SOM"] {
-//         if let Some(instances) = all_results.get(category) {
-//             if instances.is_empty() {
-//                 continue;
-//             }
-            
-//             let mut solver_wins: HashMap<String, usize> = HashMap::new();
-            
-//             for inst in instances {
-//                 if let Some(best) = inst.results.iter()
-//                     .filter(|r| r.success)
-//                     .max_by(|a, b| a.diversity.partial_cmp(&b.diversity).unwrap()) 
-//                 {
-//                     *solver_wins.entry(best.name.clone()).or_insert(0) += 1;
-//                 }
-//             }
-            
-//             println!("\n{} - Best solver frequency:", category);
-//             let mut sorted_wins: Vec<_> = solver_wins.iter().collect();
-//             sorted_wins.sort_by(|a, b| b.1.cmp(a.1));
-//             for (solver, wins) in sorted_wins {
-//                 println!("  {:<20} {:>3} wins ({:.1}%)", solver, wins, 
-//                     (*wins as f64 / instances.len() as f64) * 100.0);
-//             }
-//         }
-//     }
-    
-//     println!("\n{:=<100}\n", "");
-// }
-
-// fn truncate_filename(filename: &str, max_len: usize) -> String {
-//     if filename.len() <= max_len {
-//         filename.to_string()
-//     } else {
-//         format!("...{}", &filename[filename.len() - (max_len - 3)..])
-//     }
-// }
-
-
-mod parser;
-mod solver_qubo;
-mod solver_grasp;
-mod solver_local_search;
-mod solver_population;
-
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
-use std::collections::HashMap;
-use solver_local_search::{LocalSearchConfig, LocalSearchMethod};
-use solver_grasp::GraspConfig;
-use solver_population::GeneticConfig;
+use std::process::ExitCode;
+use std::collections::BTreeMap;
+use clap::Parser;
+use cli::{
+    BenchArgs, BudgetArgs, Cli, Command, ExportArgs, ExportFormat, GenerateArgs, GroupArgs, ProblemArg, SolveArgs,
+    ValidateArgs,
+};
+use mdp_project::{
+    evaluation, formulation, generator, graph, parser, qubo, solver, solver_grouping, solver_maxcut, solver_pareto,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use evaluation::{Objective, ParetoPoint};
+use solver::{Budget, Detail, SolveOutcome, Solver};
+use solver_maxcut::MaxCutSolver;
+use solver_pareto::{ParetoOutcome, ParetoSolver};
 use serde::{Serialize, Deserialize};
 
 #[derive(Clone, Serialize, Deserialize)]
 struct SolverResult {
     name: String,
-    diversity: f64,
+    params: BTreeMap<String, String>,
+    diversity: f64, // Pairwise sum of the selection whatever the objective; the cut weight for MaxCut
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    objective: Option<String>, // Objective the solver optimized, unless max-sum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    objective_value: Option<f64>,
+    selected: Vec<usize>,
+    time_ms: u128,
+    success: bool,
+    seed: Option<u64>,
+    #[serde(default)]
+    proven_optimal: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    details: BTreeMap<String, Detail>,
+}
+
+impl SolverResult {
+    fn new(solver: &dyn Solver, data: &parser::MdpData, outcome: SolveOutcome, time: Duration) -> Self {
+        let diversity = |selected: &[usize]| evaluation::calculate_diversity(selected, data);
+        let mut result = SolverResult::from_outcome(solver.name(), solver.params(), outcome, time, diversity);
+        // Not every solver honors capacities and side constraints: a selection breaking them is not a solution
+        if result.success {
+            let objective = result.objective.as_deref().and_then(|name| name.parse().ok()).unwrap_or_default();
+            let violations = evaluation::check_selection_for(&result.selected, data, objective);
+            if let Some(first) = violations.first() {
+                result.success = false;
+                let mut violation = first.to_string();
+                if violations.len() > 1 {
+                    violation += &format!(" (+{} more)", violations.len() - 1);
+                }
+                result.details.insert("violation".to_string(), violation.into());
+            }
+        }
+        result
+    }
+
+    // MaxCut runs report the cut weight as both the diversity and the max-cut value
+    fn for_cut(solver: &MaxCutSolver, outcome: SolveOutcome, time: Duration) -> Self {
+        let cut = outcome.diversity;
+        SolverResult::from_outcome(solver.name(), solver.params(), outcome, time, |_| cut)
+    }
+
+    fn from_outcome(
+        name: &str,
+        params: Vec<(&'static str, String)>,
+        outcome: SolveOutcome,
+        time: Duration,
+        diversity_of: impl FnOnce(&[usize]) -> f64,
+    ) -> Self {
+        let mut details: BTreeMap<String, Detail> =
+            outcome.details.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        // The solver's value moves to the objective column, the diversity column
+        // keeps the pairwise sum
+        let objective = details.remove("objective").map(|objective| objective.to_string());
+        let (diversity, objective_value) = match objective {
+            Some(_) if outcome.success => (diversity_of(&outcome.selected), Some(outcome.diversity)),
+            _ => (outcome.diversity, None),
+        };
+        SolverResult {
+            name: name.to_string(),
+            params: params.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            diversity,
+            objective,
+            objective_value,
+            selected: outcome.selected,
+            time_ms: time.as_millis(),
+            success: outcome.success,
+            seed: outcome.seed,
+            proven_optimal: outcome.proven_optimal,
+            details,
+        }
+    }
+
+    // The value the solver optimized: the objective value, or the diversity for max-sum
+    fn value(&self) -> f64 {
+        self.objective_value.unwrap_or(self.diversity)
+    }
+}
+
+// One front of a Pareto-* solver
+#[derive(Clone, Serialize, Deserialize)]
+struct ParetoResult {
+    name: String,
+    params: BTreeMap<String, String>,
+    front: Vec<ParetoPoint>, // By decreasing sum
     time_ms: u128,
     success: bool,
+    seed: Option<u64>,
+    #[serde(default)]
+    proven_optimal: bool, // The front is exact
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    details: BTreeMap<String, Detail>,
+}
+
+impl ParetoResult {
+    fn new(solver: &ParetoSolver, outcome: ParetoOutcome, time: Duration) -> Self {
+        ParetoResult {
+            name: solver.name().to_string(),
+            params: solver.params().into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            success: !outcome.front.is_empty(),
+            front: outcome.front,
+            time_ms: time.as_millis(),
+            seed: outcome.seed,
+            proven_optimal: outcome.proven_optimal,
+            details: outcome.details.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+        }
+    }
+
+    // "sum a..b, min c..d" over the front; empty fronts are not successes
+    fn ranges(&self) -> String {
+        let (first, last) = (&self.front[0], &self.front[self.front.len() - 1]);
+        format!("sum {:.2}..{:.2}, min {:.2}..{:.2}", last.sum, first.sum, first.min, last.min)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -586,8 +145,12 @@ struct InstanceResults {
     filename: String,
     category: String,
     n: usize,
-    k: usize,
+    k: usize, // 0 for MaxCut graphs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    edges: Option<usize>, // Edge count of a MaxCut graph
     results: Vec<SolverResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pareto: Vec<ParetoResult>, // Fronts of the Pareto-* solvers
 }
 
 #[derive(Serialize, Deserialize)]
@@ -596,44 +159,362 @@ struct ExperimentResults {
     instances: Vec<InstanceResults>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+// Output of `solve --json`
+#[derive(Serialize)]
+struct SolveReport<'a> {
+    instance: &'a str,
+    n: usize,
+    k: usize,
+    #[serde(flatten)]
+    result: SolverResult,
+}
+
+// Output of `solve --problem pareto --json`
+#[derive(Serialize)]
+struct ParetoReport<'a> {
+    instance: &'a str,
+    n: usize,
+    k: usize,
+    #[serde(flatten)]
+    result: ParetoResult,
+}
+
+// Output of `group --json`
+#[derive(Serialize)]
+struct GroupReport<'a> {
+    instance: &'a str,
+    n: usize,
+    solver: &'a str,
+    params: BTreeMap<&'static str, String>,
+    min_size: usize,
+    max_size: usize,
+    value: f64,
+    time_ms: u128,
+    seed: u64,
+    groups: Vec<Vec<usize>>,
+}
+
+// Output of `validate --json`
+#[derive(Serialize)]
+struct ValidationReport<'a> {
+    instance: &'a str,
+    n: usize,
+    k: usize,
+    feasible: bool,
+    diversity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    objective: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    objective_value: Option<f64>,
+    violations: Vec<String>,
+}
+
+// A registered solver plus the parameter overrides used for one benchmark suite
+struct SolverRun<'a> {
+    name: &'a str,
+    params: &'a [(&'a str, &'a str)],
+}
+
+// Small instances: full solver suite
+const SMALL_SUITE: &[SolverRun] = &[
+    SolverRun { name: "QUBO", params: &[("penalty", "adaptive")] },
+    SolverRun { name: "GRASP", params: &[("iterations", "50"), ("local_search_iters", "500")] },
+    SolverRun { name: "LS-First", params: &[("max_iters", "5000")] },
+    SolverRun { name: "LS-Best", params: &[("max_iters", "5000")] },
+    SolverRun { name: "Tabu", params: &[("max_iters", "1000"), ("tabu_tenure", "10")] },
+    SolverRun {
+        name: "GA",
+        params: &[("population_size", "30"), ("generations", "50"), ("mutation_rate", "0.15"), ("elite_size", "3")],
+    },
+    SolverRun { name: "VNS", params: &[("k_max", "10"), ("time_limit", "10")] },
+    SolverRun { name: "SVNS", params: &[("k_max", "10"), ("time_limit", "10"), ("alpha", "0.02")] },
+    // Proves optimality on the GKD-a/GKD-b/SOM-a sizes; the node limit caps the larger ones
+    SolverRun { name: "BnB", params: &[("max_nodes", "100000")] },
+    SolverRun { name: "QUBO-Tabu", params: &[("max_iters", "10000"), ("tabu_tenure", "20")] },
+    SolverRun { name: "QUBO-SA", params: &[("max_iters", "500000")] },
+];
+
+// Medium instances: reduced Gurobi time limit and iteration counts
+const MEDIUM_SUITE: &[SolverRun] = &[
+    SolverRun { name: "QUBO", params: &[("penalty", "adaptive")] },
+    SolverRun { name: "GRASP", params: &[("iterations", "30"), ("local_search_iters", "300")] },
+    SolverRun { name: "LS-Best", params: &[("max_iters", "2000")] },
+    SolverRun { name: "QUBO-Tabu", params: &[("max_iters", "5000"), ("tabu_tenure", "20")] },
+    SolverRun {
+        name: "GA",
+        params: &[("population_size", "20"), ("generations", "30"), ("mutation_rate", "0.15"), ("elite_size", "2")],
+    },
+    SolverRun { name: "VNS", params: &[("k_max", "10"), ("time_limit", "20")] },
+];
+
+// Large instances: fast solvers only
+const LARGE_SUITE: &[SolverRun] = &[
+    SolverRun { name: "GRASP", params: &[("iterations", "20"), ("local_search_iters", "200")] },
+    SolverRun { name: "LS-First", params: &[("max_iters", "1000")] },
+    SolverRun {
+        name: "GA",
+        params: &[("population_size", "15"), ("generations", "20"), ("mutation_rate", "0.15"), ("elite_size", "2")],
+    },
+    SolverRun { name: "VNS", params: &[("k_max", "5"), ("time_limit", "30")] },
+];
+
+// Benchmark settings taken from the command line; `None` falls back to the size-based defaults
+struct BenchOptions<'a> {
+    problem: ProblemArg,
+    suite: Option<Vec<SolverRun<'a>>>,
+    budget: Option<Budget>,
+    seed: Option<u64>,
+    objective: Option<Objective>,
+    index_base: Option<graph::IndexBase>,
+}
+
+// Exit codes: 0 = success, 1 = infeasible/failed result, 2 = usage or input error
+const EXIT_FAILURE: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Bench(BenchArgs {
+        input: "examples_from_mdp".to_string(),
+        solvers: Vec::new(),
+        problem: ProblemArg::Mdp,
+        budget: BudgetArgs { time: None, max_evals: None, max_iters: None, target: None },
+        seed: None,
+        objective: None,
+        index_base: None,
+        output: None,
+    }));
+
+    let result = match command {
+        Command::Solve(args) => run_solve(&args),
+        Command::Bench(args) => run_bench(&args),
+        Command::Validate(args) => run_validate(&args),
+        Command::Generate(args) => run_generate(&args),
+        Command::Export(args) => run_export(&args),
+        Command::Group(args) => run_group(&args),
+        Command::Solvers => run_list_solvers(),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+fn run_solve(args: &SolveArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if args.problem != ProblemArg::Maxcut && args.index_base.is_some() {
+        return Err("--index-base only applies to maxcut".into());
+    }
+    match args.problem {
+        ProblemArg::Mdp => {}
+        ProblemArg::Maxcut => return run_solve_max_cut(args),
+        ProblemArg::Pareto => return run_solve_pareto(args),
+    }
+    let data = parser::MdpData::load(&args.instance)?;
+    let mut solver = create_solver(args.solver.as_deref().unwrap_or("Tabu"))?;
+    for (key, value) in &args.params {
+        solver.set_param(key, value)?;
+    }
+    if let Some(seed) = args.seed {
+        solver.set_param("seed", &seed.to_string())?;
+    }
+    if let Some(objective) = args.objective {
+        solver.set_param("objective", Objective::from(objective).name())?;
+    }
+    let budget = args.budget.to_budget().unwrap_or_default();
+
+    let start = Instant::now();
+    let outcome = solver.solve(&data, &budget);
+    let result = SolverResult::new(solver.as_ref(), &data, outcome, start.elapsed());
+    let success = result.success;
+
+    if args.json {
+        let report = SolveReport { instance: &args.instance, n: data.n, k: data.k, result };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if success {
+        println!("{} on {} (n={}, k={})", result.name, args.instance, data.n, data.k);
+        println!("  Diversity: {:.2}", result.diversity);
+        if let (Some(objective), Some(value)) = (&result.objective, result.objective_value) {
+            println!("  {}: {:.2}", objective, value);
+        }
+        println!("  Time:      {} ms", result.time_ms);
+        if let Some(seed) = result.seed {
+            println!("  Seed:      {}", seed);
+        }
+        if result.proven_optimal {
+            println!("  Optimal:   proven");
+        }
+        for (key, value) in &result.details {
+            println!("  {}: {}", key, value);
+        }
+        let selected: Vec<String> = result.selected.iter().map(|i| i.to_string()).collect();
+        println!("  Selected:  {}", selected.join(" "));
+    } else {
+        println!("{} on {}: ✗ no feasible solution", result.name, args.instance);
+        for (key, value) in &result.details {
+            println!("  {}: {}", key, value);
+        }
+    }
+
+    Ok(exit_code(success))
+}
+
+fn run_solve_max_cut(args: &SolveArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if args.objective.is_some() {
+        return Err("--objective does not apply to maxcut".into());
+    }
+    let graph = graph::Graph::load(&args.instance, args.index_base.map(graph::IndexBase::from))?;
+    let mut solver = create_max_cut_solver(args.solver.as_deref().unwrap_or("Tabu"))?;
+    for (key, value) in &args.params {
+        solver.set_param(key, value)?;
+    }
+    if let Some(seed) = args.seed {
+        solver.set_param("seed", &seed.to_string())?;
+    }
+    let budget = args.budget.to_budget().unwrap_or_default();
+
+    let start = Instant::now();
+    let outcome = solver.solve(&graph, &budget);
+    let result = SolverResult::for_cut(&solver, outcome, start.elapsed());
+    let success = result.success;
+
+    if args.json {
+        let report = SolveReport { instance: &args.instance, n: graph.n, k: 0, result };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if success {
+        println!("{} on {} (n={}, m={})", result.name, args.instance, graph.n, graph.edges.len());
+        println!("  Cut:       {:.2}", result.diversity);
+        println!("  Time:      {} ms", result.time_ms);
+        if let Some(seed) = result.seed {
+            println!("  Seed:      {}", seed);
+        }
+        if result.proven_optimal {
+            println!("  Optimal:   proven");
+        }
+        for (key, value) in &result.details {
+            println!("  {}: {}", key, value);
+        }
+        let side: Vec<String> = result.selected.iter().map(|i| i.to_string()).collect();
+        println!("  Side:      {}", side.join(" "));
+    } else {
+        println!("{} on {}: ✗ no cut found", result.name, args.instance);
+        for (key, value) in &result.details {
+            println!("  {}: {}", key, value);
+        }
+    }
+
+    Ok(exit_code(success))
+}
+
+fn run_solve_pareto(args: &SolveArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if args.objective.is_some() {
+        return Err("--objective does not apply to pareto, which optimizes max-sum and max-min".into());
+    }
+    let data = parser::MdpData::load(&args.instance)?;
+    let mut solver = create_pareto_solver(args.solver.as_deref().unwrap_or("Pareto-NSGA2"))?;
+    for (key, value) in &args.params {
+        solver.set_param(key, value)?;
+    }
+    if let Some(seed) = args.seed {
+        solver.set_param("seed", &seed.to_string())?;
+    }
+    let budget = args.budget.to_budget().unwrap_or_default();
+
+    let start = Instant::now();
+    let outcome = solver.solve(&data, &budget);
+    let result = ParetoResult::new(&solver, outcome, start.elapsed());
+    let success = result.success;
+
+    if args.json {
+        let report = ParetoReport { instance: &args.instance, n: data.n, k: data.k, result };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if success {
+        println!("{} on {} (n={}, k={})", result.name, args.instance, data.n, data.k);
+        println!("  Front:     {} points, {}", result.front.len(), result.ranges());
+        println!("  Time:      {} ms", result.time_ms);
+        if let Some(seed) = result.seed {
+            println!("  Seed:      {}", seed);
+        }
+        if result.proven_optimal {
+            println!("  Optimal:   proven (exact front)");
+        }
+        for (key, value) in &result.details {
+            println!("  {}: {}", key, value);
+        }
+        println!("  {:>12} {:>12}  Selected", "Sum", "Min");
+        for point in &result.front {
+            let selected: Vec<String> = point.selected.iter().map(|i| i.to_string()).collect();
+            println!("  {:>12.2} {:>12.2}  {}", point.sum, point.min, selected.join(" "));
+        }
+    } else {
+        println!("{} on {}: ✗ no feasible solution", result.name, args.instance);
+        for (key, value) in &result.details {
+            println!("  {}: {}", key, value);
+        }
+    }
+
+    Ok(exit_code(success))
+}
+
+fn run_bench(args: &BenchArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if args.problem != ProblemArg::Mdp && args.objective.is_some() {
+        return Err("--objective only applies to the mdp problem".into());
+    }
+    if args.problem != ProblemArg::Maxcut && args.index_base.is_some() {
+        return Err("--index-base only applies to maxcut".into());
+    }
+    let options = BenchOptions {
+        problem: args.problem,
+        suite: if args.solvers.is_empty() {
+            None
+        } else {
+            Some(args.solvers.iter().map(|name| SolverRun { name, params: &[] }).collect())
+        },
+        budget: args.budget.to_budget(),
+        seed: args.seed,
+        objective: args.objective.map(Objective::from),
+        index_base: args.index_base.map(graph::IndexBase::from),
+    };
+    // Reject unknown or unavailable solvers before spending time on the first instance
+    for run in options.suite.iter().flatten() {
+        match options.problem {
+            ProblemArg::Mdp => create_solver(run.name).map(drop)?,
+            ProblemArg::Maxcut => create_max_cut_solver(run.name).map(drop)?,
+            ProblemArg::Pareto => create_pareto_solver(run.name).map(drop)?,
+        }
+    }
+
+    let files = discover_test_files(&args.input)?;
+
     println!("\n{:=<80}", "");
     println!("MAXIMUM DIVERSITY PROBLEM - COMPREHENSIVE SOLVER COMPARISON");
     println!("{:=<80}\n", "");
+    println!("Found {} files in {}\n", files.len(), args.input);
+    if let Some(objective) = options.objective {
+        println!("Objective: {} (values below are {} values)\n", objective, objective);
+    }
+    match options.problem {
+        ProblemArg::Mdp => {}
+        ProblemArg::Maxcut => println!("Problem: MaxCut (values below are cut weights)\n"),
+        ProblemArg::Pareto => println!("Problem: bi-objective max-sum / max-min (Pareto fronts)\n"),
+    }
 
-    // Discover all test files
-    let base_dir = "examples_from_mdp";
-    let subdirs = vec!["GKD-a", "GKD-b", "MDG-a", "MDG-b", "MDG-c", "SOM-a", "SOM-b"];
-    
     let mut all_instances: Vec<InstanceResults> = Vec::new();
-    
-    for subdir in subdirs {
-        let dir_path = format!("{}/{}", base_dir, subdir);
-        
-        if !Path::new(&dir_path).exists() {
-            println!("Skipping {} (directory not found)", dir_path);
-            continue;
-        }
-        
-        println!("\n{:-<80}", "");
-        println!("Processing directory: {}", dir_path);
-        println!("{:-<80}", "");
-        
-        let files = discover_test_files(&dir_path)?;
-        println!("Found {} files\n", files.len());
-        
-        let category = subdir.split('-').next().unwrap().to_string();
-        
-        for (idx, path) in files.iter().enumerate() {
-            println!("[{}/{}] Testing: {}", idx + 1, files.len(), path);
-            
-            match test_single_file(path, &category) {
-                Ok(result) => {
-                    all_instances.push(result);
-                }
-                Err(_e) => {
-                    println!("  ERROR: Failed to process file\n");
-                }
+    let mut failures = 0;
+
+    for (idx, path) in files.iter().enumerate() {
+        println!("[{}/{}] Testing: {}", idx + 1, files.len(), path);
+
+        match test_single_file(path, &instance_category(path), &options) {
+            Ok(result) => {
+                all_instances.push(result);
+            }
+            Err(e) => {
+                failures += 1;
+                println!("  ERROR: {}\n", e);
             }
         }
     }
@@ -645,23 +526,276 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         instances: all_instances,
     };
     
-    let json_file = format!("results_{}.json", timestamp);
+    let json_file = args.output.clone().unwrap_or_else(|| format!("results_{}.json", timestamp));
     save_results_to_json(&results, &json_file)?;
     println!("\n✓ Results saved to: {}", json_file);
     
     // Print comprehensive summary
-    print_comprehensive_summary(&results);
+    if options.problem == ProblemArg::Pareto {
+        print_pareto_summary(&results);
+    } else {
+        print_comprehensive_summary(&results);
+    }
     
     // Generate visualization script
     generate_visualization_script(&json_file)?;
     println!("\n✓ Visualization script saved to: visualize_results.py");
     println!("  Run with: python visualize_results.py {}", json_file);
     
-    Ok(())
+    Ok(exit_code(failures == 0))
 }
 
-fn test_single_file(path: &str, category: &str) -> Result<InstanceResults, Box<dyn std::error::Error>> {
-    let data = parser::MdpData::load(path);
+fn run_validate(args: &ValidateArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let data = parser::MdpData::load(&args.instance)?;
+    let selected = parser::load_solution(&args.solution)?;
+    let objective = args.objective.map(Objective::from);
+    let violations = evaluation::check_selection_for(&selected, &data, objective.unwrap_or_default());
+
+    // Diversity is only defined when every index refers to an element
+    let diversity = selected
+        .iter()
+        .all(|&i| i < data.n)
+        .then(|| evaluation::calculate_diversity(&selected, &data));
+    let objective_value = diversity.and(objective).map(|objective| objective.evaluate(&selected, &data));
+    let feasible = violations.is_empty();
+
+    if args.json {
+        let report = ValidationReport {
+            instance: &args.instance,
+            n: data.n,
+            k: data.k,
+            feasible,
+            diversity,
+            objective: objective.map(Objective::name),
+            objective_value,
+            violations: violations.iter().map(|v| v.to_string()).collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        if feasible {
+            println!("✓ Feasible solution ({} of {} elements)", selected.len(), data.n);
+        } else {
+            println!("✗ Infeasible solution:");
+            for violation in &violations {
+                println!("  - {}", violation);
+            }
+        }
+        if let Some(diversity) = diversity {
+            println!("  Diversity: {:.2}", diversity);
+        }
+        if let (Some(objective), Some(value)) = (objective, objective_value) {
+            println!("  {}: {:.2}", objective, value);
+        }
+    }
+
+    Ok(exit_code(feasible))
+}
+
+fn run_generate(args: &GenerateArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if args.k > args.n {
+        return Err(format!("k = {} exceeds n = {}", args.k, args.n).into());
+    }
+    let config = generator::GeneratorConfig {
+        n: args.n,
+        k: args.k,
+        kind: args.kind.into(),
+        dims: args.dims,
+    };
+    let seed = solver::resolve_seed(args.seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut data = generator::generate_instance(&config, &mut rng);
+    if let Some(groups) = args.groups {
+        if groups == 0 || groups > args.n {
+            return Err(format!("groups = {} must be between 1 and n = {}", groups, args.n).into());
+        }
+        data.groups = Some(parser::GroupSpec::balanced(args.n, groups));
+    }
+    if let Some(fraction) = args.capacity {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(format!("capacity = {} must be a fraction between 0 and 1", fraction).into());
+        }
+        // Drawn after the distances, so the distances match those of the same seed without --capacity
+        let weights: Vec<f64> = (0..args.n).map(|_| rng.gen_range(1..=10) as f64).collect();
+        let capacity = (fraction * weights.iter().sum::<f64>()).floor();
+        data.capacity = Some(parser::CapacitySpec { weights, capacity, min_size: 0, max_size: args.n });
+    }
+
+    match &args.output {
+        Some(path) => {
+            data.save(path)?;
+            println!("✓ Instance written to: {} (n={}, k={}, seed={})", path, data.n, data.k, seed);
+        }
+        None => data.write_to(std::io::stdout().lock())?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_export(args: &ExportArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let data = parser::MdpData::load(&args.instance)?;
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    match args.format.qubo_format() {
+        Some(format) => {
+            let strategy: qubo::PenaltyStrategy = args
+                .penalty
+                .parse()
+                .map_err(|_| format!("invalid penalty '{}'", args.penalty))?;
+            let penalty = strategy
+                .penalty_for(&data)
+                .ok_or("an adaptive penalty needs a solver in the loop; use a number, bound or relative:<factor>")?;
+            qubo::QuboModel::mdp(&data, penalty).write(format, out)?;
+        }
+        None => {
+            let model = formulation::MipModel::mdp(&data, args.formulation.into());
+            match args.format {
+                ExportFormat::Mps => model.write_mps(out)?,
+                _ => model.write_lp(out)?,
+            }
+        }
+    }
+
+    if let Some(path) = &args.output {
+        println!("✓ Model written to: {}", path);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_group(args: &GroupArgs) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let data = parser::MdpData::load(&args.instance)?;
+    // Command-line limits override the instance's `groups` line
+    let base = match (args.groups, data.groups) {
+        (Some(groups), _) => parser::GroupSpec::balanced(data.n, groups),
+        (None, Some(spec)) => spec,
+        (None, None) => return Err("no group count: pass --groups or add a 'groups' line to the instance".into()),
+    };
+    let spec = parser::GroupSpec {
+        min_size: args.min_size.unwrap_or(base.min_size),
+        max_size: args.max_size.unwrap_or(base.max_size),
+        ..base
+    };
+    if !spec.fits(data.n) {
+        return Err(parser::ParseError::InfeasibleGroups { spec, n: data.n }.into());
+    }
+
+    let mut solver = solver_grouping::GroupingSolver::new(solver_grouping::GroupingConfig {
+        method: args.method.into(),
+        ..Default::default()
+    });
+    for (key, value) in &args.params {
+        solver.set_param(key, value)?;
+    }
+    if let Some(seed) = args.seed {
+        solver.set_param("seed", &seed.to_string())?;
+    }
+    let budget = args.budget.to_budget().unwrap_or_default();
+
+    let start = Instant::now();
+    let outcome = solver.solve(&data, &spec, &budget);
+    let time_ms = start.elapsed().as_millis();
+    let groups = outcome.groups(&spec);
+
+    if args.json {
+        let report = GroupReport {
+            instance: &args.instance,
+            n: data.n,
+            solver: solver.name(),
+            params: solver.params().into_iter().collect(),
+            min_size: spec.min_size,
+            max_size: spec.max_size,
+            value: outcome.value,
+            time_ms,
+            seed: outcome.seed,
+            groups,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{} on {} (n={}, {})", solver.name(), args.instance, data.n, spec);
+        println!("  Value:     {:.2}", outcome.value);
+        println!("  Time:      {} ms", time_ms);
+        println!("  Seed:      {}", outcome.seed);
+        for (g, members) in groups.iter().enumerate() {
+            let members: Vec<String> = members.iter().map(|u| u.to_string()).collect();
+            println!("  Group {:>3}: {}", g + 1, members.join(" "));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_list_solvers() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    for name in solver::solver_names() {
+        let solver = create_solver(name)?;
+        let params: Vec<String> = solver.params().iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        println!("{:<12} {}", name, params.join(" "));
+    }
+    println!("\nMaxCut (--problem maxcut):");
+    for name in solver_maxcut::max_cut_solver_names() {
+        let solver = create_max_cut_solver(name)?;
+        let params: Vec<String> = solver.params().iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        println!("{:<12} {}", name, params.join(" "));
+    }
+    println!("\nBi-objective (--problem pareto):");
+    for name in solver_pareto::pareto_solver_names() {
+        let solver = create_pareto_solver(name)?;
+        let params: Vec<String> = solver.params().iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        println!("{:<14} {}", name, params.join(" "));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn create_solver(name: &str) -> Result<Box<dyn Solver>, String> {
+    solver::create_solver(name).ok_or_else(|| {
+        if solver::needs_gurobi_feature(name) {
+            format!("solver '{}' requires Gurobi; rebuild with --features gurobi", name)
+        } else {
+            format!("unknown solver '{}' (available: {})", name, solver::solver_names().join(", "))
+        }
+    })
+}
+
+fn create_max_cut_solver(name: &str) -> Result<MaxCutSolver, String> {
+    solver_maxcut::create_max_cut_solver(name).ok_or_else(|| {
+        if !cfg!(feature = "gurobi") && ["Cut-QUBO", "QUBO"].iter().any(|qubo| qubo.eq_ignore_ascii_case(name)) {
+            format!("solver '{}' requires Gurobi; rebuild with --features gurobi", name)
+        } else {
+            let available = solver_maxcut::max_cut_solver_names().join(", ");
+            format!("unknown MaxCut solver '{}' (available: {})", name, available)
+        }
+    })
+}
+
+fn create_pareto_solver(name: &str) -> Result<ParetoSolver, String> {
+    solver_pareto::create_pareto_solver(name).ok_or_else(|| {
+        if !cfg!(feature = "gurobi") && ["Pareto-Epsilon", "Epsilon"].iter().any(|mip| mip.eq_ignore_ascii_case(name)) {
+            format!("solver '{}' requires Gurobi; rebuild with --features gurobi", name)
+        } else {
+            let available = solver_pareto::pareto_solver_names().join(", ");
+            format!("unknown Pareto solver '{}' (available: {})", name, available)
+        }
+    })
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_FAILURE)
+    }
+}
+
+fn test_single_file(
+    path: &str,
+    category: &str,
+    options: &BenchOptions,
+) -> Result<InstanceResults, Box<dyn std::error::Error>> {
+    match options.problem {
+        ProblemArg::Mdp => {}
+        ProblemArg::Maxcut => return test_max_cut_file(path, category, options),
+        ProblemArg::Pareto => return test_pareto_file(path, category, options),
+    }
+    let data = parser::MdpData::load(path)?;
     println!("  Size: n={}, k={}", data.n, data.k);
     
     let filename = Path::new(path)
@@ -670,309 +804,236 @@ fn test_single_file(path: &str, category: &str) -> Result<InstanceResults, Box<d
         .unwrap_or(path)
         .to_string();
     
-    let results = if data.n > 1000 {
+    let (suite, budget) = if data.n > 1000 {
         println!("  (Large instance - using fast solvers only)");
-        run_fast_solvers(&data)
+        (LARGE_SUITE, Budget::default())
     } else if data.n > 500 {
         println!("  (Medium instance - reduced Gurobi time limit)");
-        run_medium_solvers(&data)?
+        (MEDIUM_SUITE, Budget::with_time_limit(120.0))
     } else {
         println!("  (Small instance - full solver suite)");
-        run_all_solvers(&data)?
+        (SMALL_SUITE, Budget::with_time_limit(300.0))
     };
+    let suite = options.suite.as_deref().unwrap_or(suite);
+    let budget = options.budget.clone().unwrap_or(budget);
+    let results = run_suite(&data, suite, &budget, options.seed, options.objective)?;
     
     Ok(InstanceResults {
         filename,
         category: category.to_string(),
         n: data.n,
         k: data.k,
+        edges: None,
         results,
+        pareto: Vec::new(),
     })
 }
 
-fn run_all_solvers(data: &parser::MdpData) -> Result<Vec<SolverResult>, Box<dyn std::error::Error>> {
-    let mut results = Vec::new();
+// MaxCut runs every Cut-* solver unless --solvers picks some
+fn test_max_cut_file(
+    path: &str,
+    category: &str,
+    options: &BenchOptions,
+) -> Result<InstanceResults, Box<dyn std::error::Error>> {
+    let graph = graph::Graph::load(path, options.index_base)?;
+    println!("  Size: n={}, m={}", graph.n, graph.edges.len());
+
+    let filename = Path::new(path).file_name().and_then(|s| s.to_str()).unwrap_or(path).to_string();
+    let default_suite: Vec<SolverRun> = solver_maxcut::max_cut_solver_names()
+        .into_iter()
+        .map(|name| SolverRun { name, params: &[] })
+        .collect();
+    let suite = options.suite.as_deref().unwrap_or(&default_suite);
+    let budget = options.budget.clone().unwrap_or_default();
 
-    // 1. QUBO with time limit
-    print!("  [1/6] QUBO... ");
-    let start = Instant::now();
-    match solver_qubo::solve_with_qubo(data, 1000.0, 300.0) {
-        Ok((_, div)) => {
-            let time = start.elapsed();
-            println!("✓ {:.2} ({:?})", div, time);
-            results.push(SolverResult {
-                name: "QUBO".to_string(),
-                diversity: div,
-                time_ms: time.as_millis(),
-                success: div > 0.0,
-            });
+    let mut results = Vec::new();
+    for (idx, run) in suite.iter().enumerate() {
+        let mut solver = create_max_cut_solver(run.name)?;
+        for (key, value) in run.params {
+            solver.set_param(key, value)?;
         }
-        Err(_) => {
-            println!("✗ Timeout/Error");
-            results.push(SolverResult {
-                name: "QUBO".to_string(),
-                diversity: 0.0,
-                time_ms: start.elapsed().as_millis(),
-                success: false,
-            });
+        if let Some(seed) = options.seed {
+            solver.set_param("seed", &seed.to_string())?;
         }
-    }
 
-    // 2. GRASP
-    print!("  [2/6] GRASP... ");
-    let config = GraspConfig {
-        iterations: 50,
-        alpha: 0.3,
-        local_search_iters: 500,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_grasp::solve_grasp(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "GRASP".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
-
-    // 3. First Improvement LS
-    print!("  [3/6] LS: First... ");
-    let config = LocalSearchConfig {
-        method: LocalSearchMethod::FirstImprovement,
-        max_iters: 5000,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_local_search::solve_local_search(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "LS-First".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+        print!("  [{}/{}] {}... ", idx + 1, suite.len(), solver.name());
+        std::io::stdout().flush()?;
+        let start = Instant::now();
+        let outcome = solver.solve(&graph, &budget);
+        let result = SolverResult::for_cut(&solver, outcome, start.elapsed());
+        print_run_result(&result);
+        results.push(result);
+    }
+    println!();
 
-    // 4. Best Improvement LS
-    print!("  [4/6] LS: Best... ");
-    let config = LocalSearchConfig {
-        method: LocalSearchMethod::BestImprovement,
-        max_iters: 5000,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_local_search::solve_local_search(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "LS-Best".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+    Ok(InstanceResults {
+        filename,
+        category: category.to_string(),
+        n: graph.n,
+        k: 0,
+        edges: Some(graph.edges.len()),
+        results,
+        pareto: Vec::new(),
+    })
+}
 
-    // 5. Tabu Search
-    print!("  [5/6] Tabu... ");
-    let config = LocalSearchConfig {
-        method: LocalSearchMethod::TabuSearch { tabu_tenure: 10 },
-        max_iters: 1000,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_local_search::solve_local_search(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "Tabu".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+// Pareto runs every Pareto-* solver unless --solvers picks some
+fn test_pareto_file(
+    path: &str,
+    category: &str,
+    options: &BenchOptions,
+) -> Result<InstanceResults, Box<dyn std::error::Error>> {
+    let data = parser::MdpData::load(path)?;
+    println!("  Size: n={}, k={}", data.n, data.k);
 
-    // 6. Genetic Algorithm
-    print!("  [6/6] GA... ");
-    let config = GeneticConfig {
-        population_size: 30,
-        generations: 50,
-        crossover_rate: 0.8,
-        mutation_rate: 0.15,
-        elite_size: 3,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_population::solve_genetic(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "GA".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+    let filename = Path::new(path).file_name().and_then(|s| s.to_str()).unwrap_or(path).to_string();
+    let default_suite: Vec<SolverRun> = solver_pareto::pareto_solver_names()
+        .into_iter()
+        .map(|name| SolverRun { name, params: &[] })
+        .collect();
+    let suite = options.suite.as_deref().unwrap_or(&default_suite);
+    let budget = options.budget.clone().unwrap_or_default();
+
+    let mut fronts = Vec::new();
+    for (idx, run) in suite.iter().enumerate() {
+        let mut solver = create_pareto_solver(run.name)?;
+        for (key, value) in run.params {
+            solver.set_param(key, value)?;
+        }
+        if let Some(seed) = options.seed {
+            solver.set_param("seed", &seed.to_string())?;
+        }
 
+        print!("  [{}/{}] {}... ", idx + 1, suite.len(), solver.name());
+        std::io::stdout().flush()?;
+        let start = Instant::now();
+        let outcome = solver.solve(&data, &budget);
+        let result = ParetoResult::new(&solver, outcome, start.elapsed());
+        if result.success {
+            let optimal = if result.proven_optimal { " [exact]" } else { "" };
+            println!("✓ {} points, {} ({} ms){}", result.front.len(), result.ranges(), result.time_ms, optimal);
+        } else {
+            match result.details.get("error") {
+                Some(error) => println!("✗ Error ({})", error),
+                None => println!("✗ Timeout/Error"),
+            }
+        }
+        fronts.push(result);
+    }
     println!();
-    Ok(results)
+
+    Ok(InstanceResults {
+        filename,
+        category: category.to_string(),
+        n: data.n,
+        k: data.k,
+        edges: None,
+        results: Vec::new(),
+        pareto: fronts,
+    })
 }
 
-fn run_medium_solvers(data: &parser::MdpData) -> Result<Vec<SolverResult>, Box<dyn std::error::Error>> {
+fn run_suite(
+    data: &parser::MdpData,
+    suite: &[SolverRun],
+    budget: &Budget,
+    seed: Option<u64>,
+    objective: Option<Objective>,
+) -> Result<Vec<SolverResult>, Box<dyn std::error::Error>> {
     let mut results = Vec::new();
 
-    print!("  [1/4] QUBO... ");
-    let start = Instant::now();
-    match solver_qubo::solve_with_qubo(data, 1000.0, 120.0) {
-        Ok((_, div)) => {
-            let time = start.elapsed();
-            println!("✓ {:.2} ({:?})", div, time);
-            results.push(SolverResult {
-                name: "QUBO".to_string(),
-                diversity: div,
-                time_ms: time.as_millis(),
-                success: div > 0.0,
-            });
+    for (idx, run) in suite.iter().enumerate() {
+        if solver::needs_gurobi_feature(run.name) {
+            println!("  [{}/{}] {}... skipped (built without the gurobi feature)", idx + 1, suite.len(), run.name);
+            continue;
         }
-        Err(_) => {
-            println!("✗ Timeout");
-            results.push(SolverResult {
-                name: "QUBO".to_string(),
-                diversity: 0.0,
-                time_ms: start.elapsed().as_millis(),
-                success: false,
-            });
+        let mut solver = create_solver(run.name)?;
+        for (key, value) in run.params {
+            solver.set_param(key, value)?;
+        }
+        if let Some(seed) = seed {
+            solver.set_param("seed", &seed.to_string())?;
+        }
+        if let Some(objective) = objective {
+            if solver.set_param("objective", objective.name()).is_err() {
+                println!("  [{}/{}] {}... skipped (no {} variant)", idx + 1, suite.len(), run.name, objective);
+                continue;
+            }
         }
-    }
-
-    print!("  [2/4] GRASP... ");
-    let config = GraspConfig {
-        iterations: 30,
-        alpha: 0.3,
-        local_search_iters: 300,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_grasp::solve_grasp(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "GRASP".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
-
-    print!("  [3/4] LS: Best... ");
-    let config = LocalSearchConfig {
-        method: LocalSearchMethod::BestImprovement,
-        max_iters: 2000,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_local_search::solve_local_search(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "LS-Best".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
 
-    print!("  [4/4] GA... ");
-    let config = GeneticConfig {
-        population_size: 20,
-        generations: 30,
-        crossover_rate: 0.8,
-        mutation_rate: 0.15,
-        elite_size: 2,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_population::solve_genetic(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "GA".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+        print!("  [{}/{}] {}... ", idx + 1, suite.len(), solver.name());
+        std::io::stdout().flush()?;
+        let start = Instant::now();
+        let outcome = solver.solve(data, budget);
+        let result = SolverResult::new(solver.as_ref(), data, outcome, start.elapsed());
+        print_run_result(&result);
+        results.push(result);
+    }
 
     println!();
     Ok(results)
 }
 
-fn run_fast_solvers(data: &parser::MdpData) -> Vec<SolverResult> {
-    let mut results = Vec::new();
+// Completes the `[i/n] Solver... ` progress line of one benchmark run
+fn print_run_result(result: &SolverResult) {
+    if result.success {
+        let optimal = if result.proven_optimal { " [optimal]" } else { "" };
+        println!("✓ {:.2} ({} ms){}", result.value(), result.time_ms, optimal);
+    } else {
+        match result.details.get("status") {
+            Some(status) => println!("✗ Timeout/Error (status: {})", status),
+            None => println!("✗ Timeout/Error"),
+        }
+    }
+}
 
-    print!("  [1/3] GRASP... ");
-    let config = GraspConfig {
-        iterations: 20,
-        alpha: 0.3,
-        local_search_iters: 200,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_grasp::solve_grasp(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "GRASP".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+// Instance files are grouped by the prefix of their directory name, e.g. GKD-a -> GKD
+fn instance_category(path: &str) -> String {
+    Path::new(path)
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .map(|name| name.split('-').next().unwrap_or(name).to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
 
-    print!("  [2/3] LS: First... ");
-    let config = LocalSearchConfig {
-        method: LocalSearchMethod::FirstImprovement,
-        max_iters: 1000,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_local_search::solve_local_search(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "LS-First".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+// Accepts a directory (searched recursively for .txt files) or a glob pattern
+fn discover_test_files(input: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
 
-    print!("  [3/3] GA... ");
-    let config = GeneticConfig {
-        population_size: 15,
-        generations: 20,
-        crossover_rate: 0.8,
-        mutation_rate: 0.15,
-        elite_size: 2,
-    };
-    let start = Instant::now();
-    let (_, div) = solver_population::solve_genetic(data, &config);
-    let time = start.elapsed();
-    println!("✓ {:.2} ({:?})", div, time);
-    results.push(SolverResult {
-        name: "GA".to_string(),
-        diversity: div,
-        time_ms: time.as_millis(),
-        success: true,
-    });
+    if Path::new(input).is_dir() {
+        collect_txt_files(Path::new(input), &mut files)?;
+    } else {
+        for entry in glob::glob(input)? {
+            let path = entry?;
+            if path.is_file() {
+                if let Some(path_str) = path.to_str() {
+                    files.push(path_str.to_string());
+                }
+            }
+        }
+    }
 
-    println!();
-    results
+    if files.is_empty() {
+        return Err(format!("no instance files found for '{}'", input).into());
+    }
+    files.sort();
+    Ok(files)
 }
 
-fn discover_test_files(dir: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let mut files = Vec::new();
-    
+fn collect_txt_files(dir: &Path, files: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
     for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
+        let path = entry?.path();
         
-        if path.is_file() {
-            if let Some(ext) = path.extension() {
-                if ext == "txt" {
-                    if let Some(path_str) = path.to_str() {
-                        files.push(path_str.to_string());
-                    }
-                }
+        if path.is_dir() {
+            collect_txt_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Some(path_str) = path.to_str() {
+                files.push(path_str.to_string());
             }
         }
     }
-    
-    files.sort();
-    Ok(files)
+    Ok(())
 }
 
 fn save_results_to_json(results: &ExperimentResults, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
@@ -983,76 +1044,117 @@ fn save_results_to_json(results: &ExperimentResults, filename: &str) -> Result<(
 }
 
 fn print_comprehensive_summary(results: &ExperimentResults) {
-    let mut by_category: HashMap<String, Vec<&InstanceResults>> = HashMap::new();
+    let mut by_category: BTreeMap<String, Vec<&InstanceResults>> = BTreeMap::new();
     
     for instance in &results.instances {
         by_category.entry(instance.category.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(instance);
     }
 
     println!("\n\n{:=<100}", "");
     println!("COMPREHENSIVE RESULTS SUMMARY");
     println!("{:=<100}\n", "");
+    let objective = results.instances.iter().flat_map(|inst| &inst.results).find_map(|r| r.objective.as_ref());
+    if let Some(objective) = objective {
+        println!("Values are {} values (objective_value in the JSON results)", objective);
+    }
 
-    for category in ["GKD", "MDG", "SOM"] {
-        if let Some(instances) = by_category.get(category) {
-            if instances.is_empty() {
-                continue;
-            }
-
-            println!("\n{:-<100}", "");
-            println!("{} INSTANCES ({} files)", category, instances.len());
-            println!("{:-<100}", "");
+    for (category, instances) in &by_category {
+        println!("\n{:-<100}", "");
+        println!("{} INSTANCES ({} files)", category, instances.len());
+        println!("{:-<100}", "");
+        
+        // One column per registered solver that produced results in this category
+        let solver_names: Vec<&str> = solver::solver_names()
+            .into_iter()
+            .chain(solver_maxcut::max_cut_solver_names())
+            .filter(|name| instances.iter().any(|inst| inst.results.iter().any(|r| r.name == *name)))
+            .collect();
+
+        // MaxCut graphs show their edge count in place of k
+        let size_label = if instances.iter().any(|inst| inst.edges.is_some()) { "m" } else { "k" };
+        print!("{:<30} {:>8} {:>6} |", "File", "n", size_label);
+        for solver in &solver_names {
+            print!(" {:>12}", solver);
+        }
+        println!();
+        println!("{:-<100}", "");
+
+        for inst in instances {
+            print!("{:<30} {:>8} {:>6} |", 
+                truncate_filename(&inst.filename, 30),
+                inst.n, 
+                inst.edges.unwrap_or(inst.k)
+            );
             
-            println!("{:<30} {:>8} {:>6} | {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
-                "File", "n", "k", "QUBO", "GRASP", "LS-First", "LS-Best", "Tabu", "GA");
-            println!("{:-<100}", "");
-
-            for inst in instances {
-                print!("{:<30} {:>8} {:>6} |", 
-                    truncate_filename(&inst.filename, 30),
-                    inst.n, 
-                    inst.k
-                );
-                
-                let solver_names = ["QUBO", "GRASP", "LS-First", "LS-Best", "Tabu", "GA"];
-                for solver in &solver_names {
-                    if let Some(result) = inst.results.iter().find(|r| r.name == *solver) {
-                        if result.success {
-                            print!(" {:>12.2}", result.diversity);
-                        } else {
-                            print!(" {:>12}", "TIMEOUT");
-                        }
+            for solver in &solver_names {
+                if let Some(result) = inst.results.iter().find(|r| r.name == *solver) {
+                    if result.success {
+                        print!(" {:>12.2}", result.value());
                     } else {
-                        print!(" {:>12}", "-");
+                        print!(" {:>12}", "TIMEOUT");
                     }
+                } else {
+                    print!(" {:>12}", "-");
                 }
-                println!();
             }
+            println!();
+        }
 
-            println!("\n{:<30} {:>8} {:>6} | {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
-                "Average Time (ms)", "", "", "QUBO", "GRASP", "LS-First", "LS-Best", "Tabu", "GA");
-            println!("{:-<100}", "");
-            
-            let solver_names = ["QUBO", "GRASP", "LS-First", "LS-Best", "Tabu", "GA"];
-            print!("{:<30} {:>8} {:>6} |", "", "", "");
+        print!("\n{:<30} {:>8} {:>6} |", "Average Time (ms)", "", "");
+        for solver in &solver_names {
+            print!(" {:>12}", solver);
+        }
+        println!();
+        println!("{:-<100}", "");
+        
+        print!("{:<30} {:>8} {:>6} |", "", "", "");
+        
+        for solver in &solver_names {
+            let times: Vec<u128> = instances.iter()
+                .flat_map(|inst| inst.results.iter())
+                .filter(|r| r.name == *solver && r.success)
+                .map(|r| r.time_ms)
+                .collect();
             
-            for solver in &solver_names {
-                let times: Vec<u128> = instances.iter()
-                    .flat_map(|inst| inst.results.iter())
-                    .filter(|r| r.name == *solver && r.success)
-                    .map(|r| r.time_ms)
-                    .collect();
-                
-                if !times.is_empty() {
-                    let avg_ms = times.iter().sum::<u128>() / times.len() as u128;
-                    print!(" {:>12}", avg_ms);
-                } else {
-                    print!(" {:>12}", "-");
+            if !times.is_empty() {
+                let avg_ms = times.iter().sum::<u128>() / times.len() as u128;
+                print!(" {:>12}", avg_ms);
+            } else {
+                print!(" {:>12}", "-");
+            }
+        }
+        println!("\n");
+    }
+
+    println!("\n{:=<100}\n", "");
+}
+
+// One row per instance and Pareto-* solver, with the size and ranges of its front
+fn print_pareto_summary(results: &ExperimentResults) {
+    println!("\n\n{:=<100}", "");
+    println!("PARETO FRONTS SUMMARY");
+    println!("{:=<100}\n", "");
+    println!("Sum and min are the max-sum and max-min values at the two ends of each front");
+    println!(
+        "\n{:<30} {:>6} {:>4} | {:<14} {:>6} {:>21} {:>17} {:>9}",
+        "File", "n", "k", "Solver", "Points", "Sum", "Min", "Time (ms)"
+    );
+    println!("{:-<100}", "");
+
+    for inst in &results.instances {
+        for front in &inst.pareto {
+            print!("{:<30} {:>6} {:>4} | {:<14} ", truncate_filename(&inst.filename, 30), inst.n, inst.k, front.name);
+            match (front.front.first(), front.front.last()) {
+                (Some(first), Some(last)) if front.success => {
+                    let sum = format!("{:.2}..{:.2}", last.sum, first.sum);
+                    let min = format!("{:.2}..{:.2}", first.min, last.min);
+                    let exact = if front.proven_optimal { " exact" } else { "" };
+                    println!("{:>6} {:>21} {:>17} {:>9}{}", front.front.len(), sum, min, front.time_ms, exact);
                 }
+                _ => println!("{:>6} {:>21} {:>17} {:>9}", 0, "-", "-", front.time_ms),
             }
-            println!("\n");
         }
     }
 
@@ -1257,6 +1359,8 @@ def main():
                 'k': instance['k'],
                 'solver': result['name'],
                 'diversity': result['diversity'],
+                'objective': result.get('objective', 'max-sum'),
+                'objective_value': result.get('objective_value', result['diversity']),
                 'time_ms': result['time_ms'],
                 'success': result['success']
             })
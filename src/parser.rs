@@ -1,46 +1,610 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+//! Reading and writing instances in MDPLIB format, and reading solution files.
 
+use serde::Deserialize;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+/// An MDP instance: `n` elements, `k` to select, and the symmetric distance matrix.
 pub struct MdpData {
     pub n: usize,
     pub k: usize,
     pub distances: Vec<f64>, // Flattened 1D vector for better memory performance
+    pub groups: Option<GroupSpec>, // Set by a `groups` line, for the grouping problem
+    pub capacity: Option<CapacitySpec>, // Set by `weight`, `capacity` and `cardinality` lines
+    pub constraints: Option<SideConstraints>, // Set by `include`, `exclude`, `category` and `quota` lines
+}
+
+/// Side constraints on a selection: elements that must or must not be
+/// selected, and per-category quotas of at least `min` and at most `max`
+/// selected elements. Elements without a category are only bound by the size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SideConstraints {
+    pub required: Vec<bool>,          // Per element; set by `include i` lines
+    pub forbidden: Vec<bool>,         // Per element; set by `exclude i` lines
+    pub category: Vec<Option<usize>>, // Per element; set by `category i c` lines
+    pub quotas: Vec<(usize, usize)>,  // Per category (min, max); 0..=n without a `quota c min max` line
+}
+
+impl SideConstraints {
+    /// No constraint on any of `n` elements.
+    pub fn new(n: usize) -> Self {
+        SideConstraints {
+            required: vec![false; n],
+            forbidden: vec![false; n],
+            category: vec![None; n],
+            quotas: Vec::new(),
+        }
+    }
+
+    pub fn required_elements(&self) -> Vec<usize> {
+        (0..self.required.len()).filter(|&i| self.required[i]).collect()
+    }
+
+    /// Elements of each category, indexed by category.
+    pub fn members(&self) -> Vec<Vec<usize>> {
+        let mut members = vec![Vec::new(); self.quotas.len()];
+        for (i, category) in self.category.iter().enumerate() {
+            if let Some(c) = *category {
+                members[c].push(i);
+            }
+        }
+        members
+    }
+
+    /// Checks that some selection of `min_size..=max_size` elements meets
+    /// every constraint; the error says which constraints conflict.
+    pub fn check(&self, min_size: usize, max_size: usize) -> Result<(), String> {
+        if let Some(i) = (0..self.required.len()).find(|&i| self.required[i] && self.forbidden[i]) {
+            return Err(format!("element {} is both included and excluded", i));
+        }
+        // Fewest elements any feasible selection has, and most it can have
+        let mut lower = 0;
+        let mut upper = 0;
+        for (i, category) in self.category.iter().enumerate() {
+            if category.is_none() {
+                lower += self.required[i] as usize;
+                upper += !self.forbidden[i] as usize;
+            }
+        }
+        for (c, members) in self.members().iter().enumerate() {
+            let (min, max) = self.quotas[c];
+            let required = members.iter().filter(|&&i| self.required[i]).count();
+            let available = members.iter().filter(|&&i| !self.forbidden[i]).count();
+            if min > max || required > max || min > available {
+                return Err(format!(
+                    "category {} cannot meet its quota {}..={} ({} included, {} not excluded)",
+                    c, min, max, required, available
+                ));
+            }
+            lower += min.max(required);
+            upper += max.min(available);
+        }
+        if lower > max_size {
+            return Err(format!("inclusions and quotas need at least {} elements, above {}", lower, max_size));
+        }
+        if upper < min_size {
+            return Err(format!("exclusions and quotas allow at most {} elements, below {}", upper, min_size));
+        }
+        Ok(())
+    }
+}
+
+/// Element weights, capacity and cardinality limits of a Capacitated (or
+/// Generalized) Diversity instance: any selection of `min_size..=max_size`
+/// elements whose total weight is at most `capacity`; `k` is not used.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapacitySpec {
+    pub weights: Vec<f64>, // Per element; 1 unless given by a `weight` line
+    pub capacity: f64,     // Infinite without a `capacity` line
+    pub min_size: usize,
+    pub max_size: usize,
+}
+
+impl CapacitySpec {
+    /// The plain MDP in capacitated form: unit weights, capacity k and exactly k elements.
+    pub fn cardinality(n: usize, k: usize) -> Self {
+        CapacitySpec {
+            weights: vec![1.0; n],
+            capacity: k as f64,
+            min_size: k,
+            max_size: k,
+        }
+    }
+
+    /// Total weight of `selected`.
+    pub fn weight_of(&self, selected: &[usize]) -> f64 {
+        selected.iter().map(|&i| self.weights[i]).sum()
+    }
+
+    /// Whether some selection meets both the cardinality limits and the capacity.
+    pub fn is_feasible(&self) -> bool {
+        let mut weights = self.weights.clone();
+        weights.sort_by(|a, b| a.total_cmp(b));
+        self.min_size <= self.max_size
+            && self.max_size <= weights.len()
+            && weights[..self.min_size].iter().sum::<f64>() <= self.capacity
+    }
+}
+
+/// Group count and size limits of a Maximally Diverse Grouping instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupSpec {
+    pub groups: usize,
+    pub min_size: usize,
+    pub max_size: usize,
+}
+
+impl GroupSpec {
+    /// `groups` groups whose sizes differ by at most one.
+    pub fn balanced(n: usize, groups: usize) -> Self {
+        GroupSpec {
+            groups,
+            min_size: n / groups.max(1),
+            max_size: n.div_ceil(groups.max(1)),
+        }
+    }
+
+    /// Whether `n` elements can be split into groups of the allowed sizes,
+    /// with no more groups than elements.
+    pub fn fits(&self, n: usize) -> bool {
+        (1..=n).contains(&self.groups)
+            && self.min_size <= self.max_size
+            && self.groups.saturating_mul(self.min_size) <= n
+            && n <= self.groups.saturating_mul(self.max_size)
+    }
+}
+
+impl fmt::Display for GroupSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} groups of {}..={} elements", self.groups, self.min_size, self.max_size)
+    }
+}
+
+/// Why an instance or solution file could not be read.
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    MissingHeader,
+    BadToken { line: usize, token: String },
+    IndexOutOfRange { line: usize, index: usize, n: usize },
+    CategoryOutOfRange { line: usize, category: usize, n: usize }, // Categories are numbered below n
+    KExceedsN { k: usize, n: usize },
+    TooManyElements { n: usize }, // The n × n distance matrix is not addressable
+    AmbiguousIndexBase { n: usize }, // An edge list using only 1..n-1 may be 0- or 1-based
+    SelfDistance { line: usize, index: usize },
+    ConflictingEdge { line: usize, i: usize, j: usize, previous: f64, found: f64 },
+    InfeasibleGroups { spec: GroupSpec, n: usize },
+    InfeasibleCapacity { capacity: f64, min_size: usize, max_size: usize, n: usize },
+    InfeasibleConstraints { reason: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "could not read file: {}", e),
+            ParseError::Json(e) => write!(f, "invalid JSON: {}", e),
+            ParseError::MissingHeader => write!(f, "missing 'n k' header line"),
+            ParseError::BadToken { line, token } => {
+                write!(f, "line {}: could not parse '{}'", line, token)
+            }
+            ParseError::IndexOutOfRange { line, index, n } => {
+                write!(f, "line {}: index {} out of range (n = {})", line, index, n)
+            }
+            ParseError::CategoryOutOfRange { line, category, n } => {
+                write!(f, "line {}: category {} out of range (categories are numbered below n = {})", line, category, n)
+            }
+            ParseError::KExceedsN { k, n } => write!(f, "k = {} exceeds n = {}", k, n),
+            ParseError::TooManyElements { n } => write!(f, "n = {} is too large for a distance matrix", n),
+            ParseError::AmbiguousIndexBase { n } => write!(
+                f,
+                "edge list uses neither vertex 0 nor vertex {}, so it may be 0- or 1-based; give the index base",
+                n
+            ),
+            ParseError::SelfDistance { line, index } => {
+                write!(f, "line {}: distance from {} to itself (only pairs i != j have one)", line, index)
+            }
+            ParseError::ConflictingEdge { line, i, j, previous, found } => write!(
+                f,
+                "line {}: edge ({}, {}) redefined with weight {} (was {})",
+                line, i, j, found, previous
+            ),
+            ParseError::InfeasibleGroups { spec, n } => write!(f, "{} cannot hold n = {} elements", spec, n),
+            ParseError::InfeasibleCapacity { capacity, min_size, max_size, n } => write!(
+                f,
+                "no selection of {}..={} of the n = {} elements fits capacity {}",
+                min_size, max_size, n, capacity
+            ),
+            ParseError::InfeasibleConstraints { reason } => write!(f, "side constraints cannot be met: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(e) => Some(e),
+            ParseError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(e: io::Error) -> Self {
+        ParseError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(e: serde_json::Error) -> Self {
+        ParseError::Json(e)
+    }
 }
 
 impl MdpData {
-    pub fn load(path: &str) -> Self {
-        let file = File::open(path).expect("Could not open MDPLIB file");
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
-
-        // Parse first line: n k
-        let first_line = lines.next().unwrap().unwrap();
-        let header: Vec<usize> = first_line.split_whitespace()
-            .map(|s| s.parse().unwrap()).collect();
-        
-        let n = header[0];
-        let k = header[1];
-        let mut distances = vec![0.0; n * n];
+    /// Reads an MDPLIB instance: an `n k` header, then one `i j d` line per pair.
+    /// A `groups G [min max]` line gives the group count and size limits of a
+    /// grouping instance; the sizes default to balanced groups. `weight i w`,
+    /// `capacity C` and `cardinality min max` lines make it a capacitated
+    /// instance (see `CapacitySpec`), and `include i`, `exclude i`,
+    /// `category i c` and `quota c min max` lines add `SideConstraints`.
+    pub fn load(path: &str) -> Result<Self, ParseError> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads an instance in the format of `load` from `reader`.
+    pub fn read_from(reader: impl BufRead) -> Result<Self, ParseError> {
+        let mut lines = reader.lines().enumerate();
+
+        // Parse first non-empty line: n k
+        let (n, k) = loop {
+            let (idx, line) = lines.next().ok_or(ParseError::MissingHeader)?;
+            let line = line?;
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() {
+                continue;
+            }
+            if parts.len() < 2 {
+                return Err(ParseError::MissingHeader);
+            }
+            break (parse_token(parts[0], idx + 1)?, parse_token(parts[1], idx + 1)?);
+        };
+
+        if k > n {
+            return Err(ParseError::KExceedsN { k, n });
+        }
+
+        let cells = n.checked_mul(n).ok_or(ParseError::TooManyElements { n })?;
+        let mut distances = vec![0.0; cells];
+        // Tracks which pairs were already read so duplicates can be checked
+        let mut seen = vec![false; cells];
+        let mut groups = None;
+        let mut capacity: Option<CapacitySpec> = None;
+        let mut constraints: Option<SideConstraints> = None;
 
         // Parse distance lines: i j dist
-        for line in lines {
-            let l = line.unwrap();
+        for (idx, line) in lines {
+            let line_no = idx + 1;
+            let l = line?;
             let parts: Vec<&str> = l.split_whitespace().collect();
-            if parts.len() < 3 { continue; }
-            
-            let i: usize = parts[0].parse().unwrap();
-            let j: usize = parts[1].parse().unwrap();
-            let d: f64 = parts[2].parse().unwrap();
+            if parts.is_empty() {
+                continue;
+            }
+            if parts[0] == "groups" {
+                groups = Some(parse_groups(&parts[1..], n, line_no)?);
+                continue;
+            }
+            if matches!(parts[0], "weight" | "capacity" | "cardinality") {
+                let spec = capacity.get_or_insert_with(|| CapacitySpec {
+                    weights: vec![1.0; n],
+                    capacity: f64::INFINITY,
+                    min_size: 0,
+                    max_size: n,
+                });
+                parse_capacity_line(&parts, spec, line_no)?;
+                continue;
+            }
+            if matches!(parts[0], "include" | "exclude" | "category" | "quota") {
+                let spec = constraints.get_or_insert_with(|| SideConstraints::new(n));
+                parse_constraint_line(&parts, spec, line_no)?;
+                continue;
+            }
+            if parts.len() < 3 {
+                return Err(ParseError::BadToken { line: line_no, token: l.trim().to_string() });
+            }
+
+            let i: usize = parse_token(parts[0], line_no)?;
+            let j: usize = parse_token(parts[1], line_no)?;
+            let d: f64 = parse_token(parts[2], line_no)?;
+
+            for index in [i, j] {
+                if index >= n {
+                    return Err(ParseError::IndexOutOfRange { line: line_no, index, n });
+                }
+            }
+            if i == j {
+                return Err(ParseError::SelfDistance { line: line_no, index: i });
+            }
+
+            if seen[i * n + j] && distances[i * n + j] != d {
+                return Err(ParseError::ConflictingEdge {
+                    line: line_no,
+                    i,
+                    j,
+                    previous: distances[i * n + j],
+                    found: d,
+                });
+            }
 
             // Fill symmetrically
             distances[i * n + j] = d;
             distances[j * n + i] = d;
+            seen[i * n + j] = true;
+            seen[j * n + i] = true;
+        }
+
+        if let Some(spec) = &capacity {
+            if !spec.is_feasible() {
+                let (min_size, max_size) = (spec.min_size, spec.max_size);
+                return Err(ParseError::InfeasibleCapacity { capacity: spec.capacity, min_size, max_size, n });
+            }
         }
 
-        MdpData { n, k, distances }
+        if let Some(spec) = &constraints {
+            // Capacitated instances select any size within their cardinality limits
+            let (min_size, max_size) = capacity.as_ref().map_or((k, k), |c| (c.min_size, c.max_size));
+            spec.check(min_size, max_size).map_err(|reason| ParseError::InfeasibleConstraints { reason })?;
+        }
+
+        Ok(MdpData { n, k, distances, groups, capacity, constraints })
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        self.write_to(File::create(path)?)
+    }
+
+    /// Writes the instance in MDPLIB format (header plus one `i j d` line per pair i < j),
+    /// with the `groups`, capacity and side-constraint lines of the extended
+    /// instances after the header.
+    pub fn write_to(&self, out: impl Write) -> io::Result<()> {
+        let mut out = BufWriter::new(out);
+        writeln!(out, "{} {}", self.n, self.k)?;
+        if let Some(spec) = self.groups {
+            writeln!(out, "groups {} {} {}", spec.groups, spec.min_size, spec.max_size)?;
+        }
+        if let Some(spec) = &self.capacity {
+            if spec.capacity.is_finite() {
+                writeln!(out, "capacity {}", spec.capacity)?;
+            }
+            writeln!(out, "cardinality {} {}", spec.min_size, spec.max_size)?;
+            for (i, weight) in spec.weights.iter().enumerate() {
+                writeln!(out, "weight {} {}", i, weight)?;
+            }
+        }
+        if let Some(spec) = &self.constraints {
+            for (c, (min, max)) in spec.quotas.iter().enumerate() {
+                writeln!(out, "quota {} {} {}", c, min, max)?;
+            }
+            for i in 0..self.n {
+                if let Some(c) = spec.category[i] {
+                    writeln!(out, "category {} {}", i, c)?;
+                }
+                if spec.required[i] {
+                    writeln!(out, "include {}", i)?;
+                }
+                if spec.forbidden[i] {
+                    writeln!(out, "exclude {}", i)?;
+                }
+            }
+        }
+        for i in 0..self.n {
+            for j in (i + 1)..self.n {
+                writeln!(out, "{} {} {}", i, j, self.get_dist(i, j))?;
+            }
+        }
+        out.flush()
     }
 
     pub fn get_dist(&self, i: usize, j: usize) -> f64 {
         self.distances[i * self.n + j]
     }
-}
\ No newline at end of file
+}
+
+/// Reads a selection: element indices separated by whitespace or commas, with
+/// `#` starting a comment. The JSON written by `solve --json` is accepted too.
+pub fn load_solution(path: &str) -> Result<Vec<usize>, ParseError> {
+    parse_solution(&fs::read_to_string(path)?)
+}
+
+/// Parses a selection in the format of `load_solution`.
+pub fn parse_solution(content: &str) -> Result<Vec<usize>, ParseError> {
+    if content.trim_start().starts_with('{') {
+        let solution: SolutionFile = serde_json::from_str(content)?;
+        return Ok(solution.selected);
+    }
+
+    let mut selected = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        for token in line.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()) {
+            selected.push(parse_token(token, idx + 1)?);
+        }
+    }
+    Ok(selected)
+}
+
+#[derive(Deserialize)]
+struct SolutionFile {
+    selected: Vec<usize>,
+}
+
+// Arguments of a `groups G [min max]` line
+fn parse_groups(parts: &[&str], n: usize, line: usize) -> Result<GroupSpec, ParseError> {
+    let spec = match parts {
+        [groups] => GroupSpec::balanced(n, parse_token(groups, line)?),
+        [groups, min_size, max_size] => GroupSpec {
+            groups: parse_token(groups, line)?,
+            min_size: parse_token(min_size, line)?,
+            max_size: parse_token(max_size, line)?,
+        },
+        _ => return Err(ParseError::BadToken { line, token: format!("groups {}", parts.join(" ")) }),
+    };
+    if !spec.fits(n) {
+        return Err(ParseError::InfeasibleGroups { spec, n });
+    }
+    Ok(spec)
+}
+
+// A `weight i w`, `capacity C` or `cardinality min max` line
+fn parse_capacity_line(parts: &[&str], spec: &mut CapacitySpec, line: usize) -> Result<(), ParseError> {
+    let bad_line = || ParseError::BadToken { line, token: parts.join(" ") };
+    match *parts {
+        ["weight", i, w] => {
+            let i: usize = parse_token(i, line)?;
+            let w: f64 = parse_token(w, line)?;
+            let n = spec.weights.len();
+            if i >= n {
+                return Err(ParseError::IndexOutOfRange { line, index: i, n });
+            }
+            if w < 0.0 {
+                return Err(bad_line());
+            }
+            spec.weights[i] = w;
+        }
+        ["capacity", capacity] => spec.capacity = parse_token(capacity, line)?,
+        ["cardinality", min_size, max_size] => {
+            spec.min_size = parse_token(min_size, line)?;
+            spec.max_size = parse_token(max_size, line)?;
+        }
+        _ => return Err(bad_line()),
+    }
+    Ok(())
+}
+
+// An `include i`, `exclude i`, `category i c` or `quota c min max` line. A
+// category first named by a `category` line keeps the default quota 0..=n
+// until a `quota` line sets it, and the other way round. There are at most n
+// nonempty categories, so indices from n on are rejected
+fn parse_constraint_line(parts: &[&str], spec: &mut SideConstraints, line: usize) -> Result<(), ParseError> {
+    let n = spec.required.len();
+    let element = |token: &str| -> Result<usize, ParseError> {
+        let index = parse_token(token, line)?;
+        if index >= n {
+            return Err(ParseError::IndexOutOfRange { line, index, n });
+        }
+        Ok(index)
+    };
+    let category = |token: &str| -> Result<usize, ParseError> {
+        let category = parse_token(token, line)?;
+        if category >= n {
+            return Err(ParseError::CategoryOutOfRange { line, category, n });
+        }
+        Ok(category)
+    };
+    let mut ensure_category = |c: usize| {
+        if c >= spec.quotas.len() {
+            spec.quotas.resize(c + 1, (0, n));
+        }
+    };
+    match *parts {
+        ["include", i] => spec.required[element(i)?] = true,
+        ["exclude", i] => spec.forbidden[element(i)?] = true,
+        ["category", i, c] => {
+            let (i, c) = (element(i)?, category(c)?);
+            ensure_category(c);
+            spec.category[i] = Some(c);
+        }
+        ["quota", c, min, max] => {
+            let c = category(c)?;
+            let quota = (parse_token(min, line)?, parse_token(max, line)?);
+            ensure_category(c);
+            spec.quotas[c] = quota;
+        }
+        _ => return Err(ParseError::BadToken { line, token: parts.join(" ") }),
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_token<T: std::str::FromStr>(token: &str, line: usize) -> Result<T, ParseError> {
+    token.parse().map_err(|_| ParseError::BadToken { line, token: token.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<MdpData, ParseError> {
+        MdpData::read_from(text.as_bytes())
+    }
+
+    #[test]
+    fn reads_distances_and_extension_lines() {
+        let data = parse("3 2\ngroups 1\nweight 1 2.5\ncategory 2 0\nquota 0 0 1\n0 1 1.5\n1 2 -2\n").unwrap();
+        assert_eq!((data.n, data.k), (3, 2));
+        assert_eq!((data.get_dist(1, 0), data.get_dist(2, 1), data.get_dist(0, 2)), (1.5, -2.0, 0.0));
+        assert_eq!(data.groups, Some(GroupSpec { groups: 1, min_size: 3, max_size: 3 }));
+        assert_eq!(data.capacity.unwrap().weights, vec![1.0, 2.5, 1.0]);
+        let spec = data.constraints.unwrap();
+        assert_eq!((spec.category[2], spec.quotas.as_slice()), (Some(0), &[(0, 1)][..]));
+    }
+
+    #[test]
+    fn written_instances_read_back() {
+        let text = "4 2\ncapacity 3\ncardinality 1 3\nquota 0 1 2\ncategory 1 0\ninclude 3\n0 1 1\n2 3 4.5\n";
+        let data = parse(text).unwrap();
+        let mut written = Vec::new();
+        data.write_to(&mut written).unwrap();
+        let read = MdpData::read_from(written.as_slice()).unwrap();
+        assert_eq!((read.n, read.k, &read.distances), (data.n, data.k, &data.distances));
+        assert_eq!((read.groups, read.capacity, read.constraints), (data.groups, data.capacity, data.constraints));
+    }
+
+    #[test]
+    fn every_error_comes_from_a_malformed_input() {
+        assert!(matches!(MdpData::load("no/such/instance.txt"), Err(ParseError::Io(_))));
+        assert!(matches!(MdpData::read_from(&b"2 1\n\xff\n"[..]), Err(ParseError::Io(_))));
+        assert!(matches!(parse_solution("{\"selected\": [1, "), Err(ParseError::Json(_))));
+        assert!(matches!(parse(""), Err(ParseError::MissingHeader)));
+        assert!(matches!(parse("\n5\n"), Err(ParseError::MissingHeader)));
+        assert!(matches!(parse("5 x"), Err(ParseError::BadToken { line: 1, .. })));
+        assert!(matches!(parse("3 1\n0 1"), Err(ParseError::BadToken { line: 2, .. })));
+        assert!(matches!(parse("3 1\n0 1 far"), Err(ParseError::BadToken { line: 2, .. })));
+        assert!(matches!(parse("3 1\nweight 0 -1"), Err(ParseError::BadToken { line: 2, .. })));
+        assert!(matches!(parse("3 1\nquota 0 1"), Err(ParseError::BadToken { line: 2, .. })));
+        assert!(matches!(parse("3 1\ngroups 1 2"), Err(ParseError::BadToken { line: 2, .. })));
+        assert!(matches!(parse("3 1\n0 3 1.0"), Err(ParseError::IndexOutOfRange { line: 2, index: 3, n: 3 })));
+        assert!(matches!(parse("3 1\nweight 7 1"), Err(ParseError::IndexOutOfRange { index: 7, .. })));
+        assert!(matches!(parse("3 1\n\ninclude 3"), Err(ParseError::IndexOutOfRange { line: 3, .. })));
+        assert!(matches!(
+            parse("3 1\ncategory 0 3"),
+            Err(ParseError::CategoryOutOfRange { line: 2, category: 3, n: 3 })
+        ));
+        assert!(matches!(
+            parse("3 1\nquota 18446744073709551615 0 1"),
+            Err(ParseError::CategoryOutOfRange { category: usize::MAX, .. })
+        ));
+        assert!(matches!(parse("3 5"), Err(ParseError::KExceedsN { k: 5, n: 3 })));
+        assert!(matches!(parse("4294967296 1"), Err(ParseError::TooManyElements { n: 4294967296 })));
+        assert!(matches!(parse("3 1\n0 1 2\n2 2 0"), Err(ParseError::SelfDistance { line: 3, index: 2 })));
+        assert!(matches!(
+            parse("3 1\n0 1 2\n1 0 3"),
+            Err(ParseError::ConflictingEdge { line: 3, i: 1, j: 0, .. })
+        ));
+        assert!(matches!(parse("5 1\ngroups 2 1 2"), Err(ParseError::InfeasibleGroups { n: 5, .. })));
+        assert!(matches!(parse("5 1\ngroups 18446744073709551615 2 2"), Err(ParseError::InfeasibleGroups { .. })));
+        assert!(matches!(parse("5 1\ngroups 6"), Err(ParseError::InfeasibleGroups { .. })));
+        let overweight = parse("3 1\ncapacity 1\nweight 0 2\ncardinality 2 3");
+        assert!(matches!(overweight, Err(ParseError::InfeasibleCapacity { min_size: 2, max_size: 3, .. })));
+        assert!(matches!(parse("3 1\ninclude 0\nexclude 0"), Err(ParseError::InfeasibleConstraints { .. })));
+        assert!(matches!(parse("3 2\nexclude 0\nexclude 1"), Err(ParseError::InfeasibleConstraints { .. })));
+    }
+
+    #[test]
+    fn solutions_accept_separators_comments_and_json() {
+        assert_eq!(parse_solution("3, 1\n# best so far\n4 0 # tail").unwrap(), vec![3, 1, 4, 0]);
+        assert_eq!(parse_solution("{\"selected\": [2, 5], \"diversity\": 1.0}").unwrap(), vec![2, 5]);
+        assert!(matches!(parse_solution("1 -2"), Err(ParseError::BadToken { line: 1, .. })));
+    }
+}
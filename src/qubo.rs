@@ -0,0 +1,537 @@
+//! The MDP as a QUBO, independent of any solver, with exports to common text formats.
+
+use crate::evaluation::{calculate_diversity, repair_selection};
+use crate::parser::{MdpData, SideConstraints};
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+use std::str::FromStr;
+
+/// How the penalty weight λ of `(sum x - k)^2` is chosen for an instance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PenaltyStrategy {
+    Fixed(f64),
+    Bound,         // `sufficient_penalty`: every optimum of the QUBO has k elements
+    Relative(f64), // factor * max |d_ij|
+    Adaptive,      // Start at max |d_ij| and grow until the solver returns k elements
+}
+
+impl PenaltyStrategy {
+    /// λ for `data`, or `None` for `Adaptive`, which needs a solver in the loop.
+    pub fn penalty_for(self, data: &MdpData) -> Option<f64> {
+        match self {
+            PenaltyStrategy::Fixed(penalty) => Some(penalty),
+            PenaltyStrategy::Bound => Some(sufficient_penalty(data)),
+            PenaltyStrategy::Relative(factor) => Some(factor * distance_scale(data)),
+            PenaltyStrategy::Adaptive => None,
+        }
+    }
+}
+
+impl fmt::Display for PenaltyStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PenaltyStrategy::Fixed(penalty) => write!(f, "{}", penalty),
+            PenaltyStrategy::Bound => write!(f, "bound"),
+            PenaltyStrategy::Relative(factor) => write!(f, "relative:{}", factor),
+            PenaltyStrategy::Adaptive => write!(f, "adaptive"),
+        }
+    }
+}
+
+// Accepts a number, "bound", "relative:<factor>" or "adaptive"
+impl FromStr for PenaltyStrategy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "bound" => Ok(PenaltyStrategy::Bound),
+            "adaptive" => Ok(PenaltyStrategy::Adaptive),
+            _ => match s.strip_prefix("relative:") {
+                Some(factor) => factor.parse().map(PenaltyStrategy::Relative).map_err(|_| ()),
+                None => s.parse().map(PenaltyStrategy::Fixed).map_err(|_| ()),
+            },
+        }
+    }
+}
+
+// Adaptive calibration multiplies λ by this after every infeasible round
+pub const ADAPTIVE_GROWTH: f64 = 4.0;
+pub const ADAPTIVE_MAX_ROUNDS: usize = 6;
+
+/// First λ tried by adaptive calibration: the largest distance, capped at
+/// `sufficient_penalty`. Instances with all-zero distances start at 1.
+pub fn initial_adaptive_penalty(data: &MdpData) -> f64 {
+    distance_scale(data).min(sufficient_penalty(data)).max(1.0)
+}
+
+/// Smallest λ that provably makes every optimum of the QUBO feasible:
+/// `1 + max_i sum_{j != i} |d_ij|`. With it, dropping the least contributing
+/// element of a too-large selection, or adding any element to a too-small one,
+/// always raises the penalized objective.
+pub fn sufficient_penalty(data: &MdpData) -> f64 {
+    let max_row = (0..data.n)
+        .map(|i| (0..data.n).filter(|&j| j != i).map(|j| data.get_dist(i, j).abs()).sum::<f64>())
+        .fold(0.0, f64::max);
+    1.0 + max_row
+}
+
+/// Largest absolute distance of the instance, the unit of `PenaltyStrategy::Relative`.
+pub fn distance_scale(data: &MdpData) -> f64 {
+    data.distances.iter().fold(0.0, |max: f64, d| max.max(d.abs()))
+}
+
+/// A solution of the penalized QUBO, before and after repair.
+pub struct QuboResult {
+    pub raw_selected: Vec<usize>, // Variables at 1 in the QUBO solution
+    pub raw_diversity: f64,
+    pub penalized_objective: f64, // Diversity minus the cardinality and side-constraint penalties
+    pub feasible: bool,           // Whether `raw_selected` has exactly k elements and meets the side constraints
+    pub repaired: bool,
+    pub selected: Vec<usize>, // `raw_selected`, repaired if requested and needed
+    pub diversity: f64,
+    pub penalty: f64, // λ the model was solved with
+}
+
+impl QuboResult {
+    /// Evaluates `raw_selected`; with `repair`, an infeasible selection is
+    /// fixed by `repair_selection`.
+    pub fn new(data: &MdpData, raw_selected: Vec<usize>, penalty: f64, repair: bool) -> Self {
+        let raw_diversity = calculate_diversity(&raw_selected, data);
+        let excess = raw_selected.len() as f64 - data.k as f64;
+        let violation = data.constraints.as_ref().map_or(0.0, |spec| side_violation(spec, &raw_selected));
+        let feasible = raw_selected.len() == data.k && violation == 0.0;
+
+        let repaired = repair && !feasible;
+        let selected = if repaired {
+            repair_selection(&raw_selected, data)
+        } else {
+            raw_selected.clone()
+        };
+        let diversity = calculate_diversity(&selected, data);
+
+        QuboResult {
+            penalized_objective: raw_diversity - penalty * (excess * excess + violation),
+            raw_selected,
+            raw_diversity,
+            feasible,
+            repaired,
+            selected,
+            diversity,
+            penalty,
+        }
+    }
+}
+
+// Side-constraint penalty of `selected` in units of λ, with the quota slack
+// bits at their best values: 1 per missing included or selected excluded
+// element, and the squared distance of every category count to its quota
+fn side_violation(spec: &SideConstraints, selected: &[usize]) -> f64 {
+    let mut violation = 0.0;
+    let mut chosen = vec![false; spec.required.len()];
+    for &i in selected {
+        chosen[i] = true;
+    }
+    let mut counts = vec![0; spec.quotas.len()];
+    for (i, &is_chosen) in chosen.iter().enumerate() {
+        if (is_chosen && spec.forbidden[i]) || (!is_chosen && spec.required[i]) {
+            violation += 1.0;
+        }
+        if let (true, Some(c)) = (is_chosen, spec.category[i]) {
+            counts[c] += 1;
+        }
+    }
+    for (&(min, max), &count) in spec.quotas.iter().zip(&counts) {
+        let outside = min.saturating_sub(count) + count.saturating_sub(max);
+        violation += (outside * outside) as f64;
+    }
+    violation
+}
+
+// Slack bits for every category whose quota restricts it, numbered from `first`
+fn quota_slack(spec: &SideConstraints, first: usize) -> Vec<QuotaSlack> {
+    let mut next = first;
+    let mut slack = Vec::new();
+    for (c, members) in spec.members().into_iter().enumerate() {
+        let (min, max) = spec.quotas[c];
+        let max = max.min(members.len());
+        if min == 0 && max == members.len() {
+            continue;
+        }
+        let mut bits = Vec::new();
+        let (mut remaining, mut coefficient) = (max.saturating_sub(min), 1);
+        while remaining > 0 {
+            let c = coefficient.min(remaining);
+            bits.push((next, c));
+            next += 1;
+            remaining -= c;
+            coefficient *= 2;
+        }
+        slack.push(QuotaSlack { members, max, bits });
+    }
+    slack
+}
+
+/// Text formats `QuboModel::write` can produce.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuboFormat {
+    Qbsolv,     // qbsolv `.qubo`: `p qubo` header, then `i i a_i` and `i j Q_ij` lines
+    Coordinate, // Sparse `i j value` lines (i <= j, diagonal = linear terms)
+    Dense,      // n rows of n values, upper triangular, diagonal = linear terms
+    Ising,      // Spin form: `i i h_i` and `i j J_ij` lines
+}
+
+impl FromStr for QuboFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.to_ascii_lowercase().as_str() {
+            "qbsolv" | "qubo" => Ok(QuboFormat::Qbsolv),
+            "coordinate" | "coo" => Ok(QuboFormat::Coordinate),
+            "dense" => Ok(QuboFormat::Dense),
+            "ising" => Ok(QuboFormat::Ising),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Penalized MDP as `minimize offset + sum_i a_i x_i + sum_{i<j} Q_ij x_i x_j`
+/// over binary `x`. Its value is the negated penalized diversity
+/// `sum_{i<j} d_ij x_i x_j - λ (sum x - k)^2`, following the minimization
+/// convention of qbsolv and annealing tools. Side constraints add penalties
+/// of the same λ, and the variables past the `elements` selection variables
+/// are the slack bits of the category quotas (see `QuotaSlack`).
+pub struct QuboModel {
+    pub n: usize, // All variables, slack bits included
+    pub elements: usize,
+    pub offset: f64,
+    pub linear: Vec<f64>,
+    pub quadratic: Vec<f64>, // Flattened n x n, only entries with i < j are used
+    pub slack: Vec<QuotaSlack>,
+}
+
+/// Slack bits turning the quota `min <= sum_{i in members} x_i <= max` of a
+/// category into `sum x_i + sum_b c_b y_b = max`, penalized by
+/// `λ (sum x_i + sum_b c_b y_b - max)^2`. The coefficients are powers of two,
+/// the last one capped so that they sum to `max - min`.
+#[derive(Clone, Debug)]
+pub struct QuotaSlack {
+    pub members: Vec<usize>,
+    pub max: usize,
+    pub bits: Vec<(usize, usize)>, // Slack variable and its coefficient
+}
+
+impl QuotaSlack {
+    // The bits at 1 for a category with `count` selected members, encoding the
+    // slack `max - count` clamped to the range the bits span
+    fn bits_for(&self, count: usize) -> impl Iterator<Item = usize> + '_ {
+        let range: usize = self.bits.iter().map(|&(_, c)| c).sum();
+        let mut slack = self.max.saturating_sub(count).min(range);
+        // Only the last coefficient may break the powers of two: take it first
+        let (powers, last) = self.bits.split_at(self.bits.len().saturating_sub(1));
+        let with_last = last.first().filter(|&&(_, c)| slack >= c).map(|&(var, c)| {
+            slack -= c;
+            var
+        });
+        with_last.into_iter().chain(powers.iter().filter(move |&&(_, c)| slack & c != 0).map(|&(var, _)| var))
+    }
+}
+
+impl QuboModel {
+    /// Expands `-(sum d_ij x_i x_j - λ (sum x - k)^2)` using `x_i^2 = x_i`,
+    /// plus `λ (1 - x_i)` for every included and `λ x_i` for every excluded
+    /// element, and the quota penalties of `QuotaSlack`.
+    pub fn mdp(data: &MdpData, penalty: f64) -> Self {
+        let elements = data.n;
+        let k = data.k as f64;
+        let slack = data.constraints.as_ref().map(|spec| quota_slack(spec, elements)).unwrap_or_default();
+        let n = elements + slack.iter().map(|quota| quota.bits.len()).sum::<usize>();
+        let mut quadratic = vec![0.0; n * n];
+        for i in 0..elements {
+            for j in (i + 1)..elements {
+                quadratic[i * n + j] = 2.0 * penalty - data.get_dist(i, j);
+            }
+        }
+        let mut linear = vec![penalty * (1.0 - 2.0 * k); elements];
+        linear.resize(n, 0.0);
+        let mut model = QuboModel {
+            n,
+            elements,
+            offset: penalty * k * k,
+            linear,
+            quadratic,
+            slack,
+        };
+        if let Some(spec) = &data.constraints {
+            model.add_side_penalties(spec, penalty);
+        }
+        model
+    }
+
+    /// The variables at 1 for the selection `selected`: its elements, and the
+    /// slack bits that minimize the quota penalties.
+    pub fn assignment(&self, selected: &[usize]) -> Vec<usize> {
+        let mut chosen = vec![false; self.elements];
+        let mut assignment = Vec::with_capacity(selected.len());
+        for &i in selected.iter().filter(|&&i| i < self.elements) {
+            chosen[i] = true;
+            assignment.push(i);
+        }
+        for quota in &self.slack {
+            let count = quota.members.iter().filter(|&&i| chosen[i]).count();
+            assignment.extend(quota.bits_for(count));
+        }
+        assignment
+    }
+
+    fn add_side_penalties(&mut self, spec: &SideConstraints, penalty: f64) {
+        for i in 0..self.elements {
+            if spec.required[i] {
+                self.linear[i] -= penalty;
+                self.offset += penalty;
+            }
+            if spec.forbidden[i] {
+                self.linear[i] += penalty;
+            }
+        }
+        // λ (sum_t c_t z_t - max)^2 = λ (sum_t (c_t^2 - 2 max c_t) z_t + 2 sum_{t<u} c_t c_u z_t z_u + max^2)
+        for quota in std::mem::take(&mut self.slack) {
+            let terms: Vec<(usize, f64)> = quota
+                .members
+                .iter()
+                .map(|&i| (i, 1.0))
+                .chain(quota.bits.iter().map(|&(var, c)| (var, c as f64)))
+                .collect();
+            let target = quota.max as f64;
+            self.offset += penalty * target * target;
+            for (idx, &(a, ca)) in terms.iter().enumerate() {
+                self.linear[a] += penalty * (ca * ca - 2.0 * target * ca);
+                for &(b, cb) in &terms[idx + 1..] {
+                    let (i, j) = if a < b { (a, b) } else { (b, a) };
+                    self.quadratic[i * self.n + j] += 2.0 * penalty * ca * cb;
+                }
+            }
+            self.slack.push(quota);
+        }
+    }
+
+    /// Coefficient of `x_i x_j`, for either order of `i != j`.
+    pub fn coupling(&self, i: usize, j: usize) -> f64 {
+        let (i, j) = if i < j { (i, j) } else { (j, i) };
+        self.quadratic[i * self.n + j]
+    }
+
+    /// Objective of the assignment with `x_i = 1` exactly for `selected`.
+    pub fn energy(&self, selected: &[usize]) -> f64 {
+        let mut energy = self.offset;
+        for (idx, &i) in selected.iter().enumerate() {
+            energy += self.linear[i];
+            for &j in &selected[idx + 1..] {
+                energy += self.coupling(i, j);
+            }
+        }
+        energy
+    }
+
+    /// The same objective over spins, with `x_i = (1 + s_i) / 2`.
+    pub fn to_ising(&self) -> IsingModel {
+        let n = self.n;
+        let mut ising = IsingModel {
+            n,
+            offset: self.offset,
+            h: vec![0.0; n],
+            coupling: vec![0.0; n * n],
+        };
+        for i in 0..n {
+            ising.h[i] += self.linear[i] / 2.0;
+            ising.offset += self.linear[i] / 2.0;
+            for j in (i + 1)..n {
+                let q = self.quadratic[i * n + j];
+                ising.coupling[i * n + j] = q / 4.0;
+                ising.h[i] += q / 4.0;
+                ising.h[j] += q / 4.0;
+                ising.offset += q / 4.0;
+            }
+        }
+        ising
+    }
+
+    pub fn write(&self, format: QuboFormat, out: impl Write) -> io::Result<()> {
+        let mut out = BufWriter::new(out);
+        match format {
+            QuboFormat::Qbsolv => self.write_qbsolv(&mut out)?,
+            QuboFormat::Coordinate => {
+                writeln!(out, "# minimize offset + sum Q_ij x_i x_j (i <= j), x binary")?;
+                writeln!(out, "# n = {}, offset = {}", self.n, self.offset)?;
+                write_entries(&mut out, self.n, &self.linear, &self.quadratic)?;
+            }
+            QuboFormat::Dense => self.write_dense(&mut out)?,
+            QuboFormat::Ising => self.to_ising().write_to(&mut out)?,
+        }
+        out.flush()
+    }
+
+    fn write_qbsolv(&self, out: &mut impl Write) -> io::Result<()> {
+        let n = self.n;
+        let nodes = self.linear.iter().filter(|&&a| a != 0.0).count();
+        let couplers = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.quadratic[i * n + j] != 0.0)
+            .count();
+        writeln!(out, "c Maximum Diversity Problem QUBO (minimize), constant offset {}", self.offset)?;
+        writeln!(out, "p qubo 0 {} {} {}", n, nodes, couplers)?;
+        write_entries(out, n, &self.linear, &self.quadratic)
+    }
+
+    fn write_dense(&self, out: &mut impl Write) -> io::Result<()> {
+        let n = self.n;
+        writeln!(out, "# upper triangular Q, diagonal = linear terms; offset = {}", self.offset)?;
+        for i in 0..n {
+            let row: Vec<String> = (0..n)
+                .map(|j| match j.cmp(&i) {
+                    std::cmp::Ordering::Less => "0".to_string(),
+                    std::cmp::Ordering::Equal => self.linear[i].to_string(),
+                    std::cmp::Ordering::Greater => self.quadratic[i * n + j].to_string(),
+                })
+                .collect();
+            writeln!(out, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// `minimize offset + sum_i h_i s_i + sum_{i<j} J_ij s_i s_j` over `s_i in {-1, +1}`.
+pub struct IsingModel {
+    pub n: usize,
+    pub offset: f64,
+    pub h: Vec<f64>,
+    pub coupling: Vec<f64>, // J, flattened n x n, only entries with i < j are used
+}
+
+impl IsingModel {
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "# minimize offset + sum h_i s_i + sum J_ij s_i s_j (i < j), s in {{-1, +1}}")?;
+        writeln!(out, "# lines: i i h_i / i j J_ij")?;
+        writeln!(out, "# n = {}, offset = {}", self.n, self.offset)?;
+        write_entries(out, self.n, &self.h, &self.coupling)
+    }
+}
+
+// Nonzero diagonal entries first, then nonzero pairs i < j, as `i j value` lines
+fn write_entries(out: &mut impl Write, n: usize, diagonal: &[f64], upper: &[f64]) -> io::Result<()> {
+    for (i, &value) in diagonal.iter().enumerate() {
+        if value != 0.0 {
+            writeln!(out, "{} {} {}", i, i, value)?;
+        }
+    }
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let value = upper[i * n + j];
+            if value != 0.0 {
+                writeln!(out, "{} {} {}", i, j, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{calculate_diversity, check_selection};
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Every subset of 0..n, as sorted selections
+    fn subsets(n: usize) -> impl Iterator<Item = Vec<usize>> {
+        (0u32..1 << n).map(move |mask| (0..n).filter(|&i| mask & (1 << i) != 0).collect())
+    }
+
+    fn ising_energy(ising: &IsingModel, selected: &[usize]) -> f64 {
+        let spins: Vec<f64> = (0..ising.n).map(|i| if selected.contains(&i) { 1.0 } else { -1.0 }).collect();
+        let mut energy = ising.offset;
+        for i in 0..ising.n {
+            energy += ising.h[i] * spins[i];
+            for j in (i + 1)..ising.n {
+                energy += ising.coupling[i * ising.n + j] * spins[i] * spins[j];
+            }
+        }
+        energy
+    }
+
+    fn instances(rng: &mut StdRng) -> Vec<MdpData> {
+        [InstanceKind::Euclidean, InstanceKind::Integer, InstanceKind::Signed]
+            .into_iter()
+            .map(|kind| generate_instance(&GeneratorConfig { n: 8, k: 3, kind, dims: 2 }, rng))
+            .collect()
+    }
+
+    #[test]
+    fn energy_is_negated_diversity_plus_cardinality_penalty() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for data in instances(&mut rng) {
+            for penalty in [0.0, 1.5, sufficient_penalty(&data)] {
+                let model = QuboModel::mdp(&data, penalty);
+                for selected in subsets(data.n) {
+                    let excess = selected.len() as f64 - data.k as f64;
+                    let expected = -calculate_diversity(&selected, &data) + penalty * excess * excess;
+                    let energy = model.energy(&selected);
+                    let context = format!("λ={} {:?}", penalty, selected);
+                    assert!((energy - expected).abs() < 1e-6, "{}: {} != {}", context, energy, expected);
+                }
+            }
+        }
+    }
+
+    // Element 0 included, 7 excluded and 1..=5 a category of 1 or 2, which takes a slack bit
+    fn constrained(mut data: MdpData) -> MdpData {
+        let mut spec = SideConstraints::new(data.n);
+        spec.required[0] = true;
+        spec.forbidden[7] = true;
+        for i in 1..=5 {
+            spec.category[i] = Some(0);
+        }
+        spec.quotas.push((1, 2));
+        data.constraints = Some(spec);
+        data
+    }
+
+    #[test]
+    fn side_penalties_vanish_exactly_on_feasible_selections() {
+        let mut rng = StdRng::seed_from_u64(14);
+        for data in instances(&mut rng).into_iter().map(constrained) {
+            let model = QuboModel::mdp(&data, 3.0);
+            assert_eq!(model.n, data.n + 1);
+            for selected in subsets(data.n).filter(|selected| selected.len() == data.k) {
+                let penalty = model.energy(&model.assignment(&selected)) + calculate_diversity(&selected, &data);
+                let feasible = check_selection(&selected, &data).is_empty();
+                assert_eq!(penalty.abs() < 1e-6, feasible, "{:?}: penalty {}", selected, penalty);
+            }
+        }
+    }
+
+    #[test]
+    fn ising_model_has_the_same_energy_at_every_assignment() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let mut all = instances(&mut rng);
+        all.extend(instances(&mut rng).into_iter().map(constrained));
+        for data in all {
+            let model = QuboModel::mdp(&data, initial_adaptive_penalty(&data));
+            let ising = model.to_ising();
+            for selected in subsets(model.n) {
+                let (qubo, spin) = (model.energy(&selected), ising_energy(&ising, &selected));
+                assert!((qubo - spin).abs() < 1e-6, "{:?}: {} != {}", selected, qubo, spin);
+            }
+        }
+    }
+
+    #[test]
+    fn sufficient_penalty_makes_the_minimum_feasible() {
+        let mut rng = StdRng::seed_from_u64(13);
+        for data in instances(&mut rng) {
+            let model = QuboModel::mdp(&data, sufficient_penalty(&data));
+            let best = subsets(data.n).min_by(|a, b| model.energy(a).total_cmp(&model.energy(b))).unwrap();
+            assert_eq!(best.len(), data.k, "{:?}", best);
+        }
+    }
+}
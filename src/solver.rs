@@ -0,0 +1,431 @@
+//! The `Solver` trait, run budgets, and the registry of named solvers.
+
+use crate::evaluation::Objective;
+use crate::parser::MdpData;
+use crate::solver_branch_bound::BranchBoundSolver;
+use crate::solver_capacitated::{CapacitatedConfig, CapacitatedMethod, CapacitatedSolver};
+use crate::solver_direct::DirectSolver;
+use crate::solver_grasp::GraspSolver;
+use crate::solver_local_search::{LocalSearchConfig, LocalSearchMethod, LocalSearchSolver};
+use crate::solver_population::GeneticSolver;
+use crate::solver_qubo_heuristic::{QuboHeuristicConfig, QuboHeuristicSolver, QuboSearchMethod};
+use crate::solver_vns::{VnsConfig, VnsMethod, VnsSolver};
+#[cfg(feature = "gurobi")]
+use crate::solver_mip::{MipConfig, MipFormulation, MipSolver};
+#[cfg(feature = "gurobi")]
+use crate::solver_pipeline::PipelineSolver;
+#[cfg(feature = "gurobi")]
+use crate::solver_qubo::QuboSolver;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant, TryFromFloatSecsError};
+
+/// Resources a solver is allowed to spend on one instance. Every limit is
+/// optional and a run stops as soon as any one of them is reached; limits are
+/// checked between moves, so a run may overshoot by one neighborhood sweep.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    pub time_limit: Option<Duration>,
+    pub max_evaluations: Option<u64>, // Move or solution evaluations
+    pub max_iterations: Option<u64>,  // Outer iterations: moves, GRASP starts, generations, ...
+    pub target_value: Option<f64>,    // Stop once a solution at least this good is found
+}
+
+impl Budget {
+    /// A time limit of `seconds`. Negative or NaN times leave no time at all
+    /// and times too large for a `Duration` (including infinity) leave no limit;
+    /// `try_with_time_limit` rejects both instead.
+    pub fn with_time_limit(seconds: f64) -> Self {
+        let time_limit = match Duration::try_from_secs_f64(seconds) {
+            Ok(limit) => Some(limit),
+            Err(_) if seconds > 0.0 => None,
+            Err(_) => Some(Duration::ZERO),
+        };
+        Budget { time_limit, ..Default::default() }
+    }
+
+    /// A time limit of `seconds`, or an error unless it is finite, not negative
+    /// and fits a `Duration`.
+    pub fn try_with_time_limit(seconds: f64) -> Result<Self, TryFromFloatSecsError> {
+        Ok(Budget {
+            time_limit: Some(Duration::try_from_secs_f64(seconds)?),
+            ..Default::default()
+        })
+    }
+
+    /// This budget for a search that maximizes `objective.score`: the target
+    /// is negated for minimized objectives.
+    pub fn scored(&self, objective: Objective) -> Budget {
+        Budget {
+            target_value: self.target_value.map(|target| objective.score(target)),
+            ..self.clone()
+        }
+    }
+}
+
+/// Tracks how much of a `Budget` one run has consumed.
+pub struct BudgetTracker<'a> {
+    budget: &'a Budget,
+    start: Instant,
+    evaluations: u64,
+    iterations: u64,
+    best_value: f64,
+}
+
+impl<'a> BudgetTracker<'a> {
+    pub fn start(budget: &'a Budget) -> Self {
+        BudgetTracker {
+            budget,
+            start: Instant::now(),
+            evaluations: 0,
+            iterations: 0,
+            best_value: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn add_evaluations(&mut self, count: usize) {
+        self.evaluations += count as u64;
+    }
+
+    pub fn next_iteration(&mut self) {
+        self.iterations += 1;
+    }
+
+    /// Records the value of a solution the solver has found, for the target check.
+    pub fn observe(&mut self, value: f64) {
+        self.best_value = self.best_value.max(value);
+    }
+
+    pub fn exhausted(&self) -> bool {
+        let budget = self.budget;
+        budget.max_iterations.is_some_and(|max| self.iterations >= max)
+            || budget.max_evaluations.is_some_and(|max| self.evaluations >= max)
+            || budget.target_value.is_some_and(|target| self.best_value >= target)
+            || budget.time_limit.is_some_and(|limit| self.start.elapsed() >= limit)
+    }
+}
+
+/// What every solver hands back to the benchmark driver.
+#[derive(Clone, Debug)]
+pub struct SolveOutcome {
+    pub selected: Vec<usize>,
+    pub diversity: f64, // Value of the solver's objective, the pairwise sum unless configured otherwise
+    pub success: bool,
+    pub seed: Option<u64>, // Seed the run used, if it is randomized
+    pub proven_optimal: bool, // Set by exact solvers that finished their search
+    pub details: Vec<(&'static str, Detail)>, // Solver-specific figures for the JSON results
+}
+
+/// A solver-specific value reported next to the diversity.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Detail {
+    Flag(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl From<bool> for Detail {
+    fn from(flag: bool) -> Self {
+        Detail::Flag(flag)
+    }
+}
+
+impl From<f64> for Detail {
+    fn from(number: f64) -> Self {
+        Detail::Number(number)
+    }
+}
+
+impl From<usize> for Detail {
+    fn from(number: usize) -> Self {
+        Detail::Number(number as f64)
+    }
+}
+
+impl From<String> for Detail {
+    fn from(text: String) -> Self {
+        Detail::Text(text)
+    }
+}
+
+impl fmt::Display for Detail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Detail::Flag(flag) => write!(f, "{}", flag),
+            Detail::Number(number) => write!(f, "{}", number),
+            Detail::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl SolveOutcome {
+    pub fn new(selected: Vec<usize>, diversity: f64) -> Self {
+        SolveOutcome {
+            selected,
+            diversity,
+            success: true,
+            seed: None,
+            proven_optimal: false,
+            details: Vec::new(),
+        }
+    }
+
+    pub fn failed() -> Self {
+        SolveOutcome {
+            selected: Vec::new(),
+            diversity: 0.0,
+            success: false,
+            seed: None,
+            proven_optimal: false,
+            details: Vec::new(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Records a non-default objective, so results are not read as pairwise sums.
+    pub fn with_objective(self, objective: Objective) -> Self {
+        match objective {
+            Objective::MaxSum => self,
+            _ => self.with_detail("objective", objective.name().to_string()),
+        }
+    }
+
+    pub fn with_detail(mut self, key: &'static str, value: impl Into<Detail>) -> Self {
+        self.details.push((key, value.into()));
+        self
+    }
+}
+
+/// Common interface of every algorithm, used by the registry and the benchmark driver.
+pub trait Solver {
+    /// Name used in the registry, the summary tables and the JSON results.
+    fn name(&self) -> &str;
+
+    /// Current parameter values, for logging.
+    fn params(&self) -> Vec<(&'static str, String)>;
+
+    /// Sets a parameter from its textual value (e.g. `("alpha", "0.3")`).
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError>;
+
+    /// Runs the solver on `data` until it converges or `budget` is exhausted.
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome;
+
+    /// Like `solve`, but seeded with a known selection (e.g. a heuristic's
+    /// incumbent). Solvers that cannot use one ignore it.
+    fn solve_from(&self, data: &MdpData, budget: &Budget, _start: &[usize]) -> SolveOutcome {
+        self.solve(data, budget)
+    }
+}
+
+/// Error returned by `Solver::set_param`.
+#[derive(Debug)]
+pub enum ParamError {
+    Unknown { solver: String, key: String },
+    InvalidValue { key: String, value: String },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Unknown { solver, key } => {
+                write!(f, "solver '{}' has no parameter '{}'", solver, key)
+            }
+            ParamError::InvalidValue { key, value } => {
+                write!(f, "invalid value '{}' for parameter '{}'", value, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// Parses a parameter value, mapping failures to `ParamError::InvalidValue`.
+pub fn parse_param<T: FromStr>(key: &str, value: &str) -> Result<T, ParamError> {
+    value.parse().map_err(|_| ParamError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Parses a fraction in `0.0..=1.0`, such as a GRASP RCL width.
+pub fn parse_fraction(key: &str, value: &str) -> Result<f64, ParamError> {
+    let fraction: f64 = parse_param(key, value)?;
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(ParamError::InvalidValue { key: key.to_string(), value: value.to_string() });
+    }
+    Ok(fraction)
+}
+
+/// Seed for one run: the configured one, or a fresh random one that the outcome records.
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::thread_rng().gen())
+}
+
+/// Textual form of an optional seed, for `Solver::params`.
+pub fn seed_param(seed: Option<u64>) -> String {
+    seed.map_or_else(|| "random".to_string(), |seed| seed.to_string())
+}
+
+/// The `ParamError::Unknown` for `key` on `solver`.
+pub fn unknown_param(solver: &dyn Solver, key: &str) -> ParamError {
+    ParamError::Unknown {
+        solver: solver.name().to_string(),
+        key: key.to_string(),
+    }
+}
+
+// ============ Registry ============
+
+type SolverFactory = fn() -> Box<dyn Solver>;
+
+// Solvers that need the `gurobi` feature, registered only when it is enabled
+const GUROBI_SOLVERS: &[&str] = &[
+    "QUBO",
+    "MIP-Quad",
+    "MIP-Glover",
+    "MIP-KGD",
+    "MIP-MaxMin",
+    "MIP-MaxMean",
+    "MIP-MaxMinSum",
+    "MIP-MinDiff",
+    "CDP-MIP",
+    "Tabu+QUBO",
+    "Tabu+MIP-Quad",
+];
+
+const REGISTRY: &[(&str, SolverFactory)] = &[
+    #[cfg(feature = "gurobi")]
+    ("QUBO", || Box::new(QuboSolver::default())),
+    ("GRASP", || Box::new(GraspSolver::default())),
+    ("LS-First", || {
+        Box::new(LocalSearchSolver::new(LocalSearchConfig {
+            method: LocalSearchMethod::FirstImprovement,
+            ..Default::default()
+        }))
+    }),
+    ("LS-Best", || {
+        Box::new(LocalSearchSolver::new(LocalSearchConfig {
+            method: LocalSearchMethod::BestImprovement,
+            ..Default::default()
+        }))
+    }),
+    ("Tabu", || {
+        Box::new(LocalSearchSolver::new(LocalSearchConfig {
+            method: LocalSearchMethod::TabuSearch { tabu_tenure: 10 },
+            max_iters: 1000,
+            ..Default::default()
+        }))
+    }),
+    ("GA", || Box::new(GeneticSolver::default())),
+    ("VNS", || Box::new(VnsSolver::default())),
+    ("SVNS", || {
+        Box::new(VnsSolver::new(VnsConfig {
+            method: VnsMethod::Skewed { alpha: 0.02 },
+            ..Default::default()
+        }))
+    }),
+    ("Direct", || Box::new(DirectSolver::default())),
+    ("BnB", || Box::new(BranchBoundSolver::default())),
+    ("QUBO-Tabu", || Box::new(QuboHeuristicSolver::default())),
+    ("QUBO-SA", || {
+        Box::new(QuboHeuristicSolver::new(QuboHeuristicConfig {
+            method: QuboSearchMethod::Annealing { start_temperature: 1.0, end_temperature: 0.001 },
+            max_iters: 500_000,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("MIP-Quad", || Box::new(MipSolver::new(MipConfig::default()))),
+    #[cfg(feature = "gurobi")]
+    ("MIP-Glover", || {
+        Box::new(MipSolver::new(MipConfig {
+            formulation: MipFormulation::Glover,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("MIP-KGD", || {
+        Box::new(MipSolver::new(MipConfig {
+            formulation: MipFormulation::KuoGloverDhir,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("MIP-MaxMin", || {
+        Box::new(MipSolver::new(MipConfig {
+            objective: Objective::MaxMin,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("MIP-MaxMean", || {
+        Box::new(MipSolver::new(MipConfig {
+            objective: Objective::MaxMean,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("MIP-MaxMinSum", || {
+        Box::new(MipSolver::new(MipConfig {
+            objective: Objective::MaxMinSum,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("MIP-MinDiff", || {
+        Box::new(MipSolver::new(MipConfig {
+            objective: Objective::MinDiff,
+            ..Default::default()
+        }))
+    }),
+    ("CDP-Greedy", || {
+        Box::new(CapacitatedSolver::new(CapacitatedConfig {
+            method: CapacitatedMethod::Greedy,
+            ..Default::default()
+        }))
+    }),
+    ("CDP-LS", || Box::new(CapacitatedSolver::default())),
+    ("CDP-Tabu", || {
+        Box::new(CapacitatedSolver::new(CapacitatedConfig {
+            method: CapacitatedMethod::TabuSearch { tabu_tenure: 10 },
+            max_iters: 1000,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("CDP-MIP", || {
+        Box::new(CapacitatedSolver::new(CapacitatedConfig {
+            method: CapacitatedMethod::Mip,
+            ..Default::default()
+        }))
+    }),
+    #[cfg(feature = "gurobi")]
+    ("Tabu+QUBO", || Box::new(PipelineSolver::new("Tabu", "QUBO").unwrap())),
+    #[cfg(feature = "gurobi")]
+    ("Tabu+MIP-Quad", || Box::new(PipelineSolver::new("Tabu", "MIP-Quad").unwrap())),
+];
+
+/// Names of all registered solvers, in registry order.
+pub fn solver_names() -> Vec<&'static str> {
+    REGISTRY.iter().map(|(name, _)| *name).collect()
+}
+
+/// Whether `name` is a Gurobi-backed solver that this build was compiled without.
+pub fn needs_gurobi_feature(name: &str) -> bool {
+    !cfg!(feature = "gurobi") && GUROBI_SOLVERS.iter().any(|solver| solver.eq_ignore_ascii_case(name))
+}
+
+/// Creates a solver with default parameters by (case-insensitive) name.
+pub fn create_solver(name: &str) -> Option<Box<dyn Solver>> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| registered.eq_ignore_ascii_case(name))
+        .map(|(_, factory)| factory())
+}
@@ -0,0 +1,384 @@
+//! Exact branch and bound for Max-Sum MDP, with the bounds of Martí, Gallego
+//! and Duarte (2010).
+
+use crate::evaluation::ConstraintCounts;
+use crate::parser::MdpData;
+use crate::solver::{
+    parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError,
+    SolveOutcome, Solver,
+};
+use crate::solver_grasp::{solve_grasp, GraspConfig};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Nodes whose bound does not beat the incumbent by more than this are pruned
+const BOUND_TOLERANCE: f64 = 1e-6;
+
+/// Parameters of the branch and bound.
+pub struct BranchBoundConfig {
+    pub max_nodes: Option<u64>,  // None = no limit besides the budget
+    pub warm_start_iters: usize, // GRASP starts used for the initial incumbent
+    pub seed: Option<u64>,       // Only affects the warm start
+}
+
+impl Default for BranchBoundConfig {
+    fn default() -> Self {
+        BranchBoundConfig {
+            max_nodes: None,
+            warm_start_iters: 20,
+            seed: None,
+        }
+    }
+}
+
+/// Best selection found, plus whether the search finished and so proved it optimal.
+pub struct BranchBoundResult {
+    pub selected: Vec<usize>,
+    pub diversity: f64,
+    pub nodes: u64,
+    pub proven_optimal: bool,
+}
+
+#[derive(Default)]
+pub struct BranchBoundSolver {
+    pub config: BranchBoundConfig,
+}
+
+impl Solver for BranchBoundSolver {
+    fn name(&self) -> &str {
+        "BnB"
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("max_nodes", self.config.max_nodes.map_or_else(|| "none".to_string(), |max| max.to_string())),
+            ("warm_start_iters", self.config.warm_start_iters.to_string()),
+            ("seed", seed_param(self.config.seed)),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "max_nodes" if value == "none" => self.config.max_nodes = None,
+            "max_nodes" => self.config.max_nodes = Some(parse_param(key, value)?),
+            "warm_start_iters" => self.config.warm_start_iters = parse_param(key, value)?,
+            "seed" => self.config.seed = Some(parse_param(key, value)?),
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let result = solve_branch_bound(data, &self.config, budget, &mut rng);
+        if result.selected.len() != data.k {
+            return SolveOutcome::failed().with_seed(seed);
+        }
+        SolveOutcome {
+            proven_optimal: result.proven_optimal,
+            ..SolveOutcome::new(result.selected, result.diversity).with_seed(seed)
+        }
+    }
+}
+
+/// Depth-first search over include/exclude decisions. A node with partial
+/// selection S and r elements still to choose is bounded by
+/// `value(S) + sum of the r largest f(v)` over free v, where
+/// `f(v) = d(v, S) + 1/2 * (sum of the r - 1 largest d(v, w), w free)`.
+/// Side constraints fix the included and excluded elements at the root, and
+/// an element is only included while its category has room and the
+/// categories below their minimum can still be filled.
+// One budget iteration is one explored node
+pub fn solve_branch_bound(
+    data: &MdpData,
+    config: &BranchBoundConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> BranchBoundResult {
+    let (best, best_value) = if config.warm_start_iters > 0 && data.k > 0 {
+        let grasp = GraspConfig {
+            iterations: config.warm_start_iters,
+            ..Default::default()
+        };
+        // Node and evaluation limits are meant for the tree, not the warm start
+        let warm_budget = Budget {
+            time_limit: budget.time_limit,
+            ..Default::default()
+        };
+        match solve_grasp(data, &grasp, &warm_budget, rng) {
+            // The construction can dead-end short of k under side constraints
+            (selected, value) if selected.len() == data.k => (selected, value),
+            _ => (Vec::new(), f64::NEG_INFINITY),
+        }
+    } else {
+        (Vec::new(), f64::NEG_INFINITY)
+    };
+
+    let mut search = Search::new(data, budget, config.max_nodes, best, best_value);
+    search.tracker.observe(search.best_value);
+    search.explore();
+
+    BranchBoundResult {
+        selected: search.best,
+        diversity: search.best_value,
+        nodes: search.nodes,
+        proven_optimal: !search.aborted,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Free,
+    Included,
+    Excluded,
+}
+
+struct Search<'a> {
+    data: &'a MdpData,
+    tracker: BudgetTracker<'a>,
+    max_nodes: Option<u64>,
+    by_distance: Vec<Vec<usize>>, // Other elements of each element, farthest first
+    status: Vec<Status>,
+    free_count: usize,
+    partial: Vec<usize>,
+    partial_value: f64,
+    to_partial: Vec<f64>, // Summed distance of each element to `partial`
+    counts: ConstraintCounts<'a>,
+    scores: Vec<(f64, usize)>, // Scratch space for the bound
+    best: Vec<usize>,
+    best_value: f64,
+    nodes: u64,
+    aborted: bool,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        data: &'a MdpData,
+        budget: &'a Budget,
+        max_nodes: Option<u64>,
+        best: Vec<usize>,
+        best_value: f64,
+    ) -> Self {
+        let n = data.n;
+        let by_distance = (0..n)
+            .map(|v| {
+                let mut others: Vec<usize> = (0..n).filter(|&w| w != v).collect();
+                others.sort_by(|&a, &b| data.get_dist(v, b).total_cmp(&data.get_dist(v, a)));
+                others
+            })
+            .collect();
+
+        let mut search = Search {
+            data,
+            tracker: BudgetTracker::start(budget),
+            max_nodes,
+            by_distance,
+            status: vec![Status::Free; n],
+            free_count: n,
+            partial: Vec::with_capacity(data.k),
+            partial_value: 0.0,
+            to_partial: vec![0.0; n],
+            counts: ConstraintCounts::new(data, &[]),
+            scores: Vec::with_capacity(n),
+            best,
+            best_value,
+            nodes: 0,
+            aborted: false,
+        };
+        if let Some(spec) = &data.constraints {
+            for v in 0..n {
+                if spec.forbidden[v] {
+                    search.status[v] = Status::Excluded;
+                    search.free_count -= 1;
+                } else if spec.required[v] {
+                    search.include(v);
+                }
+            }
+        }
+        search
+    }
+
+    fn explore(&mut self) {
+        if self.aborted {
+            return;
+        }
+        let remaining = self.data.k - self.partial.len();
+        if remaining == 0 {
+            if self.counts.deficit() == 0 && self.partial_value > self.best_value {
+                self.best_value = self.partial_value;
+                self.best = self.partial.clone();
+                self.tracker.observe(self.best_value);
+            }
+            return;
+        }
+        if self.free_count < remaining || self.counts.deficit() > remaining {
+            return;
+        }
+
+        if self.tracker.exhausted() || self.max_nodes.is_some_and(|max| self.nodes >= max) {
+            self.aborted = true;
+            return;
+        }
+        self.nodes += 1;
+        self.tracker.next_iteration();
+        self.tracker.add_evaluations(self.free_count);
+
+        let (bound, branch) = self.bound(remaining);
+        if bound <= self.best_value + BOUND_TOLERANCE {
+            return;
+        }
+
+        // The most promising element is included first, so good leaves are reached early
+        if self.counts.can_extend(branch, remaining - 1) {
+            self.include(branch);
+            self.explore();
+            self.undo_include(branch);
+        }
+
+        self.status[branch] = Status::Excluded;
+        self.free_count -= 1;
+        self.explore();
+        self.status[branch] = Status::Free;
+        self.free_count += 1;
+    }
+
+    // Upper bound of the node, and the free element with the largest f(v)
+    fn bound(&mut self, remaining: usize) -> (f64, usize) {
+        self.scores.clear();
+        for v in 0..self.data.n {
+            if self.status[v] != Status::Free {
+                continue;
+            }
+            let mut among_free = 0.0;
+            let mut taken = 0;
+            for &w in &self.by_distance[v] {
+                if taken + 1 == remaining {
+                    break;
+                }
+                if self.status[w] == Status::Free {
+                    among_free += self.data.get_dist(v, w);
+                    taken += 1;
+                }
+            }
+            self.scores.push((self.to_partial[v] + 0.5 * among_free, v));
+        }
+
+        let branch = self.scores.iter().max_by(|a, b| a.0.total_cmp(&b.0)).map(|&(_, v)| v).unwrap();
+        self.scores.select_nth_unstable_by(remaining - 1, |a, b| b.0.total_cmp(&a.0));
+        let bound = self.partial_value + self.scores[..remaining].iter().map(|&(f, _)| f).sum::<f64>();
+        (bound, branch)
+    }
+
+    fn include(&mut self, v: usize) {
+        self.status[v] = Status::Included;
+        self.free_count -= 1;
+        self.partial.push(v);
+        self.partial_value += self.to_partial[v];
+        self.counts.add(v);
+        self.update_distances(v, 1.0);
+    }
+
+    fn undo_include(&mut self, v: usize) {
+        self.update_distances(v, -1.0);
+        self.counts.remove(v);
+        self.partial_value -= self.to_partial[v];
+        self.partial.pop();
+        self.free_count += 1;
+        self.status[v] = Status::Free;
+    }
+
+    fn update_distances(&mut self, v: usize, sign: f64) {
+        for u in 0..self.data.n {
+            if u != v {
+                self.to_partial[u] += sign * self.data.get_dist(v, u);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{calculate_diversity, check_selection};
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+    use crate::parser::SideConstraints;
+
+    // Best pairwise sum over all k-subsets that meet the side constraints
+    fn brute_force(data: &MdpData) -> f64 {
+        (0u32..1 << data.n)
+            .filter(|mask| mask.count_ones() as usize == data.k)
+            .map(|mask| (0..data.n).filter(|&i| mask & (1 << i) != 0).collect::<Vec<usize>>())
+            .filter(|selected| check_selection(selected, data).is_empty())
+            .map(|selected| calculate_diversity(&selected, data))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    #[test]
+    fn matches_brute_force_on_small_instances() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for kind in [InstanceKind::Euclidean, InstanceKind::Integer, InstanceKind::Signed] {
+            for n in [6, 9, 12] {
+                for k in [1, 2, n / 3, n / 2, n - 1] {
+                    let data = generate_instance(&GeneratorConfig { n, k, kind, dims: 2 }, &mut rng);
+                    let optimum = brute_force(&data);
+                    // Without a warm start the tree alone has to find the optimum
+                    for warm_start_iters in [0, 5] {
+                        let config = BranchBoundConfig { warm_start_iters, ..Default::default() };
+                        let result = solve_branch_bound(&data, &config, &Budget::default(), &mut rng);
+                        let context = format!("{:?} n={} k={} warm={}", kind, n, k, warm_start_iters);
+                        assert!(result.proven_optimal, "{}: search did not finish", context);
+                        assert_eq!(result.selected.len(), k, "{}", context);
+                        let value = result.diversity;
+                        assert!((value - optimum).abs() < 1e-6, "{}: {} != {}", context, value, optimum);
+                        let recomputed = calculate_diversity(&result.selected, &data);
+                        assert!((recomputed - result.diversity).abs() < 1e-6, "{}: reported value", context);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_brute_force_under_side_constraints() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for kind in [InstanceKind::Integer, InstanceKind::Signed] {
+            for k in 1..=5 {
+                let mut data = generate_instance(&GeneratorConfig { n: 10, k, kind, dims: 2 }, &mut rng);
+                // Element 9 excluded, 1..=4 a category of one or two and 5..=7 one of at most one,
+                // so no selection has more than five elements
+                let mut spec = SideConstraints::new(data.n);
+                spec.forbidden[9] = true;
+                for i in 1..=7 {
+                    spec.category[i] = Some(if i <= 4 { 0 } else { 1 });
+                }
+                spec.quotas = vec![(1, 2), (0, 1)];
+                // Element 0 included from k = 2 on, so it does not fill a selection of one
+                spec.required[0] = k >= 2;
+                assert!(spec.check(k, k).is_ok(), "k={}", k);
+                data.constraints = Some(spec);
+
+                let optimum = brute_force(&data);
+                for warm_start_iters in [0, 5] {
+                    let config = BranchBoundConfig { warm_start_iters, ..Default::default() };
+                    let result = solve_branch_bound(&data, &config, &Budget::default(), &mut rng);
+                    let context = format!("{:?} k={} warm={}", kind, k, warm_start_iters);
+                    assert!(result.proven_optimal, "{}: search did not finish", context);
+                    let violations = check_selection(&result.selected, &data);
+                    assert!(violations.is_empty(), "{}: {:?}", context, violations);
+                    let value = result.diversity;
+                    assert!((value - optimum).abs() < 1e-6, "{}: {} != {}", context, value, optimum);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn node_limit_leaves_the_result_unproven() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let data = generate_instance(&GeneratorConfig { n: 12, k: 6, kind: InstanceKind::Uniform, dims: 2 }, &mut rng);
+        let config = BranchBoundConfig { max_nodes: Some(3), warm_start_iters: 0, ..Default::default() };
+        let result = solve_branch_bound(&data, &config, &Budget::default(), &mut rng);
+        assert!(!result.proven_optimal);
+    }
+}
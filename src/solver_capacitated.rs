@@ -0,0 +1,594 @@
+//! Capacitated (Generalized) Diversity Problem: maximize the pairwise sum over
+//! selections of `min_size..=max_size` elements whose total weight fits the
+//! capacity (see `CapacitySpec`). A greedy randomized construction is
+//! improved by add, drop and swap moves that keep the selection feasible;
+//! with the `gurobi` feature the quadratic MIP is solved exactly.
+//! Instances without capacity lines are solved as the plain MDP.
+
+use crate::evaluation::{SolutionState, CAPACITY_TOLERANCE};
+use crate::parser::{CapacitySpec, MdpData};
+use crate::solver::{
+    parse_fraction, parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError,
+    SolveOutcome, Solver,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Parameters of the capacitated solvers.
+pub struct CapacitatedConfig {
+    pub method: CapacitatedMethod,
+    pub alpha: f64,        // RCL width of the construction: 0 is greedy, 1 is random
+    pub max_iters: usize,  // Construction starts for LocalSearch, moves for TabuSearch
+    pub seed: Option<u64>, // None draws a fresh seed per run
+}
+
+/// What follows the construction.
+pub enum CapacitatedMethod {
+    Greedy,      // Nothing: a single construction
+    LocalSearch, // Best-improvement descent after every construction start
+    TabuSearch { tabu_tenure: usize },
+    #[cfg(feature = "gurobi")]
+    Mip, // The quadratic MIP solved by Gurobi instead
+}
+
+impl Default for CapacitatedConfig {
+    fn default() -> Self {
+        CapacitatedConfig {
+            method: CapacitatedMethod::LocalSearch,
+            alpha: 0.3,
+            max_iters: 50,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CapacitatedSolver {
+    pub config: CapacitatedConfig,
+}
+
+impl CapacitatedSolver {
+    pub fn new(config: CapacitatedConfig) -> Self {
+        CapacitatedSolver { config }
+    }
+}
+
+impl Solver for CapacitatedSolver {
+    fn name(&self) -> &str {
+        match self.config.method {
+            CapacitatedMethod::Greedy => "CDP-Greedy",
+            CapacitatedMethod::LocalSearch => "CDP-LS",
+            CapacitatedMethod::TabuSearch { .. } => "CDP-Tabu",
+            #[cfg(feature = "gurobi")]
+            CapacitatedMethod::Mip => "CDP-MIP",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("alpha", self.config.alpha.to_string()),
+            ("max_iters", self.config.max_iters.to_string()),
+            ("seed", seed_param(self.config.seed)),
+        ];
+        if let CapacitatedMethod::TabuSearch { tabu_tenure } = self.config.method {
+            params.push(("tabu_tenure", tabu_tenure.to_string()));
+        }
+        params
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("alpha", _) => self.config.alpha = parse_fraction(key, value)?,
+            ("max_iters", _) => self.config.max_iters = parse_param(key, value)?,
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("tabu_tenure", CapacitatedMethod::TabuSearch { tabu_tenure }) => {
+                *tabu_tenure = parse_param(key, value)?
+            }
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let plain;
+        let spec = match &data.capacity {
+            Some(spec) => spec,
+            None => {
+                plain = CapacitySpec::cardinality(data.n, data.k);
+                &plain
+            }
+        };
+        let seed = resolve_seed(self.config.seed);
+
+        #[cfg(feature = "gurobi")]
+        if let CapacitatedMethod::Mip = self.config.method {
+            return solve_mip(data, spec, seed, budget);
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        match solve_capacitated(data, spec, &self.config, budget, &mut rng) {
+            Some((selected, diversity)) => {
+                let weight = spec.weight_of(&selected);
+                SolveOutcome::new(selected, diversity).with_seed(seed).with_detail("weight", weight)
+            }
+            None => SolveOutcome::failed().with_seed(seed),
+        }
+    }
+}
+
+#[cfg(feature = "gurobi")]
+fn solve_mip(data: &MdpData, spec: &CapacitySpec, seed: u64, budget: &Budget) -> SolveOutcome {
+    use crate::solver_mip::{solve_capacitated_mip, MipConfig};
+
+    let config = MipConfig { seed: Some(seed), ..Default::default() };
+    let run = match solve_capacitated_mip(data, spec, &config, budget) {
+        Ok(run) => run,
+        Err(e) => return SolveOutcome::failed().with_detail("error", e.to_string()),
+    };
+    let outcome = match run.solution {
+        Some((selected, diversity)) => {
+            let weight = spec.weight_of(&selected);
+            SolveOutcome {
+                seed: Some(seed),
+                proven_optimal: run.proven_optimal,
+                ..SolveOutcome::new(selected, diversity)
+            }
+            .with_detail("weight", weight)
+        }
+        None => SolveOutcome::failed(),
+    };
+    run.stats.annotate(outcome)
+}
+
+/// Constructs feasible selections and improves them with the configured
+/// method; returns the best selection and its diversity, or `None` if no
+/// construction reached the minimum size within the capacity.
+// One budget iteration is one construction start or one tabu move
+pub fn solve_capacitated(
+    data: &MdpData,
+    spec: &CapacitySpec,
+    config: &CapacitatedConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> Option<(Vec<usize>, f64)> {
+    let mut tracker = BudgetTracker::start(budget);
+    let starts = match config.method {
+        CapacitatedMethod::LocalSearch => config.max_iters.max(1),
+        _ => 1,
+    };
+
+    let mut best: Option<CapacityState> = None;
+    for _ in 0..starts {
+        let mut state = greedy_randomized_construction(data, spec, config.alpha, &mut tracker, rng);
+        if state.len() < spec.min_size {
+            continue;
+        }
+        match config.method {
+            CapacitatedMethod::LocalSearch => descend(&mut state, &mut tracker),
+            CapacitatedMethod::TabuSearch { tabu_tenure } => {
+                state = tabu_search(state, config.max_iters, tabu_tenure, &mut tracker)
+            }
+            _ => {}
+        }
+        tracker.observe(state.value());
+        tracker.next_iteration();
+        if best.as_ref().is_none_or(|best| state.value() > best.value()) {
+            best = Some(state);
+        }
+        if tracker.exhausted() {
+            break;
+        }
+    }
+    best.map(|state| {
+        let diversity = state.value();
+        (state.base.into_selected(), diversity)
+    })
+}
+
+// ============ Incremental State ============
+
+/// A selection with its total weight; the diversity gains come from `SolutionState`.
+#[derive(Clone)]
+pub struct CapacityState<'a> {
+    base: SolutionState<'a>,
+    spec: &'a CapacitySpec,
+    weight: f64,
+}
+
+impl<'a> CapacityState<'a> {
+    pub fn empty(data: &'a MdpData, spec: &'a CapacitySpec) -> Self {
+        CapacityState { base: SolutionState::empty(data), spec, weight: 0.0 }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.base.value()
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    pub fn selected(&self) -> &[usize] {
+        self.base.selected()
+    }
+
+    fn len(&self) -> usize {
+        self.base.selected().len()
+    }
+
+    fn fits(&self, weight: f64) -> bool {
+        weight <= self.spec.capacity + CAPACITY_TOLERANCE
+    }
+
+    pub fn can_add(&self, v: usize) -> bool {
+        self.len() < self.spec.max_size && self.fits(self.weight + self.spec.weights[v])
+    }
+
+    pub fn can_drop(&self) -> bool {
+        self.len() > self.spec.min_size
+    }
+
+    pub fn can_swap(&self, out: usize, inc: usize) -> bool {
+        self.fits(self.weight - self.spec.weights[out] + self.spec.weights[inc])
+    }
+
+    pub fn add(&mut self, v: usize) {
+        self.base.add(v);
+        self.weight += self.spec.weights[v];
+    }
+
+    pub fn remove(&mut self, v: usize) {
+        self.base.remove(v);
+        self.weight -= self.spec.weights[v];
+    }
+
+    pub fn apply_swap(&mut self, out: usize, inc: usize) {
+        self.base.apply_swap(out, inc);
+        self.weight += self.spec.weights[inc] - self.spec.weights[out];
+    }
+}
+
+// ============ Construction ============
+
+// Adds elements by contribution per unit of weight, drawn from the `alpha`
+// restricted candidate list, until none fits or, past the minimum size,
+// none improves the diversity. Below the minimum size an element is only a
+// candidate if the lightest remaining elements could still complete the
+// selection after it. The first element is drawn at random, as every
+// contribution is 0 on an empty selection.
+fn greedy_randomized_construction<'a>(
+    data: &'a MdpData,
+    spec: &'a CapacitySpec,
+    alpha: f64,
+    tracker: &mut BudgetTracker,
+    rng: &mut impl Rng,
+) -> CapacityState<'a> {
+    let mut state = CapacityState::empty(data, spec);
+    loop {
+        let reserve = Reserve::new(&state, spec.min_size.saturating_sub(state.len() + 1));
+        let candidates: Vec<(usize, f64)> = state
+            .base
+            .unselected()
+            .iter()
+            .filter(|&&v| {
+                let w = spec.weights[v];
+                state.can_add(v) && state.fits(state.weight + w + reserve.without(w))
+            })
+            .map(|&v| (v, state.base.contribution(v)))
+            .filter(|&(_, gain)| state.len() < spec.min_size || state.len() == 0 || gain > 1e-9)
+            .map(|(v, gain)| (v, gain_per_weight(gain, spec.weights[v])))
+            .collect();
+        tracker.add_evaluations(candidates.len());
+        if candidates.is_empty() {
+            return state;
+        }
+
+        let (low, high) = candidates
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &(_, score)| (low.min(score), high.max(score)));
+        let rcl: Vec<usize> = if high.is_infinite() {
+            // Weightless elements with a positive gain come first
+            candidates.iter().filter(|&&(_, score)| score == high).map(|&(v, _)| v).collect()
+        } else {
+            let threshold = high - alpha * (high - low);
+            candidates.iter().filter(|&&(_, score)| score >= threshold).map(|&(v, _)| v).collect()
+        };
+        state.add(rcl[rng.gen_range(0..rcl.len())]);
+    }
+}
+
+fn gain_per_weight(gain: f64, weight: f64) -> f64 {
+    if weight > 0.0 {
+        gain / weight
+    } else if gain > 0.0 {
+        f64::INFINITY
+    } else {
+        gain
+    }
+}
+
+// Total weight of the `needed` lightest unselected elements, which must still
+// fit after the next addition for the selection to reach the minimum size
+struct Reserve {
+    lightest: f64, // Sum of the `needed` lightest weights
+    cutoff: f64,   // Largest weight among them
+    next: f64,     // The following weight, which replaces a candidate taken from them
+}
+
+impl Reserve {
+    fn new(state: &CapacityState, needed: usize) -> Self {
+        if needed == 0 {
+            return Reserve { lightest: 0.0, cutoff: f64::NEG_INFINITY, next: 0.0 };
+        }
+        let mut weights: Vec<f64> = state.base.unselected().iter().map(|&v| state.spec.weights[v]).collect();
+        weights.sort_by(|a, b| a.total_cmp(b));
+        let lightest = weights.iter().take(needed).sum();
+        Reserve {
+            lightest,
+            cutoff: weights.get(needed - 1).copied().unwrap_or(f64::INFINITY),
+            next: weights.get(needed).copied().unwrap_or(f64::INFINITY),
+        }
+    }
+
+    // The reserve once an element of `weight` is added; it cannot also serve as
+    // one of the lightest, so the next weight takes its place
+    fn without(&self, weight: f64) -> f64 {
+        if weight <= self.cutoff {
+            self.lightest - weight + self.next
+        } else {
+            self.lightest
+        }
+    }
+}
+
+// ============ Neighborhood ============
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Move {
+    Add(usize),
+    Drop(usize),
+    Swap { out: usize, inc: usize },
+}
+
+impl Move {
+    // The move that undoes this one, which tabu search forbids for a while
+    fn reverse(self) -> Move {
+        match self {
+            Move::Add(v) => Move::Drop(v),
+            Move::Drop(v) => Move::Add(v),
+            Move::Swap { out, inc } => Move::Swap { out: inc, inc: out },
+        }
+    }
+
+    fn apply(self, state: &mut CapacityState) {
+        match self {
+            Move::Add(v) => state.add(v),
+            Move::Drop(v) => state.remove(v),
+            Move::Swap { out, inc } => state.apply_swap(out, inc),
+        }
+    }
+}
+
+// Calls `visit` with every feasible move and its gain; returns the number of moves evaluated
+fn scan_moves(state: &CapacityState, mut visit: impl FnMut(Move, f64)) -> usize {
+    let base = &state.base;
+    let mut evaluated = 0;
+    for &v in base.unselected() {
+        if state.can_add(v) {
+            evaluated += 1;
+            visit(Move::Add(v), base.contribution(v));
+        }
+    }
+    if state.can_drop() {
+        for &v in base.selected() {
+            evaluated += 1;
+            visit(Move::Drop(v), -base.contribution(v));
+        }
+    }
+    for &out in base.selected() {
+        for &inc in base.unselected() {
+            if state.can_swap(out, inc) {
+                evaluated += 1;
+                visit(Move::Swap { out, inc }, base.swap_gain(out, inc));
+            }
+        }
+    }
+    evaluated
+}
+
+// ============ Local Search ============
+
+// Applies the best improving move until none improves
+fn descend(state: &mut CapacityState, tracker: &mut BudgetTracker) {
+    while !tracker.exhausted() {
+        let mut chosen: Option<(Move, f64)> = None;
+        let evaluated = scan_moves(state, |mv, gain| {
+            if gain > 1e-9 && chosen.is_none_or(|(_, best_gain)| gain > best_gain) {
+                chosen = Some((mv, gain));
+            }
+        });
+        tracker.add_evaluations(evaluated);
+        match chosen {
+            Some((mv, _)) => mv.apply(state),
+            None => break,
+        }
+    }
+}
+
+// Applies the best admissible move each iteration, even when it worsens the
+// selection; the reverse of an applied move is tabu for `tabu_tenure`
+// iterations unless it yields a new best. Returns the best selection visited.
+fn tabu_search<'a>(
+    mut state: CapacityState<'a>,
+    max_iters: usize,
+    tabu_tenure: usize,
+    tracker: &mut BudgetTracker,
+) -> CapacityState<'a> {
+    let mut best = state.clone();
+    let mut tabu: Vec<(Move, usize)> = Vec::new(); // Forbidden move and the iteration it expires
+
+    for iter in 0..max_iters {
+        if tracker.exhausted() {
+            break;
+        }
+        tabu.retain(|&(_, expires)| expires > iter);
+        let mut chosen: Option<(Move, f64)> = None;
+        let evaluated = scan_moves(&state, |mv, gain| {
+            let is_tabu = tabu.iter().any(|&(forbidden, _)| forbidden == mv);
+            let aspires = state.value() + gain > best.value() + 1e-9;
+            if (!is_tabu || aspires) && chosen.is_none_or(|(_, best_gain)| gain > best_gain) {
+                chosen = Some((mv, gain));
+            }
+        });
+        tracker.add_evaluations(evaluated);
+        let Some((mv, _)) = chosen else { break };
+
+        mv.apply(&mut state);
+        tabu.push((mv.reverse(), iter + tabu_tenure));
+        tracker.next_iteration();
+        tracker.observe(state.value());
+        if state.value() > best.value() + 1e-9 {
+            best = state.clone();
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{calculate_diversity, check_selection};
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+
+    // An instance of `n` elements with weights in 1..=10 and room for `min_size..=max_size`
+    // of them within `fraction` of the total weight
+    fn capacitated(n: usize, kind: InstanceKind, sizes: (usize, usize), fraction: f64, rng: &mut StdRng) -> MdpData {
+        let mut data = generate_instance(&GeneratorConfig { n, k: sizes.0, kind, dims: 2 }, rng);
+        let weights: Vec<f64> = (0..n).map(|_| rng.gen_range(1..=10) as f64).collect();
+        let capacity = (fraction * weights.iter().sum::<f64>()).floor();
+        data.capacity = Some(CapacitySpec { weights, capacity, min_size: sizes.0, max_size: sizes.1 });
+        data
+    }
+
+    // Best diversity over every feasible selection, if there is one
+    fn brute_force(data: &MdpData) -> Option<f64> {
+        (0u32..1 << data.n)
+            .map(|mask| (0..data.n).filter(|&i| mask & (1 << i) != 0).collect::<Vec<usize>>())
+            .filter(|selected| check_selection(selected, data).is_empty())
+            .map(|selected| calculate_diversity(&selected, data))
+            .reduce(f64::max)
+    }
+
+    fn assert_close(actual: f64, expected: f64, context: &str) {
+        assert!((actual - expected).abs() < 1e-6, "{}: {} != {}", context, actual, expected);
+    }
+
+    #[test]
+    fn scanned_moves_are_all_the_feasible_ones_with_their_gains() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for kind in [InstanceKind::Integer, InstanceKind::Signed] {
+            let data = capacitated(12, kind, (2, 6), 0.4, &mut rng);
+            let spec = data.capacity.as_ref().unwrap();
+            let budget = Budget::default();
+            let mut tracker = BudgetTracker::start(&budget);
+            let mut state = greedy_randomized_construction(&data, spec, 0.5, &mut tracker, &mut rng);
+
+            for step in 0..100 {
+                let mut moves = Vec::new();
+                scan_moves(&state, |mv, gain| moves.push((mv, gain)));
+                for &(mv, gain) in &moves {
+                    let mut next = state.clone();
+                    mv.apply(&mut next);
+                    let context = format!("{:?} step {}: {:?}", kind, step, mv);
+                    assert_close(next.value(), calculate_diversity(next.selected(), &data), &context);
+                    assert_close(state.value() + gain, next.value(), &context);
+                    assert_close(next.weight(), spec.weight_of(next.selected()), &context);
+                    assert!(check_selection(next.selected(), &data).is_empty(), "{}: infeasible", context);
+                }
+
+                // Every feasible neighbor shows up: one add or drop per element, one swap per pair
+                let (selected, unselected) = (state.selected(), state.base.unselected());
+                let feasible = |selected: Vec<usize>| check_selection(&selected, &data).is_empty();
+                let adds = unselected.iter().filter(|&&v| feasible([selected, &[v]].concat()));
+                let without = |v: usize| selected.iter().copied().filter(|&u| u != v).collect();
+                let drops = selected.iter().filter(|&&v| feasible(without(v)));
+                let pairs = selected.iter().flat_map(|&out| unselected.iter().map(move |&inc| (out, inc)));
+                let swaps = pairs.filter(|&(out, inc)| feasible([without(out), vec![inc]].concat()));
+                assert_eq!(moves.len(), adds.count() + drops.count() + swaps.count(), "{:?} step {}", kind, step);
+
+                let Some(&(mv, _)) = moves.get(rng.gen_range(0..moves.len().max(1))) else { break };
+                mv.apply(&mut state);
+            }
+        }
+    }
+
+    #[test]
+    fn reserve_is_the_lightest_completion_after_an_addition() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let data = capacitated(12, InstanceKind::Integer, (0, 12), 1.0, &mut rng);
+        let spec = data.capacity.as_ref().unwrap();
+        let mut state = CapacityState::empty(&data, spec);
+        for v in [3, 7, 8] {
+            state.add(v);
+        }
+        for needed in 0..=10 {
+            let reserve = Reserve::new(&state, needed);
+            for &v in state.base.unselected() {
+                let mut rest: Vec<f64> =
+                    state.base.unselected().iter().filter(|&&u| u != v).map(|&u| spec.weights[u]).collect();
+                rest.sort_by(|a, b| a.total_cmp(b));
+                let expected = if rest.len() < needed { f64::INFINITY } else { rest[..needed].iter().sum() };
+                assert_eq!(reserve.without(spec.weights[v]), expected, "needed {} after adding {}", needed, v);
+            }
+        }
+    }
+
+    #[test]
+    fn construction_reaches_the_minimum_size_under_a_tight_capacity() {
+        let mut rng = StdRng::seed_from_u64(43);
+        for _ in 0..50 {
+            let mut data = capacitated(15, InstanceKind::Euclidean, (5, 8), 1.0, &mut rng);
+            let spec = data.capacity.as_mut().unwrap();
+            // Only slightly more than the five lightest weights fit
+            let mut weights = spec.weights.clone();
+            weights.sort_by(|a, b| a.total_cmp(b));
+            spec.capacity = weights[..5].iter().sum::<f64>() + 2.0;
+
+            let spec = data.capacity.as_ref().unwrap();
+            let budget = Budget::default();
+            let state = greedy_randomized_construction(&data, spec, 1.0, &mut BudgetTracker::start(&budget), &mut rng);
+            assert!(check_selection(state.selected(), &data).is_empty(), "{:?}", state.selected());
+        }
+    }
+
+    #[test]
+    fn searches_match_brute_force_on_small_instances() {
+        let mut rng = StdRng::seed_from_u64(44);
+        for (kind, sizes, fraction) in [
+            (InstanceKind::Euclidean, (2, 10), 0.3),
+            (InstanceKind::Integer, (3, 5), 0.5),
+            (InstanceKind::Signed, (2, 6), 0.4),
+            (InstanceKind::Signed, (0, 10), 1.0),
+        ] {
+            let data = capacitated(10, kind, sizes, fraction, &mut rng);
+            let optimum = brute_force(&data).expect("the instances are feasible");
+            for method in [
+                CapacitatedMethod::Greedy,
+                CapacitatedMethod::LocalSearch,
+                CapacitatedMethod::TabuSearch { tabu_tenure: 5 },
+            ] {
+                let exact = !matches!(method, CapacitatedMethod::Greedy);
+                let config = CapacitatedConfig { method, max_iters: 100, ..Default::default() };
+                let spec = data.capacity.as_ref().unwrap();
+                let (selected, value) = solve_capacitated(&data, spec, &config, &Budget::default(), &mut rng).unwrap();
+                let context = format!("{:?} {:?} {}", kind, sizes, fraction);
+                assert!(check_selection(&selected, &data).is_empty(), "{}: infeasible {:?}", context, selected);
+                assert_close(value, calculate_diversity(&selected, &data), &context);
+                assert!(value <= optimum + 1e-6, "{}: {} beats the optimum {}", context, value, optimum);
+                if exact {
+                    assert_close(value, optimum, &context);
+                }
+            }
+        }
+    }
+}
@@ -1,36 +1,73 @@
+//! Plain first-improvement descent, used as a quick baseline.
+
+use crate::evaluation::{random_selection, ConstraintCounts, SolutionState};
 use crate::parser::MdpData;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use crate::solver::{
+    parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError,
+    SolveOutcome, Solver,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-pub fn solve_direct(data: &MdpData) -> (Vec<usize>, f64) {
-    let mut rng = thread_rng();
-    let n = data.n;
-    let k = data.k;
+/// Random start followed by an unbounded first-improvement descent, both
+/// kept within the side constraints of the instance.
+#[derive(Default)]
+pub struct DirectSolver {
+    pub seed: Option<u64>,
+}
 
-    // 1. Initial Solution: Randomly select k indices
-    let mut all_indices: Vec<usize> = (0..n).collect();
-    all_indices.shuffle(&mut rng);
-    let mut selected: Vec<usize> = all_indices[0..k].to_vec();
-    let mut unselected: Vec<usize> = all_indices[k..n].to_vec();
+impl Solver for DirectSolver {
+    fn name(&self) -> &str {
+        "Direct"
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![("seed", seed_param(self.seed))]
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "seed" => self.seed = Some(parse_param(key, value)?),
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (selected, diversity) = solve_direct(data, budget, &mut rng);
+        SolveOutcome::new(selected, diversity).with_seed(seed)
+    }
+}
 
-    let mut current_diversity = calculate_diversity(&selected, data);
+// One budget iteration is one applied swap
+pub fn solve_direct(data: &MdpData, budget: &Budget, rng: &mut impl Rng) -> (Vec<usize>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+    // 1. Initial Solution: Randomly select k indices
+    let start = random_selection(data, data.k, rng);
+    let mut state = SolutionState::new(data, &start);
+    let mut counts = ConstraintCounts::new(data, &start);
     let mut improved = true;
 
     // 2. Local Search (First Improvement)
-    while improved {
+    while improved && !tracker.exhausted() {
         improved = false;
-        'outer: for i in 0..selected.len() {
-            for j in 0..unselected.len() {
-                let old_val = selected[i];
-                let new_val = unselected[j];
+        'outer: for i in 0..state.selected().len() {
+            let old_val = state.selected()[i];
+            for j in 0..state.unselected().len() {
+                let new_val = state.unselected()[j];
 
                 // Calculate gain of swapping old_val for new_val
-                let gain = calculate_swap_gain(old_val, new_val, &selected, data);
+                let gain = state.swap_gain(old_val, new_val);
+                tracker.add_evaluations(1);
 
-                if gain > 0.0 {
-                    selected[i] = new_val;
-                    unselected[j] = old_val;
-                    current_diversity += gain;
+                if gain > 0.0 && counts.can_swap(old_val, new_val) {
+                    state.apply_swap(old_val, new_val);
+                    counts.remove(old_val);
+                    counts.add(new_val);
+                    tracker.next_iteration();
+                    tracker.observe(state.value());
                     improved = true;
                     break 'outer; // Greedily take the first improvement found
                 }
@@ -38,24 +75,40 @@ pub fn solve_direct(data: &MdpData) -> (Vec<usize>, f64) {
         }
     }
 
-    (selected, current_diversity)
+    let diversity = state.value();
+    (state.into_selected(), diversity)
 }
 
-fn calculate_diversity(selected: &[usize], data: &MdpData) -> f64 {
-    let mut sum = 0.0;
-    for i in 0..selected.len() {
-        for j in (i + 1)..selected.len() {
-            sum += data.get_dist(selected[i], selected[j]);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{calculate_diversity, check_selection};
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+    use crate::parser::SideConstraints;
+
+    #[test]
+    fn descent_keeps_the_side_constraints() {
+        let mut rng = StdRng::seed_from_u64(31);
+        for kind in [InstanceKind::Euclidean, InstanceKind::Signed] {
+            for k in [2, 4, 6] {
+                let mut data = generate_instance(&GeneratorConfig { n: 10, k, kind, dims: 2 }, &mut rng);
+                // Element 0 included, 9 excluded and 1..=4 a category of at most one
+                let mut spec = SideConstraints::new(data.n);
+                spec.required[0] = true;
+                spec.forbidden[9] = true;
+                for i in 1..=4 {
+                    spec.category[i] = Some(0);
+                }
+                spec.quotas.push((0, 1));
+                data.constraints = Some(spec);
+
+                for _ in 0..10 {
+                    let (selected, diversity) = solve_direct(&data, &Budget::default(), &mut rng);
+                    let violations = check_selection(&selected, &data);
+                    assert!(violations.is_empty(), "{:?} k={} {:?}: {:?}", kind, k, selected, violations);
+                    assert!((calculate_diversity(&selected, &data) - diversity).abs() < 1e-6);
+                }
+            }
         }
     }
-    sum
 }
-
-fn calculate_swap_gain(old: usize, new: usize, selected: &[usize], data: &MdpData) -> f64 {
-    let mut gain = 0.0;
-    for &s in selected {
-        if s == old { continue; }
-        gain += data.get_dist(new, s) - data.get_dist(old, s);
-    }
-    gain
-}
\ No newline at end of file
@@ -1,10 +1,24 @@
-use crate::parser::MdpData;
-use rand::Rng;
+//! GRASP: greedy randomized adaptive search procedure.
 
+use crate::evaluation::{
+    ConstraintCounts, IncrementalState, MaxMeanState, MaxMinState, MaxMinSumState, MinDiffState, Objective,
+    SolutionState,
+};
+use crate::parser::MdpData;
+use crate::solver::{
+    parse_fraction, parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError,
+    SolveOutcome, Solver,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Parameters of the GRASP multi-start.
 pub struct GraspConfig {
     pub iterations: usize,
     pub alpha: f64,  // RCL parameter: 0.0 = pure greedy, 1.0 = pure random
     pub local_search_iters: usize,
+    pub seed: Option<u64>,  // None draws a fresh seed per run
+    pub objective: Objective,
 }
 
 impl Default for GraspConfig {
@@ -13,11 +27,88 @@ impl Default for GraspConfig {
             iterations: 100,
             alpha: 0.3,
             local_search_iters: 1000,
+            seed: None,
+            objective: Objective::MaxSum,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GraspSolver {
+    pub config: GraspConfig,
+}
+
+impl Solver for GraspSolver {
+    fn name(&self) -> &str {
+        "GRASP"
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("iterations", self.config.iterations.to_string()),
+            ("alpha", self.config.alpha.to_string()),
+            ("local_search_iters", self.config.local_search_iters.to_string()),
+            ("seed", seed_param(self.config.seed)),
+            ("objective", self.config.objective.to_string()),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "iterations" => self.config.iterations = parse_param(key, value)?,
+            "alpha" => self.config.alpha = parse_fraction(key, value)?,
+            "local_search_iters" => self.config.local_search_iters = parse_param(key, value)?,
+            "seed" => self.config.seed = Some(parse_param(key, value)?),
+            "objective" => {
+                let objective: Objective = parse_param(key, value)?;
+                // The construction always builds k elements and the descent only swaps
+                if !objective.fixed_size() {
+                    return Err(ParamError::InvalidValue { key: key.to_string(), value: value.to_string() });
+                }
+                self.config.objective = objective;
+            }
+            _ => return Err(unknown_param(self, key)),
         }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (selected, diversity) = solve_grasp(data, &self.config, budget, &mut rng);
+        SolveOutcome::new(selected, diversity).with_seed(seed).with_objective(self.config.objective)
     }
 }
 
-pub fn solve_grasp(data: &MdpData, config: &GraspConfig) -> (Vec<usize>, f64) {
+/// Greedy randomized construction followed by best-improvement local search,
+/// repeated from fresh starts; returns the best selection and its value under
+/// the configured objective. Both phases keep the side constraints of `data`.
+// One budget iteration is one construction + local search start
+pub fn solve_grasp(
+    data: &MdpData,
+    config: &GraspConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    let budget = &budget.scored(config.objective);
+    let (selected, score) = match config.objective {
+        Objective::MaxSum => grasp::<SolutionState>(data, config, budget, rng),
+        Objective::MaxMin => grasp::<MaxMinState>(data, config, budget, rng),
+        // Not accepted by `set_param`: searched as if the size were fixed at k
+        Objective::MaxMean => grasp::<MaxMeanState>(data, config, budget, rng),
+        Objective::MaxMinSum => grasp::<MaxMinSumState>(data, config, budget, rng),
+        Objective::MinDiff => grasp::<MinDiffState>(data, config, budget, rng),
+    };
+    (selected, config.objective.score(score))
+}
+
+fn grasp<'a, S: IncrementalState<'a>>(
+    data: &'a MdpData,
+    config: &GraspConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
     let mut best_solution = Vec::new();
     let mut best_diversity = f64::NEG_INFINITY;
     
@@ -27,14 +118,16 @@ pub fn solve_grasp(data: &MdpData, config: &GraspConfig) -> (Vec<usize>, f64) {
 
     for _iter in 0..config.iterations {
         // Construction phase: greedy randomized
-        let solution = greedy_randomized_construction(data, config.alpha);
+        let mut state: S = greedy_randomized_construction(data, config.alpha, &mut tracker, rng);
         
         // Local search phase
-        let (improved_solution, diversity) = local_search(data, solution, config.local_search_iters);
+        local_search(&mut state, data, config.local_search_iters, &mut tracker);
+        tracker.observe(state.value());
+        tracker.next_iteration();
         
-        if diversity > best_diversity {
-            best_diversity = diversity;
-            best_solution = improved_solution;
+        if state.value() > best_diversity {
+            best_diversity = state.value();
+            best_solution = state.into_selected();
             no_improvement_iters = 0; // Reset counter on improvement
         } else {
             no_improvement_iters += 1;
@@ -46,29 +139,40 @@ pub fn solve_grasp(data: &MdpData, config: &GraspConfig) -> (Vec<usize>, f64) {
                 break;
             }
         }
+
+        if tracker.exhausted() {
+            break;
+        }
     }
 
     (best_solution, best_diversity)
 }
 
-fn greedy_randomized_construction(data: &MdpData, alpha: f64) -> Vec<usize> {
-    let mut rng = rand::thread_rng();
-    let mut selected = Vec::with_capacity(data.k);
-    let mut available: Vec<usize> = (0..data.n).collect();
-
-    for _ in 0..data.k {
-        if available.is_empty() {
-            break;
-        }
-
-        // Calculate contribution of each available element
-        let mut contributions: Vec<(usize, f64)> = available
+// Starts from the included elements; a candidate must leave room for the
+// categories still below their minimum
+fn greedy_randomized_construction<'a, S: IncrementalState<'a>>(
+    data: &'a MdpData,
+    alpha: f64,
+    tracker: &mut BudgetTracker,
+    rng: &mut impl Rng,
+) -> S {
+    let required = data.constraints.as_ref().map(|spec| spec.required_elements()).unwrap_or_default();
+    let mut state = S::new(data, &required);
+    let mut counts = ConstraintCounts::new(data, &required);
+
+    while state.selected().len() < data.k {
+        // Greedy score of each available element is kept up to date by the state
+        let remaining = data.k - state.selected().len() - 1;
+        let mut contributions: Vec<(usize, f64)> = state
+            .unselected()
             .iter()
-            .map(|&idx| {
-                let contrib = calculate_marginal_contribution(idx, &selected, data);
-                (idx, contrib)
-            })
+            .filter(|&&idx| counts.can_extend(idx, remaining))
+            .map(|&idx| (idx, state.greedy_score(idx)))
             .collect();
+        tracker.add_evaluations(contributions.len());
+        if contributions.is_empty() {
+            break;
+        }
 
         // Sort by contribution (descending)
         contributions.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
@@ -86,79 +190,48 @@ fn greedy_randomized_construction(data: &MdpData, alpha: f64) -> Vec<usize> {
 
         // Randomly select from RCL
         let chosen = rcl[rng.gen_range(0..rcl.len())];
-        selected.push(chosen);
-        available.retain(|&x| x != chosen);
+        state.add(chosen);
+        counts.add(chosen);
     }
 
-    selected
+    state
 }
 
-fn calculate_marginal_contribution(candidate: usize, selected: &[usize], data: &MdpData) -> f64 {
-    let mut contribution = 0.0;
-    for &s in selected {
-        contribution += data.get_dist(candidate, s);
-    }
-    contribution
-}
-
-fn local_search(data: &MdpData, mut selected: Vec<usize>, max_iters: usize) -> (Vec<usize>, f64) {
-    let mut unselected: Vec<usize> = (0..data.n)
-        .filter(|&i| !selected.contains(&i))
-        .collect();
-
-    let mut current_diversity = calculate_diversity(&selected, data);
-    let mut no_improvement_count = 0;
-
+// Best-improvement swap descent over the admissible swaps
+fn local_search<'a>(
+    state: &mut impl IncrementalState<'a>,
+    data: &'a MdpData,
+    max_iters: usize,
+    tracker: &mut BudgetTracker,
+) {
+    let mut counts = ConstraintCounts::new(data, state.selected());
     for _ in 0..max_iters {
+        if tracker.exhausted() {
+            break;
+        }
+
         let mut best_swap = None;
         let mut best_gain = 0.0;
 
         // Find best swap
-        for i in 0..selected.len() {
-            for j in 0..unselected.len() {
-                let gain = calculate_swap_gain(selected[i], unselected[j], &selected, data);
-                if gain > best_gain {
+        for &out in state.selected() {
+            for &inc in state.unselected() {
+                let gain = state.swap_gain(out, inc);
+                if gain > best_gain && counts.can_swap(out, inc) {
                     best_gain = gain;
-                    best_swap = Some((i, j));
+                    best_swap = Some((out, inc));
                 }
             }
         }
+        tracker.add_evaluations(state.selected().len() * state.unselected().len());
 
-        if let Some((i, j)) = best_swap {
-            // Perform swap
-            let temp = selected[i];
-            selected[i] = unselected[j];
-            unselected[j] = temp;
-            current_diversity += best_gain;
-            no_improvement_count = 0;
-        } else {
-            no_improvement_count += 1;
-            if no_improvement_count > 10 {
-                break; // No improvement for several iterations
+        match best_swap {
+            Some((out, inc)) => {
+                state.apply_swap(out, inc);
+                counts.remove(out);
+                counts.add(inc);
             }
+            None => break, // Local optimum reached
         }
     }
-
-    (selected, current_diversity)
 }
-
-fn calculate_diversity(selected: &[usize], data: &MdpData) -> f64 {
-    let mut sum = 0.0;
-    for i in 0..selected.len() {
-        for j in (i + 1)..selected.len() {
-            sum += data.get_dist(selected[i], selected[j]);
-        }
-    }
-    sum
-}
-
-fn calculate_swap_gain(old: usize, new: usize, selected: &[usize], data: &MdpData) -> f64 {
-    let mut gain = 0.0;
-    for &s in selected {
-        if s == old {
-            continue;
-        }
-        gain += data.get_dist(new, s) - data.get_dist(old, s);
-    }
-    gain
-}
\ No newline at end of file
@@ -0,0 +1,517 @@
+//! Maximally Diverse Grouping Problem (MDGP): partition all n elements into G
+//! groups with sizes within the instance's limits, maximizing the sum of the
+//! pairwise distances inside each group. A greedy randomized construction is
+//! improved by tabu search or iterated local search over two neighborhoods:
+//! moving one element to another group and swapping two elements of different groups.
+
+use crate::parser::{GroupSpec, MdpData};
+use crate::solver::{parse_param, resolve_seed, seed_param, Budget, BudgetTracker, ParamError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Parameters of the grouping search.
+pub struct GroupingConfig {
+    pub method: GroupingMethod,
+    pub max_iters: usize, // Tabu moves, or ILS perturbation rounds
+    pub seed: Option<u64>, // None draws a fresh seed per run
+}
+
+/// Improvement phase run after the construction.
+pub enum GroupingMethod {
+    TabuSearch { tabu_tenure: usize },
+    IteratedLocalSearch { perturbation: usize }, // Random swaps per kick
+}
+
+impl Default for GroupingConfig {
+    fn default() -> Self {
+        GroupingConfig {
+            method: GroupingMethod::TabuSearch { tabu_tenure: 10 },
+            max_iters: 2000,
+            seed: None,
+        }
+    }
+}
+
+/// The best partition a run found.
+#[derive(Clone, Debug)]
+pub struct GroupingOutcome {
+    pub assignment: Vec<usize>, // Group of each element
+    pub value: f64,             // Sum of the within-group distances
+    pub seed: u64,
+}
+
+impl GroupingOutcome {
+    /// Members of each group, in index order.
+    pub fn groups(&self, spec: &GroupSpec) -> Vec<Vec<usize>> {
+        let mut groups = vec![Vec::new(); spec.groups];
+        for (u, &g) in self.assignment.iter().enumerate() {
+            groups[g].push(u);
+        }
+        groups
+    }
+}
+
+#[derive(Default)]
+pub struct GroupingSolver {
+    pub config: GroupingConfig,
+}
+
+impl GroupingSolver {
+    pub fn new(config: GroupingConfig) -> Self {
+        GroupingSolver { config }
+    }
+
+    pub fn name(&self) -> &str {
+        match self.config.method {
+            GroupingMethod::TabuSearch { .. } => "MDGP-Tabu",
+            GroupingMethod::IteratedLocalSearch { .. } => "MDGP-ILS",
+        }
+    }
+
+    /// Current parameter values, for logging.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("max_iters", self.config.max_iters.to_string()),
+            ("seed", seed_param(self.config.seed)),
+        ];
+        match self.config.method {
+            GroupingMethod::TabuSearch { tabu_tenure } => params.push(("tabu_tenure", tabu_tenure.to_string())),
+            GroupingMethod::IteratedLocalSearch { perturbation } => {
+                params.push(("perturbation", perturbation.to_string()))
+            }
+        }
+        params
+    }
+
+    /// Sets a parameter from its textual value, like `Solver::set_param`.
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("max_iters", _) => self.config.max_iters = parse_param(key, value)?,
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("tabu_tenure", GroupingMethod::TabuSearch { tabu_tenure }) => *tabu_tenure = parse_param(key, value)?,
+            ("perturbation", GroupingMethod::IteratedLocalSearch { perturbation }) => {
+                *perturbation = parse_param(key, value)?
+            }
+            _ => {
+                return Err(ParamError::Unknown {
+                    solver: self.name().to_string(),
+                    key: key.to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Partitions the elements of `data` as `spec` allows; `spec` must fit `data.n`.
+    pub fn solve(&self, data: &MdpData, spec: &GroupSpec, budget: &Budget) -> GroupingOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (assignment, value) = solve_grouping(data, spec, &self.config, budget, &mut rng);
+        GroupingOutcome { assignment, value, seed }
+    }
+}
+
+/// Sum of the pairwise distances within each group of `assignment`.
+pub fn grouping_value(assignment: &[usize], data: &MdpData) -> f64 {
+    let mut value = 0.0;
+    for u in 0..assignment.len() {
+        for w in (u + 1)..assignment.len() {
+            if assignment[u] == assignment[w] {
+                value += data.get_dist(u, w);
+            }
+        }
+    }
+    value
+}
+
+/// Constructs a partition and improves it with the configured method; returns
+/// the best assignment and its value.
+// One budget iteration is one tabu move or one ILS round
+pub fn solve_grouping(
+    data: &MdpData,
+    spec: &GroupSpec,
+    config: &GroupingConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    assert!(spec.fits(data.n), "{} cannot hold n = {} elements", spec, data.n);
+    let mut tracker = BudgetTracker::start(budget);
+    let state = greedy_randomized_construction(data, spec, rng);
+    tracker.observe(state.value);
+    match config.method {
+        GroupingMethod::TabuSearch { tabu_tenure } => tabu_search(state, config.max_iters, tabu_tenure, &mut tracker),
+        GroupingMethod::IteratedLocalSearch { perturbation } => {
+            iterated_local_search(state, config.max_iters, perturbation, &mut tracker, rng)
+        }
+    }
+}
+
+// ============ Incremental State ============
+
+// Element whose group is not decided yet
+const UNASSIGNED: usize = usize::MAX;
+
+/// A (partial) partition with every element's distance sum to every group, so
+/// moves and swaps are evaluated in O(1) and applied in O(n).
+#[derive(Clone)]
+pub struct GroupingState<'a> {
+    data: &'a MdpData,
+    spec: GroupSpec,
+    assignment: Vec<usize>,
+    sizes: Vec<usize>,
+    to_group: Vec<f64>, // n × G: sum of distances from element u to the members of group g
+    value: f64,
+}
+
+impl<'a> GroupingState<'a> {
+    pub fn empty(data: &'a MdpData, spec: &GroupSpec) -> Self {
+        GroupingState {
+            data,
+            spec: *spec,
+            assignment: vec![UNASSIGNED; data.n],
+            sizes: vec![0; spec.groups],
+            to_group: vec![0.0; data.n * spec.groups],
+            value: 0.0,
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn assignment(&self) -> &[usize] {
+        &self.assignment
+    }
+
+    // Distance sum from `u` to the members of `g`, excluding `u` itself
+    fn to_group(&self, u: usize, g: usize) -> f64 {
+        self.to_group[u * self.spec.groups + g]
+    }
+
+    /// Change in value if `u` joined group `g` (leaving its own group, if any).
+    pub fn move_gain(&self, u: usize, g: usize) -> f64 {
+        let from = self.assignment[u];
+        let lost = if from == UNASSIGNED { 0.0 } else { self.to_group(u, from) };
+        self.to_group(u, g) - lost
+    }
+
+    /// Whether moving assigned `u` to `g` keeps both groups within the size limits.
+    pub fn can_move(&self, u: usize, g: usize) -> bool {
+        let from = self.assignment[u];
+        from != g && self.sizes[from] > self.spec.min_size && self.sizes[g] < self.spec.max_size
+    }
+
+    /// Change in value if `u` and `v`, in different groups, traded places.
+    pub fn swap_gain(&self, u: usize, v: usize) -> f64 {
+        let (a, b) = (self.assignment[u], self.assignment[v]);
+        let d = self.data.get_dist(u, v);
+        (self.to_group(u, b) - d - self.to_group(u, a)) + (self.to_group(v, a) - d - self.to_group(v, b))
+    }
+
+    /// Puts `u` into group `g`, taking it out of its current group first.
+    pub fn assign(&mut self, u: usize, g: usize) {
+        self.value += self.move_gain(u, g);
+        let groups = self.spec.groups;
+        let from = self.assignment[u];
+        if from != UNASSIGNED {
+            self.sizes[from] -= 1;
+        }
+        for w in 0..self.data.n {
+            let d = self.data.get_dist(u, w);
+            if w != u && d != 0.0 {
+                if from != UNASSIGNED {
+                    self.to_group[w * groups + from] -= d;
+                }
+                self.to_group[w * groups + g] += d;
+            }
+        }
+        self.assignment[u] = g;
+        self.sizes[g] += 1;
+    }
+
+    pub fn apply_swap(&mut self, u: usize, v: usize) {
+        let (a, b) = (self.assignment[u], self.assignment[v]);
+        self.assign(u, b);
+        self.assign(v, a);
+    }
+
+    pub fn into_assignment(self) -> Vec<usize> {
+        self.assignment
+    }
+}
+
+// ============ Construction ============
+
+// Seeds every group with a random element, then adds the remaining elements in
+// random order, each to the open group it is most diverse from. Groups below
+// the minimum size are only preferred once the elements left are just enough to fill them.
+fn greedy_randomized_construction<'a>(data: &'a MdpData, spec: &GroupSpec, rng: &mut impl Rng) -> GroupingState<'a> {
+    let mut state = GroupingState::empty(data, spec);
+    let mut order: Vec<usize> = (0..data.n).collect();
+    order.shuffle(rng);
+
+    for (g, &u) in order.iter().take(spec.groups).enumerate() {
+        state.assign(u, g);
+    }
+    for (placed, &u) in order.iter().enumerate().skip(spec.groups) {
+        let remaining = data.n - placed;
+        let missing: usize = state.sizes.iter().map(|&size| spec.min_size.saturating_sub(size)).sum();
+        let best = (0..spec.groups)
+            .filter(|&g| state.sizes[g] < spec.max_size)
+            .filter(|&g| remaining > missing || state.sizes[g] < spec.min_size)
+            .max_by(|&a, &b| state.to_group(u, a).total_cmp(&state.to_group(u, b)))
+            .expect("a group with room exists while the sizes fit n");
+        state.assign(u, best);
+    }
+    state
+}
+
+// ============ Neighborhood ============
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupMove {
+    Move { u: usize, to: usize },
+    Swap { u: usize, v: usize },
+}
+
+impl GroupMove {
+    fn apply(self, state: &mut GroupingState) {
+        match self {
+            GroupMove::Move { u, to } => state.assign(u, to),
+            GroupMove::Swap { u, v } => state.apply_swap(u, v),
+        }
+    }
+
+    // (element, group it leaves) for each element the move relocates
+    fn departures(self, state: &GroupingState) -> [(usize, usize); 2] {
+        match self {
+            GroupMove::Move { u, .. } => [(u, state.assignment[u]); 2],
+            GroupMove::Swap { u, v } => [(u, state.assignment[u]), (v, state.assignment[v])],
+        }
+    }
+
+    // (element, group it enters) for each element the move relocates
+    fn arrivals(self, state: &GroupingState) -> [(usize, usize); 2] {
+        match self {
+            GroupMove::Move { u, to } => [(u, to); 2],
+            GroupMove::Swap { u, v } => [(u, state.assignment[v]), (v, state.assignment[u])],
+        }
+    }
+}
+
+// Calls `visit` with every size-feasible move and swap and its gain; returns
+// the number of moves evaluated.
+fn scan_moves(state: &GroupingState, mut visit: impl FnMut(GroupMove, f64)) -> usize {
+    let n = state.data.n;
+    let mut evaluated = 0;
+    for u in 0..n {
+        for to in 0..state.spec.groups {
+            if state.can_move(u, to) {
+                evaluated += 1;
+                visit(GroupMove::Move { u, to }, state.move_gain(u, to));
+            }
+        }
+    }
+    for u in 0..n {
+        for v in (u + 1)..n {
+            if state.assignment[u] != state.assignment[v] {
+                evaluated += 1;
+                visit(GroupMove::Swap { u, v }, state.swap_gain(u, v));
+            }
+        }
+    }
+    evaluated
+}
+
+// ============ Tabu Search ============
+
+// Applies the best admissible move each iteration, even when it worsens the
+// partition; an element may not return to a group it left for `tabu_tenure`
+// iterations unless that yields a new best.
+fn tabu_search(
+    mut state: GroupingState,
+    max_iters: usize,
+    tabu_tenure: usize,
+    tracker: &mut BudgetTracker,
+) -> (Vec<usize>, f64) {
+    let groups = state.spec.groups;
+    let mut best_value = state.value;
+    let mut best_assignment = state.assignment.clone();
+    // Iteration until which element u may not re-enter group g
+    let mut tabu_until = vec![0usize; state.data.n * groups];
+
+    for iter in 1..=max_iters {
+        if tracker.exhausted() {
+            break;
+        }
+        let mut chosen: Option<(GroupMove, f64)> = None;
+        let evaluated = scan_moves(&state, |mv, gain| {
+            let tabu = mv.arrivals(&state).iter().any(|&(u, g)| tabu_until[u * groups + g] >= iter);
+            let aspires = state.value + gain > best_value + 1e-9;
+            if (!tabu || aspires) && chosen.is_none_or(|(_, best_gain)| gain > best_gain) {
+                chosen = Some((mv, gain));
+            }
+        });
+        tracker.add_evaluations(evaluated);
+        let Some((mv, _)) = chosen else { break };
+
+        for (u, g) in mv.departures(&state) {
+            tabu_until[u * groups + g] = iter + tabu_tenure;
+        }
+        mv.apply(&mut state);
+        tracker.next_iteration();
+        tracker.observe(state.value);
+
+        if state.value > best_value + 1e-9 {
+            best_value = state.value;
+            best_assignment.clone_from(&state.assignment);
+        }
+    }
+    (best_assignment, best_value)
+}
+
+// ============ Iterated Local Search ============
+
+// Best-improvement descent to a local optimum, then `perturbation` random
+// swaps and another descent; a kicked solution is kept when it is at least as
+// good as the current one.
+fn iterated_local_search(
+    mut state: GroupingState,
+    max_iters: usize,
+    perturbation: usize,
+    tracker: &mut BudgetTracker,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    descend(&mut state, tracker);
+    let mut current = state.clone();
+
+    for _ in 0..max_iters {
+        if tracker.exhausted() {
+            break;
+        }
+        let mut candidate = current.clone();
+        perturb(&mut candidate, perturbation, rng);
+        descend(&mut candidate, tracker);
+        tracker.next_iteration();
+        tracker.observe(candidate.value);
+        if candidate.value >= current.value - 1e-9 {
+            current = candidate;
+            if current.value > state.value + 1e-9 {
+                state = current.clone();
+            }
+        }
+    }
+    let value = state.value;
+    (state.into_assignment(), value)
+}
+
+fn descend(state: &mut GroupingState, tracker: &mut BudgetTracker) {
+    while !tracker.exhausted() {
+        let mut chosen: Option<(GroupMove, f64)> = None;
+        let evaluated = scan_moves(state, |mv, gain| {
+            if gain > 1e-9 && chosen.is_none_or(|(_, best_gain)| gain > best_gain) {
+                chosen = Some((mv, gain));
+            }
+        });
+        tracker.add_evaluations(evaluated);
+        match chosen {
+            Some((mv, _)) => mv.apply(state),
+            None => break,
+        }
+        tracker.observe(state.value);
+    }
+}
+
+fn perturb(state: &mut GroupingState, swaps: usize, rng: &mut impl Rng) {
+    let n = state.data.n;
+    if state.spec.groups < 2 {
+        return;
+    }
+    for _ in 0..swaps {
+        let u = rng.gen_range(0..n);
+        let v = rng.gen_range(0..n);
+        if state.assignment[u] != state.assignment[v] {
+            state.apply_swap(u, v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+
+    fn assert_close(actual: f64, expected: f64, context: &str) {
+        assert!((actual - expected).abs() < 1e-6, "{}: {} != {}", context, actual, expected);
+    }
+
+    // Within-group sum of the assigned elements only
+    fn partial_value(assignment: &[usize], data: &MdpData) -> f64 {
+        let assigned: Vec<usize> = (0..assignment.len()).filter(|&u| assignment[u] != UNASSIGNED).collect();
+        let mut value = 0.0;
+        for (idx, &u) in assigned.iter().enumerate() {
+            for &w in &assigned[idx + 1..] {
+                if assignment[u] == assignment[w] {
+                    value += data.get_dist(u, w);
+                }
+            }
+        }
+        value
+    }
+
+    #[test]
+    fn incremental_gains_match_grouping_value() {
+        let mut rng = StdRng::seed_from_u64(21);
+        for kind in [InstanceKind::Euclidean, InstanceKind::Integer, InstanceKind::Signed] {
+            let data = generate_instance(&GeneratorConfig { n: 15, k: 5, kind, dims: 2 }, &mut rng);
+            let spec = GroupSpec::balanced(data.n, 4);
+            let mut state = GroupingState::empty(&data, &spec);
+
+            let mut order: Vec<usize> = (0..data.n).collect();
+            order.shuffle(&mut rng);
+            for &u in &order {
+                let g = rng.gen_range(0..spec.groups);
+                let expected = state.value() + state.move_gain(u, g);
+                state.assign(u, g);
+                assert_close(state.value(), expected, &format!("{:?}: assign {} to {}", kind, u, g));
+                assert_close(state.value(), partial_value(state.assignment(), &data), "construction");
+            }
+
+            for step in 0..300 {
+                let before = state.value();
+                let (u, v) = (rng.gen_range(0..data.n), rng.gen_range(0..data.n));
+                let (gain, context) = if state.assignment()[u] != state.assignment()[v] && rng.gen_bool(0.5) {
+                    let gain = state.swap_gain(u, v);
+                    state.apply_swap(u, v);
+                    (gain, format!("{:?} step {}: swap {} and {}", kind, step, u, v))
+                } else {
+                    let g = rng.gen_range(0..spec.groups);
+                    let gain = state.move_gain(u, g);
+                    state.assign(u, g);
+                    (gain, format!("{:?} step {}: move {} to {}", kind, step, u, g))
+                };
+                let expected = grouping_value(state.assignment(), &data);
+                assert_close(state.value(), expected, &context);
+                assert_close(before + gain, expected, &format!("{} (gain)", context));
+            }
+        }
+    }
+
+    #[test]
+    fn searches_return_partitions_within_the_size_limits() {
+        let mut rng = StdRng::seed_from_u64(22);
+        let data = generate_instance(&GeneratorConfig { n: 14, k: 5, kind: InstanceKind::Signed, dims: 2 }, &mut rng);
+        let spec = GroupSpec { groups: 3, min_size: 3, max_size: 6 };
+        let tabu = GroupingMethod::TabuSearch { tabu_tenure: 5 };
+        for method in [tabu, GroupingMethod::IteratedLocalSearch { perturbation: 2 }] {
+            let config = GroupingConfig { method, max_iters: 200, seed: None };
+            let (assignment, value) = solve_grouping(&data, &spec, &config, &Budget::default(), &mut rng);
+            assert_close(value, grouping_value(&assignment, &data), "reported value");
+            for g in 0..spec.groups {
+                let size = assignment.iter().filter(|&&group| group == g).count();
+                assert!((spec.min_size..=spec.max_size).contains(&size), "group {} has {} elements", g, size);
+            }
+        }
+    }
+}
@@ -1,11 +1,30 @@
+//! Swap-based local search: first improvement, best improvement and tabu search.
+//! Objectives with a free selection size also move by adding and dropping elements.
+//! Every search starts from a selection meeting the side constraints and only
+//! makes moves that keep them.
+
+use crate::evaluation::{
+    random_selection, ConstraintCounts, IncrementalState, MaxMeanState, MaxMinState, MaxMinSumState, MinDiffState,
+    Objective, SolutionState, MAX_MEAN_MIN_SIZE,
+};
 use crate::parser::MdpData;
-use rand::seq::SliceRandom;
+use crate::solver::{
+    parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError,
+    SolveOutcome, Solver,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ops::ControlFlow;
 
+/// Parameters of a swap-neighborhood local search.
 pub struct LocalSearchConfig {
     pub method: LocalSearchMethod,
     pub max_iters: usize,
+    pub seed: Option<u64>, // None draws a fresh seed per run
+    pub objective: Objective,
 }
 
+/// Move acceptance rule of the local search.
 pub enum LocalSearchMethod {
     FirstImprovement,
     BestImprovement,
@@ -17,153 +36,338 @@ impl Default for LocalSearchConfig {
         LocalSearchConfig {
             method: LocalSearchMethod::BestImprovement,
             max_iters: 5000,
+            seed: None,
+            objective: Objective::MaxSum,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LocalSearchSolver {
+    pub config: LocalSearchConfig,
+}
+
+impl LocalSearchSolver {
+    pub fn new(config: LocalSearchConfig) -> Self {
+        LocalSearchSolver { config }
+    }
+}
+
+impl Solver for LocalSearchSolver {
+    fn name(&self) -> &str {
+        match self.config.method {
+            LocalSearchMethod::FirstImprovement => "LS-First",
+            LocalSearchMethod::BestImprovement => "LS-Best",
+            LocalSearchMethod::TabuSearch { .. } => "Tabu",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("max_iters", self.config.max_iters.to_string()),
+            ("seed", seed_param(self.config.seed)),
+            ("objective", self.config.objective.to_string()),
+        ];
+        if let LocalSearchMethod::TabuSearch { tabu_tenure } = self.config.method {
+            params.push(("tabu_tenure", tabu_tenure.to_string()));
+        }
+        params
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("max_iters", _) => self.config.max_iters = parse_param(key, value)?,
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("objective", _) => self.config.objective = parse_param(key, value)?,
+            ("tabu_tenure", LocalSearchMethod::TabuSearch { tabu_tenure }) => {
+                *tabu_tenure = parse_param(key, value)?
+            }
+            _ => return Err(unknown_param(self, key)),
         }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (selected, diversity) = solve_local_search(data, &self.config, budget, &mut rng);
+        SolveOutcome::new(selected, diversity).with_seed(seed).with_objective(self.config.objective)
     }
 }
 
-pub fn solve_local_search(data: &MdpData, config: &LocalSearchConfig) -> (Vec<usize>, f64) {
+/// Runs the configured search from a random start; returns the best selection
+/// and its value under the configured objective.
+// One budget iteration is one applied move
+pub fn solve_local_search(
+    data: &MdpData,
+    config: &LocalSearchConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    let budget = &budget.scored(config.objective);
+    let (selected, score) = match config.objective {
+        Objective::MaxSum => local_search::<SolutionState>(data, config, budget, rng),
+        Objective::MaxMin => local_search::<MaxMinState>(data, config, budget, rng),
+        Objective::MaxMean => local_search::<MaxMeanState>(data, config, budget, rng),
+        Objective::MaxMinSum => local_search::<MaxMinSumState>(data, config, budget, rng),
+        Objective::MinDiff => local_search::<MinDiffState>(data, config, budget, rng),
+    };
+    (selected, config.objective.score(score))
+}
+
+fn local_search<'a, S: IncrementalState<'a>>(
+    data: &'a MdpData,
+    config: &LocalSearchConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+    let start = random_selection(data, start_size(data, config.objective), rng);
+    let searched = Searched { state: S::new(data, &start), counts: ConstraintCounts::new(data, &start) };
     match &config.method {
-        LocalSearchMethod::FirstImprovement => first_improvement_search(data, config.max_iters),
-        LocalSearchMethod::BestImprovement => best_improvement_search(data, config.max_iters),
-        LocalSearchMethod::TabuSearch { tabu_tenure } => tabu_search(data, config.max_iters, *tabu_tenure),
+        LocalSearchMethod::FirstImprovement => first_improvement_search(searched, config.max_iters, &mut tracker),
+        LocalSearchMethod::BestImprovement => best_improvement_search(searched, config.max_iters, &mut tracker),
+        LocalSearchMethod::TabuSearch { tabu_tenure } => {
+            tabu_search(searched, config.max_iters, *tabu_tenure, &mut tracker)
+        }
+    }
+}
+
+/// Size of a random start: k, but at least the smallest size a free-size objective accepts.
+pub(crate) fn start_size(data: &MdpData, objective: Objective) -> usize {
+    if objective.fixed_size() {
+        data.k
+    } else {
+        data.k.max(MAX_MEAN_MIN_SIZE).min(data.n)
+    }
+}
+
+/// Best-improvement descent from `start` on the objective of `S` until no
+/// admissible move improves; returns the local optimum and its value. Moves
+/// count as evaluations only, so the caller keeps its own iterations.
+pub(crate) fn descend<'a, S: IncrementalState<'a>>(
+    data: &'a MdpData,
+    start: &[usize],
+    tracker: &mut BudgetTracker,
+) -> (Vec<usize>, f64) {
+    let mut searched = Searched { state: S::new(data, start), counts: ConstraintCounts::new(data, start) };
+    while !tracker.exhausted() {
+        match best_move(&searched, tracker) {
+            Some(mv) => mv.apply(&mut searched),
+            None => break,
+        }
+    }
+    let value = searched.state.value();
+    (searched.state.into_selected(), value)
+}
+
+// ============ Neighborhood ============
+
+// The searched selection with its category counts, which decide the admissible moves
+struct Searched<'c, S> {
+    state: S,
+    counts: ConstraintCounts<'c>,
+}
+
+/// One move of the local searches. Fixed-size objectives only swap; max-mean
+/// also adds and drops elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Move {
+    Swap { out: usize, inc: usize },
+    Add(usize),
+    Drop(usize),
+}
+
+impl Move {
+    // The move that undoes this one, which tabu search forbids for a while
+    fn reverse(self) -> Move {
+        match self {
+            Move::Swap { out, inc } => Move::Swap { out: inc, inc: out },
+            Move::Add(v) => Move::Drop(v),
+            Move::Drop(v) => Move::Add(v),
+        }
+    }
+
+    fn apply<'a, S: IncrementalState<'a>>(self, searched: &mut Searched<'_, S>) {
+        let Searched { state, counts } = searched;
+        match self {
+            Move::Swap { out, inc } => {
+                state.apply_swap(out, inc);
+                counts.remove(out);
+                counts.add(inc);
+            }
+            Move::Add(v) => {
+                state.add(v);
+                counts.add(v);
+            }
+            Move::Drop(v) => {
+                state.remove(v);
+                counts.remove(v);
+            }
+        }
     }
 }
 
+// Calls `visit` with every admissible move and its gain until it breaks: adds
+// and drops first (when the objective allows them), then swaps. Returns the
+// number of moves evaluated.
+fn scan_moves<'a, S: IncrementalState<'a>>(
+    searched: &Searched<'_, S>,
+    mut visit: impl FnMut(Move, f64) -> ControlFlow<()>,
+) -> usize {
+    let Searched { state, counts } = searched;
+    let mut evaluated = 0;
+    for &v in state.unselected() {
+        let Some(gain) = state.add_gain(v) else { break };
+        evaluated += 1;
+        if counts.can_add(v) && visit(Move::Add(v), gain).is_break() {
+            return evaluated;
+        }
+    }
+    for &v in state.selected() {
+        let Some(gain) = state.drop_gain(v) else { break };
+        evaluated += 1;
+        if counts.can_drop(v) && visit(Move::Drop(v), gain).is_break() {
+            return evaluated;
+        }
+    }
+    for &out in state.selected() {
+        for &inc in state.unselected() {
+            evaluated += 1;
+            if counts.can_swap(out, inc) && visit(Move::Swap { out, inc }, state.swap_gain(out, inc)).is_break() {
+                return evaluated;
+            }
+        }
+    }
+    evaluated
+}
+
 // ============ First Improvement (like your original) ============
-fn first_improvement_search(data: &MdpData, max_iters: usize) -> (Vec<usize>, f64) {
-    let mut rng = rand::thread_rng();
-    let mut all_indices: Vec<usize> = (0..data.n).collect();
-    all_indices.shuffle(&mut rng);
-    
-    let mut selected: Vec<usize> = all_indices[0..data.k].to_vec();
-    let mut unselected: Vec<usize> = all_indices[data.k..].to_vec();
-    let mut current_diversity = calculate_diversity(&selected, data);
-    
+fn first_improvement_search<'a>(
+    mut searched: Searched<'_, impl IncrementalState<'a>>,
+    max_iters: usize,
+    tracker: &mut BudgetTracker,
+) -> (Vec<usize>, f64) {
     for _ in 0..max_iters {
-        let mut improved = false;
-        
-        'outer: for i in 0..selected.len() {
-            for j in 0..unselected.len() {
-                let gain = calculate_swap_gain(selected[i], unselected[j], &selected, data);
-                
-                if gain > 1e-9 { // Small epsilon for floating point
-                    let temp = selected[i];
-                    selected[i] = unselected[j];
-                    unselected[j] = temp;
-                    current_diversity += gain;
-                    improved = true;
-                    break 'outer;
-                }
-            }
+        if tracker.exhausted() {
+            break;
         }
+
+        let mut improving_move = None;
+        let evaluated = scan_moves(&searched, |mv, gain| {
+            if gain > 1e-9 { // Small epsilon for floating point
+                improving_move = Some(mv);
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        });
+        tracker.add_evaluations(evaluated);
         
-        if !improved {
-            break;
+        match improving_move {
+            Some(mv) => mv.apply(&mut searched),
+            None => break,
         }
+        tracker.next_iteration();
+        tracker.observe(searched.state.value());
     }
     
-    (selected, current_diversity)
+    let diversity = searched.state.value();
+    (searched.state.into_selected(), diversity)
 }
 
 // ============ Best Improvement ============
-fn best_improvement_search(data: &MdpData, max_iters: usize) -> (Vec<usize>, f64) {
-    let mut rng = rand::thread_rng();
-    let mut all_indices: Vec<usize> = (0..data.n).collect();
-    all_indices.shuffle(&mut rng);
-    
-    let mut selected: Vec<usize> = all_indices[0..data.k].to_vec();
-    let mut unselected: Vec<usize> = all_indices[data.k..].to_vec();
-    let mut current_diversity = calculate_diversity(&selected, data);
-    
+fn best_improvement_search<'a>(
+    mut searched: Searched<'_, impl IncrementalState<'a>>,
+    max_iters: usize,
+    tracker: &mut BudgetTracker,
+) -> (Vec<usize>, f64) {
     for _ in 0..max_iters {
-        let mut best_swap = None;
-        let mut best_gain = 0.0;
-        
-        // Examine all possible swaps, find the best one
-        for i in 0..selected.len() {
-            for j in 0..unselected.len() {
-                let gain = calculate_swap_gain(selected[i], unselected[j], &selected, data);
-                if gain > best_gain {
-                    best_gain = gain;
-                    best_swap = Some((i, j));
-                }
-            }
+        if tracker.exhausted() {
+            break;
         }
-        
-        if let Some((i, j)) = best_swap {
-            let temp = selected[i];
-            selected[i] = unselected[j];
-            unselected[j] = temp;
-            current_diversity += best_gain;
-        } else {
-            break; // No improvement found
+
+        match best_move(&searched, tracker) {
+            Some(mv) => mv.apply(&mut searched),
+            None => break, // No improvement found
         }
+        tracker.next_iteration();
+        tracker.observe(searched.state.value());
     }
     
-    (selected, current_diversity)
+    let diversity = searched.state.value();
+    (searched.state.into_selected(), diversity)
+}
+
+// Examines the whole neighborhood for the move with the largest positive gain
+fn best_move<'a, S: IncrementalState<'a>>(searched: &Searched<'_, S>, tracker: &mut BudgetTracker) -> Option<Move> {
+    let mut best_move = None;
+    let mut best_gain = 0.0;
+    let evaluated = scan_moves(searched, |mv, gain| {
+        if gain > best_gain {
+            best_gain = gain;
+            best_move = Some(mv);
+        }
+        ControlFlow::Continue(())
+    });
+    tracker.add_evaluations(evaluated);
+    best_move
 }
 
 // ============ Tabu Search ============
-fn tabu_search(data: &MdpData, max_iters: usize, tabu_tenure: usize) -> (Vec<usize>, f64) {
-    let mut rng = rand::thread_rng();
-    let mut all_indices: Vec<usize> = (0..data.n).collect();
-    all_indices.shuffle(&mut rng);
+fn tabu_search<'a>(
+    mut current: Searched<'_, impl IncrementalState<'a>>,
+    max_iters: usize,
+    tabu_tenure: usize,
+    tracker: &mut BudgetTracker,
+) -> (Vec<usize>, f64) {
+    let mut best_selected = current.state.selected().to_vec();
+    let mut best_diversity = current.state.value();
     
-    let mut current_selected: Vec<usize> = all_indices[0..data.k].to_vec();
-    let mut current_unselected: Vec<usize> = all_indices[data.k..].to_vec();
-    let mut current_diversity = calculate_diversity(&current_selected, data);
-    
-    let mut best_selected = current_selected.clone();
-    let mut best_diversity = current_diversity;
-    
-    // Tabu list: stores (element_in, element_out, iteration_when_tabu_expires)
-    let mut tabu_list: Vec<(usize, usize, usize)> = Vec::new();
+    // Tabu list: stores (forbidden move, iteration_when_tabu_expires)
+    let mut tabu_list: Vec<(Move, usize)> = Vec::new();
     
     for iter in 0..max_iters {
-        let mut best_swap = None;
-        let mut best_swap_gain = f64::NEG_INFINITY;
+        if tracker.exhausted() {
+            break;
+        }
+
+        let mut best_move = None;
+        let mut best_move_gain = f64::NEG_INFINITY;
         
         // Find best non-tabu move (or best tabu move if it's better than best known)
-        for i in 0..current_selected.len() {
-            for j in 0..current_unselected.len() {
-                let elem_out = current_selected[i];
-                let elem_in = current_unselected[j];
-                let gain = calculate_swap_gain(elem_out, elem_in, &current_selected, data);
-                
-                let is_tabu = tabu_list.iter().any(|(tin, tout, expires)| {
-                    *tin == elem_in && *tout == elem_out && *expires > iter
-                });
-                
-                // Aspiration criterion: accept tabu move if it beats best known
-                let new_diversity = current_diversity + gain;
-                let aspiration = new_diversity > best_diversity;
-                
-                if !is_tabu || aspiration {
-                    if gain > best_swap_gain {
-                        best_swap_gain = gain;
-                        best_swap = Some((i, j, elem_in, elem_out));
-                    }
-                }
+        let current_value = current.state.value();
+        let evaluated = scan_moves(&current, |mv, gain| {
+            let is_tabu = tabu_list.iter().any(|(forbidden, expires)| *forbidden == mv && *expires > iter);
+            
+            // Aspiration criterion: accept tabu move if it beats best known
+            let new_diversity = current_value + gain;
+            let aspiration = new_diversity > best_diversity;
+            
+            if (!is_tabu || aspiration) && gain > best_move_gain {
+                best_move_gain = gain;
+                best_move = Some(mv);
             }
-        }
+            ControlFlow::Continue(())
+        });
+        tracker.add_evaluations(evaluated);
         
-        if let Some((i, j, elem_in, elem_out)) = best_swap {
-            // Perform swap
-            current_selected[i] = elem_in;
-            current_unselected[j] = elem_out;
-            current_diversity += best_swap_gain;
+        if let Some(mv) = best_move {
+            // Perform move
+            mv.apply(&mut current);
             
             // Update tabu list
-            tabu_list.push((elem_out, elem_in, iter + tabu_tenure));
+            tabu_list.push((mv.reverse(), iter + tabu_tenure));
             
             // Clean old tabu entries
-            tabu_list.retain(|(_, _, expires)| *expires > iter);
+            tabu_list.retain(|(_, expires)| *expires > iter);
             
             // Update best solution
-            if current_diversity > best_diversity {
-                best_diversity = current_diversity;
-                best_selected = current_selected.clone();
+            if current.state.value() > best_diversity {
+                best_diversity = current.state.value();
+                best_selected = current.state.selected().to_vec();
             }
+            tracker.next_iteration();
+            tracker.observe(best_diversity);
         } else {
             break; // No valid moves
         }
@@ -171,25 +375,3 @@ fn tabu_search(data: &MdpData, max_iters: usize, tabu_tenure: usize) -> (Vec<usi
     
     (best_selected, best_diversity)
 }
-
-// ============ Helper Functions ============
-fn calculate_diversity(selected: &[usize], data: &MdpData) -> f64 {
-    let mut sum = 0.0;
-    for i in 0..selected.len() {
-        for j in (i + 1)..selected.len() {
-            sum += data.get_dist(selected[i], selected[j]);
-        }
-    }
-    sum
-}
-
-fn calculate_swap_gain(old: usize, new: usize, selected: &[usize], data: &MdpData) -> f64 {
-    let mut gain = 0.0;
-    for &s in selected {
-        if s == old {
-            continue;
-        }
-        gain += data.get_dist(new, s) - data.get_dist(old, s);
-    }
-    gain
-}
\ No newline at end of file
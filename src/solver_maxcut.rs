@@ -0,0 +1,489 @@
+//! MaxCut on a weighted graph: split the vertices into two sides maximizing the
+//! total weight of the edges between them. GRASP, tabu search and a genetic
+//! algorithm all move by flipping one vertex to the other side, with the gain
+//! of every flip kept up to date like the MDP solvers' swap gains; with the
+//! `gurobi` feature the QUBO model is also solved by Gurobi.
+
+use crate::graph::Graph;
+use crate::solver::{
+    parse_fraction, parse_param, resolve_seed, seed_param, Budget, BudgetTracker, ParamError, SolveOutcome,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Parameters of a MaxCut search.
+pub struct MaxCutConfig {
+    pub method: MaxCutMethod,
+    pub max_iters: usize,  // GRASP starts, tabu flips or GA offspring
+    pub seed: Option<u64>, // None draws a fresh seed per run
+}
+
+/// Search strategy of a `MaxCutSolver`.
+pub enum MaxCutMethod {
+    Grasp { alpha: f64 },
+    TabuSearch { tabu_tenure: usize },
+    Genetic { population_size: usize, mutation_rate: f64 }, // Per-vertex flip probability
+    #[cfg(feature = "gurobi")]
+    Qubo, // Gurobi on the QUBO model; max_iters is unused
+}
+
+impl Default for MaxCutConfig {
+    fn default() -> Self {
+        MaxCutConfig {
+            method: MaxCutMethod::TabuSearch { tabu_tenure: 20 },
+            max_iters: 10_000,
+            seed: None,
+        }
+    }
+}
+
+type ConfigFactory = fn() -> MaxCutConfig;
+
+// Registered MaxCut solvers, selected by `--problem maxcut`
+const REGISTRY: &[(&str, ConfigFactory)] = &[
+    ("Cut-GRASP", || MaxCutConfig {
+        method: MaxCutMethod::Grasp { alpha: 0.2 },
+        max_iters: 50,
+        seed: None,
+    }),
+    ("Cut-Tabu", MaxCutConfig::default),
+    ("Cut-GA", || MaxCutConfig {
+        method: MaxCutMethod::Genetic { population_size: 20, mutation_rate: 0.05 },
+        max_iters: 500,
+        seed: None,
+    }),
+    #[cfg(feature = "gurobi")]
+    ("Cut-QUBO", || MaxCutConfig {
+        method: MaxCutMethod::Qubo,
+        max_iters: 0,
+        seed: None,
+    }),
+];
+
+/// Names of all registered MaxCut solvers, in registry order.
+pub fn max_cut_solver_names() -> Vec<&'static str> {
+    REGISTRY.iter().map(|(name, _)| *name).collect()
+}
+
+/// Creates a MaxCut solver with default parameters by (case-insensitive) name;
+/// the `Cut-` prefix may be left out, so `grasp` names Cut-GRASP.
+pub fn create_max_cut_solver(name: &str) -> Option<MaxCutSolver> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| {
+            registered.eq_ignore_ascii_case(name)
+                || registered.strip_prefix("Cut-").is_some_and(|method| method.eq_ignore_ascii_case(name))
+        })
+        .map(|(_, config)| MaxCutSolver::new(config()))
+}
+
+#[derive(Default)]
+pub struct MaxCutSolver {
+    pub config: MaxCutConfig,
+}
+
+impl MaxCutSolver {
+    pub fn new(config: MaxCutConfig) -> Self {
+        MaxCutSolver { config }
+    }
+
+    pub fn name(&self) -> &str {
+        match self.config.method {
+            MaxCutMethod::Grasp { .. } => "Cut-GRASP",
+            MaxCutMethod::TabuSearch { .. } => "Cut-Tabu",
+            MaxCutMethod::Genetic { .. } => "Cut-GA",
+            #[cfg(feature = "gurobi")]
+            MaxCutMethod::Qubo => "Cut-QUBO",
+        }
+    }
+
+    /// Current parameter values, for logging.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("max_iters", self.config.max_iters.to_string()),
+            ("seed", seed_param(self.config.seed)),
+        ];
+        match self.config.method {
+            MaxCutMethod::Grasp { alpha } => params.push(("alpha", alpha.to_string())),
+            MaxCutMethod::TabuSearch { tabu_tenure } => params.push(("tabu_tenure", tabu_tenure.to_string())),
+            MaxCutMethod::Genetic { population_size, mutation_rate } => {
+                params.push(("population_size", population_size.to_string()));
+                params.push(("mutation_rate", mutation_rate.to_string()));
+            }
+            #[cfg(feature = "gurobi")]
+            MaxCutMethod::Qubo => {}
+        }
+        params
+    }
+
+    /// Sets a parameter from its textual value, like `Solver::set_param`.
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("max_iters", _) => self.config.max_iters = parse_param(key, value)?,
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("alpha", MaxCutMethod::Grasp { alpha }) => *alpha = parse_fraction(key, value)?,
+            ("tabu_tenure", MaxCutMethod::TabuSearch { tabu_tenure }) => *tabu_tenure = parse_param(key, value)?,
+            ("population_size", MaxCutMethod::Genetic { population_size, .. }) => {
+                *population_size = parse_param(key, value)?
+            }
+            ("mutation_rate", MaxCutMethod::Genetic { mutation_rate, .. }) => *mutation_rate = parse_param(key, value)?,
+            _ => {
+                return Err(ParamError::Unknown {
+                    solver: self.name().to_string(),
+                    key: key.to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the configured search; the outcome selects the vertices on one
+    /// side of the cut and its diversity is the cut weight.
+    pub fn solve(&self, graph: &Graph, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        if graph.n == 0 {
+            return SolveOutcome::new(Vec::new(), 0.0).with_seed(seed).with_detail("objective", "max-cut".to_string());
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (side, value) = match self.config.method {
+            MaxCutMethod::Grasp { alpha } => grasp(graph, alpha, self.config.max_iters, budget, &mut rng),
+            MaxCutMethod::TabuSearch { tabu_tenure } => {
+                tabu_search(graph, tabu_tenure, self.config.max_iters, budget, &mut rng)
+            }
+            MaxCutMethod::Genetic { population_size, mutation_rate } => {
+                let population_size = population_size.max(2);
+                genetic(graph, population_size, mutation_rate, self.config.max_iters, budget, &mut rng)
+            }
+            #[cfg(feature = "gurobi")]
+            MaxCutMethod::Qubo => return self.solve_qubo(graph, seed, budget),
+        };
+        SolveOutcome::new(side_members(&side), value).with_seed(seed).with_detail("objective", "max-cut".to_string())
+    }
+
+    #[cfg(feature = "gurobi")]
+    fn solve_qubo(&self, graph: &Graph, seed: u64, budget: &Budget) -> SolveOutcome {
+        let (stats, side) = match crate::solver_qubo::solve_max_cut(graph, Some(seed), budget) {
+            Ok(run) => run,
+            Err(e) => return SolveOutcome::failed().with_detail("error", e.to_string()),
+        };
+        let outcome = match side {
+            Some(side) => SolveOutcome {
+                seed: Some(seed),
+                proven_optimal: stats.is_optimal(),
+                ..SolveOutcome::new(side_members(&side), graph.cut_value(&side))
+            }
+            .with_detail("objective", "max-cut".to_string()),
+            None => SolveOutcome::failed(),
+        };
+        stats.annotate(outcome)
+    }
+}
+
+// Vertices on the `true` side
+fn side_members(side: &[bool]) -> Vec<usize> {
+    (0..side.len()).filter(|&v| side[v]).collect()
+}
+
+// ============ Incremental State ============
+
+/// A cut with the gain of flipping each vertex: `gain[v]` is the weight of v's
+/// uncut edges minus that of its cut ones. A flip updates v's neighbors in O(deg v).
+#[derive(Clone)]
+pub struct CutState<'a> {
+    graph: &'a Graph,
+    side: Vec<bool>,
+    gain: Vec<f64>,
+    value: f64,
+}
+
+impl<'a> CutState<'a> {
+    pub fn new(graph: &'a Graph, side: Vec<bool>) -> Self {
+        let gain = (0..graph.n)
+            .map(|v| {
+                let edges = graph.neighbors(v).iter();
+                edges.map(|&(u, w)| if side[u] == side[v] { w } else { -w }).sum()
+            })
+            .collect();
+        let value = graph.cut_value(&side);
+        CutState { graph, side, gain, value }
+    }
+
+    pub fn random(graph: &'a Graph, rng: &mut impl Rng) -> Self {
+        CutState::new(graph, (0..graph.n).map(|_| rng.gen()).collect())
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn side(&self) -> &[bool] {
+        &self.side
+    }
+
+    pub fn flip_gain(&self, v: usize) -> f64 {
+        self.gain[v]
+    }
+
+    pub fn flip(&mut self, v: usize) {
+        self.value += self.gain[v];
+        self.gain[v] = -self.gain[v];
+        self.side[v] = !self.side[v];
+        for &(u, w) in self.graph.neighbors(v) {
+            // The edge is now uncut if u shares v's new side, and cut otherwise
+            if self.side[u] == self.side[v] {
+                self.gain[u] += 2.0 * w;
+            } else {
+                self.gain[u] -= 2.0 * w;
+            }
+        }
+    }
+
+    pub fn into_side(self) -> Vec<bool> {
+        self.side
+    }
+
+    // Vertex with the largest flip gain among those `allowed`
+    fn best_flip(&self, mut allowed: impl FnMut(usize, f64) -> bool) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        for (v, &gain) in self.gain.iter().enumerate() {
+            if allowed(v, gain) && best.is_none_or(|(_, best_gain)| gain > best_gain) {
+                best = Some((v, gain));
+            }
+        }
+        best
+    }
+}
+
+// Flips the best improving vertex until none improves
+fn local_search(state: &mut CutState, tracker: &mut BudgetTracker) {
+    while !tracker.exhausted() {
+        tracker.add_evaluations(state.graph.n);
+        match state.best_flip(|_, gain| gain > 1e-9) {
+            Some((v, _)) => state.flip(v),
+            None => break,
+        }
+    }
+}
+
+// ============ GRASP ============
+
+// One budget iteration is one construction + local search start
+fn grasp(graph: &Graph, alpha: f64, starts: usize, budget: &Budget, rng: &mut impl Rng) -> (Vec<bool>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+    let mut best: Option<(Vec<bool>, f64)> = None;
+    for _ in 0..starts.max(1) {
+        let mut state = greedy_randomized_construction(graph, alpha, &mut tracker, rng);
+        local_search(&mut state, &mut tracker);
+        tracker.observe(state.value);
+        tracker.next_iteration();
+        if best.as_ref().is_none_or(|(_, value)| state.value > *value) {
+            best = Some((state.side.clone(), state.value));
+        }
+        if tracker.exhausted() {
+            break;
+        }
+    }
+    best.expect("at least one start runs")
+}
+
+// Places the vertices one by one. A vertex's greedy value is the weight it
+// would cut on its better side, given the vertices placed so far; the next
+// vertex is drawn from those within `alpha` of the best value.
+fn greedy_randomized_construction<'a>(
+    graph: &'a Graph,
+    alpha: f64,
+    tracker: &mut BudgetTracker,
+    rng: &mut impl Rng,
+) -> CutState<'a> {
+    let n = graph.n;
+    let mut side = vec![false; n];
+    let mut placed = vec![false; n];
+    // Weight from each vertex to the placed vertices on the false / true side
+    let mut to_false = vec![0.0; n];
+    let mut to_true = vec![0.0; n];
+
+    for _ in 0..n {
+        let open: Vec<usize> = (0..n).filter(|&v| !placed[v]).collect();
+        tracker.add_evaluations(open.len());
+        let greedy_value = |v: usize| f64::max(to_false[v], to_true[v]);
+        let (low, high) = open.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &v| {
+            (low.min(greedy_value(v)), high.max(greedy_value(v)))
+        });
+        let threshold = high - alpha * (high - low);
+        let rcl: Vec<usize> = open.into_iter().filter(|&v| greedy_value(v) >= threshold).collect();
+        let v = rcl[rng.gen_range(0..rcl.len())];
+
+        // Joining the true side cuts the edges to the false side, and vice versa
+        side[v] = to_false[v] > to_true[v] || (to_false[v] == to_true[v] && rng.gen());
+        placed[v] = true;
+        for &(u, w) in graph.neighbors(v) {
+            if side[v] {
+                to_true[u] += w;
+            } else {
+                to_false[u] += w;
+            }
+        }
+    }
+    CutState::new(graph, side)
+}
+
+// ============ Tabu Search ============
+
+// Flips the best non-tabu vertex each iteration, even when it worsens the cut;
+// a flipped vertex stays put for `tabu_tenure` iterations unless flipping it
+// back yields a new best. One budget iteration is one flip.
+fn tabu_search(
+    graph: &Graph,
+    tabu_tenure: usize,
+    max_iters: usize,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<bool>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+    let mut state = CutState::random(graph, rng);
+    local_search(&mut state, &mut tracker);
+    let mut best_side = state.side.clone();
+    let mut best_value = state.value;
+    let mut tabu_until = vec![0usize; graph.n];
+    // A tenure near n would leave no vertex to flip on small graphs
+    let tabu_tenure = tabu_tenure.min(graph.n / 2);
+
+    for iter in 1..=max_iters {
+        if tracker.exhausted() {
+            break;
+        }
+        tracker.add_evaluations(graph.n);
+        let current = state.value;
+        let chosen = state.best_flip(|v, gain| tabu_until[v] < iter || current + gain > best_value + 1e-9);
+        let Some((v, _)) = chosen else { break };
+        state.flip(v);
+        tabu_until[v] = iter + tabu_tenure;
+        tracker.next_iteration();
+        tracker.observe(state.value);
+
+        if state.value > best_value + 1e-9 {
+            best_value = state.value;
+            best_side.clone_from(&state.side);
+        }
+    }
+    (best_side, best_value)
+}
+
+// ============ Genetic Algorithm ============
+
+// Steady-state memetic search: each offspring of two tournament-selected
+// parents gets uniform crossover, per-vertex mutation and a local search, and
+// replaces the worst individual when it is better and not already present.
+// One budget iteration is one offspring.
+fn genetic(
+    graph: &Graph,
+    population_size: usize,
+    mutation_rate: f64,
+    offspring: usize,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<bool>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+    let mut population: Vec<CutState> = (0..population_size)
+        .map(|_| {
+            let mut state = CutState::random(graph, rng);
+            local_search(&mut state, &mut tracker);
+            state
+        })
+        .collect();
+
+    for _ in 0..offspring {
+        if tracker.exhausted() {
+            break;
+        }
+        let first = tournament(&population, rng);
+        let second = tournament(&population, rng);
+        let side = (0..graph.n)
+            .map(|v| {
+                let inherited = if rng.gen() { population[first].side[v] } else { population[second].side[v] };
+                inherited ^ (rng.gen::<f64>() < mutation_rate)
+            })
+            .collect();
+        let mut child = CutState::new(graph, side);
+        local_search(&mut child, &mut tracker);
+        tracker.next_iteration();
+        tracker.observe(child.value);
+
+        let worst = (0..population.len())
+            .min_by(|&a, &b| population[a].value.total_cmp(&population[b].value))
+            .expect("the population is not empty");
+        // A cut and its mirror image are the same solution
+        let duplicate = population.iter().any(|other| {
+            let mirrored = other.side[0] != child.side[0];
+            other.side.iter().zip(&child.side).all(|(a, b)| (a != b) == mirrored)
+        });
+        if child.value > population[worst].value && !duplicate {
+            population[worst] = child;
+        }
+    }
+
+    let best = population
+        .into_iter()
+        .max_by(|a, b| a.value.total_cmp(&b.value))
+        .expect("the population is not empty");
+    let value = best.value;
+    (best.into_side(), value)
+}
+
+// Index of the better of two random individuals
+fn tournament(population: &[CutState], rng: &mut impl Rng) -> usize {
+    let a = rng.gen_range(0..population.len());
+    let b = rng.gen_range(0..population.len());
+    if population[a].value >= population[b].value {
+        a
+    } else {
+        b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sparse graph with weights in -5..5, parallel edges and self-loops included
+    fn random_graph(n: usize, edges: usize, rng: &mut StdRng) -> Graph {
+        let edges: Vec<(usize, usize, f64)> =
+            (0..edges).map(|_| (rng.gen_range(0..n), rng.gen_range(0..n), rng.gen_range(-5.0..5.0))).collect();
+        Graph::new(n, edges)
+    }
+
+    fn assert_close(actual: f64, expected: f64, context: &str) {
+        assert!((actual - expected).abs() < 1e-6, "{}: {} != {}", context, actual, expected);
+    }
+
+    #[test]
+    fn flip_gains_match_cut_value() {
+        let mut rng = StdRng::seed_from_u64(31);
+        let graph = random_graph(20, 60, &mut rng);
+        let mut state = CutState::random(&graph, &mut rng);
+        assert_close(state.value(), graph.cut_value(state.side()), "initial cut");
+
+        for step in 0..300 {
+            for v in 0..graph.n {
+                let mut flipped = state.side().to_vec();
+                flipped[v] = !flipped[v];
+                let expected = graph.cut_value(&flipped) - graph.cut_value(state.side());
+                assert_close(state.flip_gain(v), expected, &format!("step {}: gain of {}", step, v));
+            }
+            let v = rng.gen_range(0..graph.n);
+            state.flip(v);
+            assert_close(state.value(), graph.cut_value(state.side()), &format!("step {}: flip {}", step, v));
+        }
+    }
+
+    #[test]
+    fn local_search_ends_in_a_local_optimum() {
+        let mut rng = StdRng::seed_from_u64(32);
+        let graph = random_graph(30, 120, &mut rng);
+        let budget = Budget::default();
+        let mut state = CutState::random(&graph, &mut rng);
+        local_search(&mut state, &mut BudgetTracker::start(&budget));
+        assert_close(state.value(), graph.cut_value(state.side()), "value");
+        assert!((0..graph.n).all(|v| state.flip_gain(v) <= 1e-9));
+    }
+}
@@ -0,0 +1,362 @@
+//! Exact MIP formulations of the MDP with a hard cardinality constraint, solved with Gurobi.
+
+use grb::prelude::*;
+use crate::evaluation::{calculate_diversity, check_selection_for, pareto_front, Objective, ParetoPoint};
+use crate::formulation::{MipModel, Sense, VarKind};
+use crate::parser::{CapacitySpec, MdpData};
+use crate::solver::{parse_param, unknown_param, Budget, ParamError, SolveOutcome, Solver};
+use crate::solver_qubo::{configure_model, extract_selection, set_mip_start, GurobiStats};
+use grb::constr::IneqExpr;
+use grb::expr::{LinExpr, QuadExpr};
+use std::time::{Duration, Instant};
+
+pub use crate::formulation::MipFormulation;
+
+pub struct MipConfig {
+    pub objective: Objective,
+    pub formulation: MipFormulation, // Linearization of the max-sum objective
+    pub mip_gap: f64,
+    pub seed: Option<u64>, // Passed to Gurobi's Seed parameter; Gurobi's default when None
+}
+
+impl Default for MipConfig {
+    fn default() -> Self {
+        MipConfig {
+            objective: Objective::MaxSum,
+            formulation: MipFormulation::Quadratic,
+            mip_gap: 1e-4, // Gurobi's default
+            seed: None,
+        }
+    }
+}
+
+// Dinkelbach's method stops once the subproblem optimum is within this of 0 (scaled by the ratio)
+const DINKELBACH_TOLERANCE: f64 = 1e-6;
+const DINKELBACH_MAX_ROUNDS: usize = 50;
+
+/// Gurobi's statistics and the incumbent with its objective value, if the run found one.
+pub struct MipRun {
+    pub stats: GurobiStats, // Of the last model solved
+    pub solution: Option<(Vec<usize>, f64)>,
+    pub proven_optimal: bool,
+    pub rounds: usize, // Models solved: Dinkelbach iterations for max-mean, otherwise 1
+}
+
+/// The front found by `solve_epsilon_constraint`.
+pub struct EpsilonRun {
+    pub front: Vec<ParetoPoint>, // Non-dominated, by decreasing sum
+    pub models: usize,
+    pub proven_optimal: bool, // Every model was solved to optimality and no ε was left: the front is exact
+}
+
+/// Solves one of the MIP formulations with Gurobi.
+pub struct MipSolver {
+    pub config: MipConfig,
+    name: String,
+}
+
+impl MipSolver {
+    pub fn new(config: MipConfig) -> Self {
+        let name = solver_name(&config);
+        MipSolver { config, name }
+    }
+}
+
+// MIP-<formulation> for the max-sum objective, MIP-MaxMin for max-min, ...
+fn solver_name(config: &MipConfig) -> String {
+    match config.objective {
+        Objective::MaxSum => format!("MIP-{}", config.formulation.name()),
+        Objective::MaxMin => "MIP-MaxMin".to_string(),
+        Objective::MaxMean => "MIP-MaxMean".to_string(),
+        Objective::MaxMinSum => "MIP-MaxMinSum".to_string(),
+        Objective::MinDiff => "MIP-MinDiff".to_string(),
+    }
+}
+
+impl Solver for MipSolver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("objective", self.config.objective.to_string()),
+            ("formulation", self.config.formulation.name().to_string()),
+            ("mip_gap", self.config.mip_gap.to_string()),
+            ("seed", self.config.seed.map_or_else(|| "default".to_string(), |seed| seed.to_string())),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "objective" => self.config.objective = parse_param(key, value)?,
+            "formulation" => self.config.formulation = parse_param(key, value)?,
+            "mip_gap" => self.config.mip_gap = parse_param(key, value)?,
+            "seed" => self.config.seed = Some(parse_param(key, value)?),
+            _ => return Err(unknown_param(self, key)),
+        }
+        self.name = solver_name(&self.config);
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        self.run(data, budget, None)
+    }
+
+    fn solve_from(&self, data: &MdpData, budget: &Budget, start: &[usize]) -> SolveOutcome {
+        self.run(data, budget, Some(start))
+    }
+}
+
+impl MipSolver {
+    fn run(&self, data: &MdpData, budget: &Budget, start: Option<&[usize]>) -> SolveOutcome {
+        let objective = self.config.objective;
+        let run = match solve_mip(data, &self.config, start, budget) {
+            Ok(run) => run,
+            Err(e) => return SolveOutcome::failed().with_detail("error", e.to_string()),
+        };
+        let outcome = match run.solution {
+            Some((selected, value)) if check_selection_for(&selected, data, objective).is_empty() => SolveOutcome {
+                seed: self.config.seed,
+                proven_optimal: run.proven_optimal,
+                ..SolveOutcome::new(selected, value)
+            }
+            .with_objective(objective),
+            _ => SolveOutcome::failed(),
+        };
+        let outcome = run.stats.annotate(outcome);
+        if objective == Objective::MaxMean {
+            outcome.with_detail("dinkelbach_rounds", run.rounds)
+        } else {
+            outcome
+        }
+    }
+}
+
+/// Builds and solves the configured formulation. A `start` selection is
+/// handed to Gurobi as a MIP start; auxiliary variables are left to Gurobi.
+/// Max-mean is solved by Dinkelbach's method, see `solve_max_mean`.
+pub fn solve_mip(
+    data: &MdpData,
+    config: &MipConfig,
+    start: Option<&[usize]>,
+    budget: &Budget,
+) -> grb::Result<MipRun> {
+    if config.objective == Objective::MaxMean {
+        return solve_max_mean(data, config, start, budget);
+    }
+    let formulation = MipModel::for_objective(data, config.objective, config.formulation);
+    let (stats, selected) = solve_model(data, &formulation, config, start, None, budget)?;
+    let solution = selected.map(|selected| {
+        let value = config.objective.evaluate(&selected, data);
+        (selected, value)
+    });
+    Ok(MipRun { proven_optimal: stats.is_optimal(), stats, solution, rounds: 1 })
+}
+
+/// Dinkelbach's method for max-mean dispersion: solve `MipModel::max_mean` at
+/// ratio λ, set λ to the mean of its solution, and repeat until the subproblem
+/// optimum is 0, which proves λ optimal. λ starts at the mean of a valid
+/// `start`, or 0; the budget's time limit is shared across rounds, and its
+/// target, a max-mean value, is checked against the best mean after each round.
+pub fn solve_max_mean(
+    data: &MdpData,
+    config: &MipConfig,
+    start: Option<&[usize]>,
+    budget: &Budget,
+) -> grb::Result<MipRun> {
+    let started = Instant::now();
+    let mut best = start
+        .filter(|start| check_selection_for(start, data, Objective::MaxMean).is_empty())
+        .map(|start| (start.to_vec(), Objective::MaxMean.evaluate(start, data)));
+    let mut lambda = best.as_ref().map_or(0.0, |(_, value)| *value);
+    let mut last_stats = None;
+
+    for round in 1..=DINKELBACH_MAX_ROUNDS {
+        // The subproblems optimize sum - λ|S|, which the target says nothing about
+        let mut round_budget = Budget { target_value: None, ..budget.clone() };
+        if let Some(limit) = budget.time_limit {
+            match limit.checked_sub(started.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => round_budget.time_limit = Some(remaining),
+                _ if last_stats.is_some() => break,
+                // Always solve at least once, if only to report Gurobi's status
+                _ => round_budget.time_limit = Some(Duration::ZERO),
+            }
+        }
+
+        let model = MipModel::max_mean(data, lambda);
+        let tolerance = DINKELBACH_TOLERANCE * lambda.abs().max(1.0);
+        let incumbent = best.as_ref().map(|(selected, _)| selected.as_slice());
+        let (stats, selected) = solve_model(data, &model, config, incumbent, Some(tolerance), &round_budget)?;
+        let Some(selected) = selected else {
+            return Ok(MipRun { stats, solution: best, proven_optimal: false, rounds: round });
+        };
+
+        let value = Objective::MaxMean.evaluate(&selected, data);
+        // Subproblem value sum - λ|S| = |S| (mean - λ)
+        let converged = selected.len() as f64 * (value - lambda) <= tolerance;
+        if best.as_ref().is_none_or(|(_, best_value)| value > *best_value) {
+            best = Some((selected, value));
+        }
+        let target_reached = budget.target_value.is_some_and(|target| value >= target);
+        if converged || !stats.is_optimal() || target_reached {
+            let proven_optimal = converged && stats.is_optimal();
+            return Ok(MipRun { stats, solution: best, proven_optimal, rounds: round });
+        }
+        lambda = value;
+        last_stats = Some(stats);
+    }
+
+    Ok(MipRun {
+        stats: last_stats.expect("at least one round is solved"),
+        solution: best,
+        proven_optimal: false,
+        rounds: DINKELBACH_MAX_ROUNDS,
+    })
+}
+
+/// Solves `MipModel::capacitated` for the capacity, weights and cardinality
+/// limits of `spec`; the objective and formulation of `config` are ignored.
+pub fn solve_capacitated_mip(
+    data: &MdpData,
+    spec: &CapacitySpec,
+    config: &MipConfig,
+    budget: &Budget,
+) -> grb::Result<MipRun> {
+    let formulation = MipModel::capacitated(data, spec);
+    let (stats, selected) = solve_model(data, &formulation, config, None, None, budget)?;
+    let solution = selected.map(|selected| {
+        let value = calculate_diversity(&selected, data);
+        (selected, value)
+    });
+    Ok(MipRun { proven_optimal: stats.is_optimal(), stats, solution, rounds: 1 })
+}
+
+/// Epsilon-constraint method for the bi-objective (max-sum, max-min) problem:
+/// solve `MipModel::min_separated` at ε, raise ε to the next distance above the
+/// smallest one of the solution, and repeat from ε = -∞ until no selection is
+/// left or `max_points` models are solved. The budget's time limit is shared
+/// across models.
+pub fn solve_epsilon_constraint(
+    data: &MdpData,
+    config: &MipConfig,
+    max_points: usize,
+    budget: &Budget,
+) -> grb::Result<EpsilonRun> {
+    let started = Instant::now();
+    let mut levels: Vec<f64> = (0..data.n)
+        .flat_map(|i| ((i + 1)..data.n).map(move |j| data.get_dist(i, j)))
+        .collect();
+    levels.sort_by(f64::total_cmp);
+    levels.dedup();
+
+    let mut epsilon = f64::NEG_INFINITY;
+    let mut points = Vec::new();
+    let mut all_optimal = true;
+    let mut complete = false;
+    let mut models = 0;
+    while models < max_points {
+        let mut model_budget = budget.clone();
+        if let Some(limit) = budget.time_limit {
+            match limit.checked_sub(started.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => model_budget.time_limit = Some(remaining),
+                _ => break,
+            }
+        }
+
+        let model = MipModel::min_separated(data, epsilon);
+        let (stats, selected) = solve_model(data, &model, config, None, None, &model_budget)?;
+        models += 1;
+        let Some(selected) = selected else {
+            // No selection is that spread out, unless Gurobi stopped before finding one
+            complete = matches!(stats.status.as_str(), "Infeasible" | "InfOrUnbd");
+            break;
+        };
+        all_optimal &= stats.is_optimal();
+        let point = ParetoPoint::new(selected, data);
+        // Without pairs the smallest distance is always 0
+        let next = if data.k < 2 { None } else { levels.iter().copied().find(|&d| d > point.min) };
+        points.push(point);
+        match next {
+            Some(next) => epsilon = next,
+            None => {
+                complete = true;
+                break;
+            }
+        }
+    }
+
+    // A model's optimum may tie on the sum with the next one's at a larger min
+    Ok(EpsilonRun { front: pareto_front(points), models, proven_optimal: all_optimal && complete })
+}
+
+// Solves one model, returning Gurobi's statistics and the selection of its
+// incumbent. `mip_gap_abs` overrides Gurobi's absolute gap tolerance.
+fn solve_model(
+    data: &MdpData,
+    formulation: &MipModel,
+    config: &MipConfig,
+    start: Option<&[usize]>,
+    mip_gap_abs: Option<f64>,
+    budget: &Budget,
+) -> grb::Result<(GurobiStats, Option<Vec<usize>>)> {
+    let mut model = Model::new(&formulation.name)?;
+    configure_model(&mut model, config.mip_gap, config.seed, budget)?;
+    if let Some(gap) = mip_gap_abs {
+        model.set_param(param::MIPGapAbs, gap)?;
+    }
+
+    let vars = add_to_gurobi(&mut model, formulation)?;
+    let x = &vars[..data.n];
+    if let Some(start) = start {
+        set_mip_start(&mut model, x, start)?;
+    }
+
+    model.optimize()?;
+    let stats = GurobiStats::from_model(&model)?;
+    Ok((stats, extract_selection(&model, x)?))
+}
+
+/// Adds the variables, constraints and objective of `formulation` to `model`,
+/// returning the Gurobi variables in the same order.
+pub(crate) fn add_to_gurobi(model: &mut Model, formulation: &MipModel) -> grb::Result<Vec<Var>> {
+    let vars: Vec<Var> = formulation
+        .variables
+        .iter()
+        .map(|v| {
+            let kind = match v.kind {
+                VarKind::Binary => VarType::Binary,
+                VarKind::Continuous => VarType::Continuous,
+            };
+            model.add_var(&v.name, kind, 0.0, v.lower, v.upper, [])
+        })
+        .collect::<grb::Result<_>>()?;
+
+    for constraint in &formulation.constraints {
+        let mut lhs = LinExpr::new();
+        for &(v, coefficient) in &constraint.terms {
+            lhs.add_term(coefficient, vars[v]);
+        }
+        let sense = match constraint.sense {
+            Sense::LessEqual => ConstrSense::Less,
+            Sense::Equal => ConstrSense::Equal,
+            Sense::GreaterEqual => ConstrSense::Greater,
+        };
+        let inequality = IneqExpr {
+            lhs: lhs.into(),
+            sense,
+            rhs: constraint.rhs.into(),
+        };
+        model.add_constr(&constraint.name, inequality)?;
+    }
+
+    let mut obj = QuadExpr::new();
+    for &(v, coefficient) in &formulation.objective {
+        obj.add_term(coefficient, vars[v]);
+    }
+    for &(a, b, coefficient) in &formulation.quadratic_objective {
+        obj.add_qterm(coefficient, vars[a], vars[b]);
+    }
+    model.set_objective(obj, Maximize)?;
+    Ok(vars)
+}
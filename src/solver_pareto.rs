@@ -0,0 +1,190 @@
+//! Bi-objective MDP: approximate the Pareto front of (max-sum, max-min), which
+//! trades the total diversity of a selection against its closest pair. NSGA-II
+//! evolves a population of selections (`solver_population::solve_nsga2`); with
+//! the `gurobi` feature the epsilon-constraint method solves a sequence of MIPs
+//! (`solver_mip::solve_epsilon_constraint`) and can prove the front exact.
+
+use crate::evaluation::ParetoPoint;
+use crate::parser::MdpData;
+use crate::solver::{parse_param, resolve_seed, seed_param, Budget, Detail, ParamError};
+use crate::solver_population::{solve_nsga2, GeneticConfig};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Parameters of a Pareto front search.
+pub struct ParetoConfig {
+    pub method: ParetoMethod,
+    pub seed: Option<u64>, // NSGA-II draws a fresh seed per run when None; Gurobi keeps its default
+}
+
+/// Search strategy of a `ParetoSolver`.
+pub enum ParetoMethod {
+    Nsga2 { population_size: usize, generations: usize, crossover_rate: f64, mutation_rate: f64 },
+    #[cfg(feature = "gurobi")]
+    EpsilonConstraint { max_points: usize, mip_gap: f64 }, // At most max_points models are solved
+}
+
+impl Default for ParetoConfig {
+    fn default() -> Self {
+        ParetoConfig {
+            method: ParetoMethod::Nsga2 {
+                population_size: 50,
+                generations: 200,
+                crossover_rate: 0.8,
+                mutation_rate: 0.2,
+            },
+            seed: None,
+        }
+    }
+}
+
+type ConfigFactory = fn() -> ParetoConfig;
+
+// Registered Pareto solvers, selected by `--problem pareto`
+const REGISTRY: &[(&str, ConfigFactory)] = &[
+    ("Pareto-NSGA2", ParetoConfig::default),
+    #[cfg(feature = "gurobi")]
+    ("Pareto-Epsilon", || ParetoConfig {
+        method: ParetoMethod::EpsilonConstraint { max_points: 50, mip_gap: 1e-4 },
+        seed: None,
+    }),
+];
+
+/// Names of all registered Pareto solvers, in registry order.
+pub fn pareto_solver_names() -> Vec<&'static str> {
+    REGISTRY.iter().map(|(name, _)| *name).collect()
+}
+
+/// Creates a Pareto solver with default parameters by (case-insensitive) name;
+/// the `Pareto-` prefix may be left out, so `nsga2` names Pareto-NSGA2.
+pub fn create_pareto_solver(name: &str) -> Option<ParetoSolver> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| {
+            registered.eq_ignore_ascii_case(name)
+                || registered.strip_prefix("Pareto-").is_some_and(|method| method.eq_ignore_ascii_case(name))
+        })
+        .map(|(_, config)| ParetoSolver::new(config()))
+}
+
+/// What a Pareto solver hands back: the non-dominated selections it found.
+pub struct ParetoOutcome {
+    pub front: Vec<ParetoPoint>, // By decreasing sum, so increasing min
+    pub seed: Option<u64>,
+    pub proven_optimal: bool, // The front is the exact Pareto front
+    pub details: Vec<(&'static str, Detail)>,
+}
+
+#[derive(Default)]
+pub struct ParetoSolver {
+    pub config: ParetoConfig,
+}
+
+impl ParetoSolver {
+    pub fn new(config: ParetoConfig) -> Self {
+        ParetoSolver { config }
+    }
+
+    pub fn name(&self) -> &str {
+        match self.config.method {
+            ParetoMethod::Nsga2 { .. } => "Pareto-NSGA2",
+            #[cfg(feature = "gurobi")]
+            ParetoMethod::EpsilonConstraint { .. } => "Pareto-Epsilon",
+        }
+    }
+
+    /// Current parameter values, for logging.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self.config.method {
+            ParetoMethod::Nsga2 { population_size, generations, crossover_rate, mutation_rate } => vec![
+                ("population_size", population_size.to_string()),
+                ("generations", generations.to_string()),
+                ("crossover_rate", crossover_rate.to_string()),
+                ("mutation_rate", mutation_rate.to_string()),
+                ("seed", seed_param(self.config.seed)),
+            ],
+            #[cfg(feature = "gurobi")]
+            ParetoMethod::EpsilonConstraint { max_points, mip_gap } => vec![
+                ("max_points", max_points.to_string()),
+                ("mip_gap", mip_gap.to_string()),
+                ("seed", self.config.seed.map_or_else(|| "default".to_string(), |seed| seed.to_string())),
+            ],
+        }
+    }
+
+    /// Sets a parameter from its textual value, like `Solver::set_param`.
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("population_size", ParetoMethod::Nsga2 { population_size, .. }) => {
+                *population_size = parse_param(key, value)?
+            }
+            ("generations", ParetoMethod::Nsga2 { generations, .. }) => *generations = parse_param(key, value)?,
+            ("crossover_rate", ParetoMethod::Nsga2 { crossover_rate, .. }) => {
+                *crossover_rate = parse_param(key, value)?
+            }
+            ("mutation_rate", ParetoMethod::Nsga2 { mutation_rate, .. }) => *mutation_rate = parse_param(key, value)?,
+            #[cfg(feature = "gurobi")]
+            ("max_points", ParetoMethod::EpsilonConstraint { max_points, .. }) => {
+                *max_points = parse_param(key, value)?
+            }
+            #[cfg(feature = "gurobi")]
+            ("mip_gap", ParetoMethod::EpsilonConstraint { mip_gap, .. }) => *mip_gap = parse_param(key, value)?,
+            _ => {
+                return Err(ParamError::Unknown {
+                    solver: self.name().to_string(),
+                    key: key.to_string(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the configured search; the front meets the side constraints of `data`.
+    pub fn solve(&self, data: &MdpData, budget: &Budget) -> ParetoOutcome {
+        match self.config.method {
+            ParetoMethod::Nsga2 { population_size, generations, crossover_rate, mutation_rate } => {
+                let seed = resolve_seed(self.config.seed);
+                let config = GeneticConfig {
+                    population_size,
+                    generations,
+                    crossover_rate,
+                    mutation_rate,
+                    seed: Some(seed),
+                    ..GeneticConfig::default()
+                };
+                let front = solve_nsga2(data, &config, budget, &mut StdRng::seed_from_u64(seed));
+                ParetoOutcome { front, seed: Some(seed), proven_optimal: false, details: Vec::new() }
+            }
+            #[cfg(feature = "gurobi")]
+            ParetoMethod::EpsilonConstraint { max_points, mip_gap } => {
+                self.solve_epsilon_constraint(data, max_points, mip_gap, budget)
+            }
+        }
+    }
+
+    #[cfg(feature = "gurobi")]
+    fn solve_epsilon_constraint(
+        &self,
+        data: &MdpData,
+        max_points: usize,
+        mip_gap: f64,
+        budget: &Budget,
+    ) -> ParetoOutcome {
+        let config = crate::solver_mip::MipConfig { mip_gap, seed: self.config.seed, ..Default::default() };
+        match crate::solver_mip::solve_epsilon_constraint(data, &config, max_points, budget) {
+            Ok(run) => ParetoOutcome {
+                front: run.front,
+                seed: self.config.seed,
+                proven_optimal: run.proven_optimal,
+                details: vec![("models", run.models.into())],
+            },
+            Err(e) => ParetoOutcome {
+                front: Vec::new(),
+                seed: self.config.seed,
+                proven_optimal: false,
+                details: vec![("error", e.to_string().into())],
+            },
+        }
+    }
+}
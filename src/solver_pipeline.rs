@@ -0,0 +1,209 @@
+//! Heuristic-then-exact pipelines: a fast heuristic's incumbent warm-starts an exact solver.
+
+use crate::evaluation::Objective;
+use crate::parser::MdpData;
+use crate::solver::{
+    create_solver, parse_param, seed_param, unknown_param, Budget, ParamError, SolveOutcome, Solver,
+};
+use std::time::Instant;
+
+// Relative difference below which both stages' values count as equal
+const TIE_TOLERANCE: f64 = 1e-9;
+
+/// Runs `heuristic`, then `exact` through `Solver::solve_from` with the heuristic's selection.
+pub struct PipelineSolver {
+    heuristic: Box<dyn Solver>,
+    exact: Box<dyn Solver>,
+    pub heuristic_share: f64, // Fraction of the time limit given to the heuristic, in 0.0..=1.0
+    seed: Option<u64>,
+    name: String,
+}
+
+impl PipelineSolver {
+    /// Pipeline of two registered solvers, or `None` if either name is unknown.
+    pub fn new(heuristic: &str, exact: &str) -> Option<Self> {
+        Some(Self::from_solvers(create_solver(heuristic)?, create_solver(exact)?))
+    }
+
+    pub fn from_solvers(heuristic: Box<dyn Solver>, exact: Box<dyn Solver>) -> Self {
+        let name = format!("{}+{}", heuristic.name(), exact.name());
+        PipelineSolver {
+            heuristic,
+            exact,
+            heuristic_share: 0.1,
+            seed: None,
+            name,
+        }
+    }
+
+    // Swaps in another registered solver for one stage, keeping the pipeline's seed
+    fn replace_stage(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        let mut solver = create_solver(value).ok_or_else(|| ParamError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        })?;
+        if let Some(seed) = self.seed {
+            solver.set_param("seed", &seed.to_string())?;
+        }
+        if key == "heuristic" {
+            self.heuristic = solver;
+        } else {
+            self.exact = solver;
+        }
+        self.name = format!("{}+{}", self.heuristic.name(), self.exact.name());
+        Ok(())
+    }
+
+    // Objective the exact stage is configured with, which both stages' values are compared under
+    fn objective(&self) -> Objective {
+        let params = self.exact.params();
+        let configured = params.iter().find(|(key, _)| *key == "objective");
+        configured.and_then(|(_, value)| value.parse().ok()).unwrap_or_default()
+    }
+}
+
+impl Solver for PipelineSolver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("heuristic", self.heuristic.name().to_string()),
+            ("exact", self.exact.name().to_string()),
+            ("heuristic_share", self.heuristic_share.to_string()),
+            ("seed", seed_param(self.seed)),
+        ]
+    }
+
+    // Stage parameters are forwarded with a prefix, e.g. `heuristic.alpha` or `exact.penalty`
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        if let Some(stage_key) = key.strip_prefix("heuristic.") {
+            return self.heuristic.set_param(stage_key, value);
+        }
+        if let Some(stage_key) = key.strip_prefix("exact.") {
+            return self.exact.set_param(stage_key, value);
+        }
+        match key {
+            "heuristic" | "exact" => self.replace_stage(key, value)?,
+            "heuristic_share" => {
+                let share: f64 = parse_param(key, value)?;
+                if !(0.0..=1.0).contains(&share) {
+                    return Err(ParamError::InvalidValue { key: key.to_string(), value: value.to_string() });
+                }
+                self.heuristic_share = share;
+            }
+            "seed" => {
+                self.seed = Some(parse_param(key, value)?);
+                self.heuristic.set_param(key, value)?;
+                self.exact.set_param(key, value)?;
+            }
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let started = Instant::now();
+        // The heuristic only gets a share of the time; its own settings bound the rest
+        let heuristic_budget = Budget {
+            time_limit: budget.time_limit.map(|limit| limit.mul_f64(self.heuristic_share)),
+            ..Default::default()
+        };
+        let first = self.heuristic.solve(data, &heuristic_budget);
+        let heuristic_ms = started.elapsed().as_millis() as f64;
+
+        let exact_budget = Budget {
+            time_limit: budget.time_limit.map(|limit| limit.saturating_sub(started.elapsed())),
+            ..budget.clone()
+        };
+        let second = if first.success {
+            self.exact.solve_from(data, &exact_budget, &first.selected)
+        } else {
+            self.exact.solve(data, &exact_budget)
+        };
+
+        // The exact stage may end without an incumbent, e.g. on a Gurobi error;
+        // rounding differences alone never demote it
+        let heuristic_diversity = first.diversity;
+        let seed = first.seed;
+        let objective = self.objective();
+        let (first_score, second_score) = (objective.score(first.diversity), objective.score(second.diversity));
+        let exact_worse = second_score < first_score - TIE_TOLERANCE * first_score.abs().max(1.0);
+        let mut outcome = if first.success && (!second.success || exact_worse) {
+            first
+        } else {
+            second
+        };
+        outcome.seed = seed;
+        outcome
+            .with_detail("heuristic_diversity", heuristic_diversity)
+            .with_detail("heuristic_ms", heuristic_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate_instance, GeneratorConfig};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Stage that always returns `selected` with value `value` under `objective`
+    struct Fixed {
+        selected: Vec<usize>,
+        value: f64,
+        objective: Objective,
+    }
+
+    impl Solver for Fixed {
+        fn name(&self) -> &str {
+            "Fixed"
+        }
+
+        fn params(&self) -> Vec<(&'static str, String)> {
+            vec![("objective", self.objective.to_string())]
+        }
+
+        fn set_param(&mut self, key: &str, _value: &str) -> Result<(), ParamError> {
+            Err(unknown_param(self, key))
+        }
+
+        fn solve(&self, _data: &MdpData, _budget: &Budget) -> SolveOutcome {
+            SolveOutcome::new(self.selected.clone(), self.value).with_objective(self.objective)
+        }
+    }
+
+    fn pipeline(objective: Objective, heuristic_value: f64, exact_value: f64) -> PipelineSolver {
+        PipelineSolver::from_solvers(
+            Box::new(Fixed { selected: vec![0, 1], value: heuristic_value, objective }),
+            Box::new(Fixed { selected: vec![2, 3], value: exact_value, objective }),
+        )
+    }
+
+    #[test]
+    fn keeps_the_better_stage_under_the_configured_objective() {
+        let config = GeneratorConfig { n: 6, k: 2, ..Default::default() };
+        let data = generate_instance(&config, &mut StdRng::seed_from_u64(1));
+        let budget = Budget::default();
+        for (objective, heuristic, exact, kept) in [
+            (Objective::MaxSum, 10.0, 12.0, vec![2, 3]),
+            (Objective::MaxSum, 12.0, 10.0, vec![0, 1]),
+            (Objective::MinDiff, 10.0, 4.0, vec![2, 3]),
+            (Objective::MinDiff, 4.0, 10.0, vec![0, 1]),
+            (Objective::MinDiff, 4.0, 4.0, vec![2, 3]),
+        ] {
+            let outcome = pipeline(objective, heuristic, exact).solve(&data, &budget);
+            assert_eq!(outcome.selected, kept, "{} {} -> {}", objective, heuristic, exact);
+        }
+    }
+
+    #[test]
+    fn rejects_shares_outside_the_unit_interval() {
+        let mut solver = pipeline(Objective::MaxSum, 0.0, 0.0);
+        for share in ["-0.5", "1.5", "NaN"] {
+            assert!(matches!(solver.set_param("heuristic_share", share), Err(ParamError::InvalidValue { .. })));
+        }
+        assert!(solver.set_param("heuristic_share", "1").is_ok());
+    }
+}
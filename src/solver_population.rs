@@ -1,13 +1,31 @@
+//! Population-based search: a genetic algorithm with local improvement, and
+//! NSGA-II for the bi-objective (max-sum, max-min) problem.
+//! Every individual meets the side constraints of the instance: crossover and
+//! mutation only make admissible additions and swaps.
+
+use crate::evaluation::{
+    calculate_diversity, pareto_front, random_selection, ConstraintCounts, IncrementalState, MaxMinState, Objective,
+    ParetoPoint, SolutionState,
+};
 use crate::parser::MdpData;
+use crate::solver::{
+    parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError,
+    SolveOutcome, Solver,
+};
+use crate::solver_grasp::{solve_grasp, GraspConfig};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
 
+/// Parameters of the genetic algorithm.
 pub struct GeneticConfig {
     pub population_size: usize,
     pub generations: usize,
     pub crossover_rate: f64,
     pub mutation_rate: f64,
     pub elite_size: usize,  // Number of best individuals to carry over
+    pub seed: Option<u64>,  // None draws a fresh seed per run
 }
 
 impl Default for GeneticConfig {
@@ -18,6 +36,7 @@ impl Default for GeneticConfig {
             crossover_rate: 0.8,
             mutation_rate: 0.1,
             elite_size: 5,
+            seed: None,
         }
     }
 }
@@ -28,12 +47,77 @@ struct Individual {
     fitness: f64,
 }
 
-pub fn solve_genetic(data: &MdpData, config: &GeneticConfig) -> (Vec<usize>, f64) {
-    let mut rng = rand::thread_rng();
-    
+#[derive(Default)]
+pub struct GeneticSolver {
+    pub config: GeneticConfig,
+}
+
+impl Solver for GeneticSolver {
+    fn name(&self) -> &str {
+        "GA"
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("population_size", self.config.population_size.to_string()),
+            ("generations", self.config.generations.to_string()),
+            ("crossover_rate", self.config.crossover_rate.to_string()),
+            ("mutation_rate", self.config.mutation_rate.to_string()),
+            ("elite_size", self.config.elite_size.to_string()),
+            ("seed", seed_param(self.config.seed)),
+        ]
+    }
+
+    // The elites are copied out of the population, so it must hold at least one and all of them
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        let invalid = || ParamError::InvalidValue { key: key.to_string(), value: value.to_string() };
+        match key {
+            "population_size" => {
+                let size: usize = parse_param(key, value)?;
+                if size == 0 || size < self.config.elite_size {
+                    return Err(invalid());
+                }
+                self.config.population_size = size;
+            }
+            "generations" => self.config.generations = parse_param(key, value)?,
+            "crossover_rate" => self.config.crossover_rate = parse_param(key, value)?,
+            "mutation_rate" => self.config.mutation_rate = parse_param(key, value)?,
+            "elite_size" => {
+                let elites: usize = parse_param(key, value)?;
+                if elites > self.config.population_size {
+                    return Err(invalid());
+                }
+                self.config.elite_size = elites;
+            }
+            "seed" => self.config.seed = Some(parse_param(key, value)?),
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (selected, diversity) = solve_genetic(data, &self.config, budget, &mut rng);
+        SolveOutcome::new(selected, diversity).with_seed(seed)
+    }
+}
+
+/// Evolves a population of selections with crossover, mutation and local
+/// improvement; returns the best selection and its diversity.
+// One budget iteration is one generation
+pub fn solve_genetic(
+    data: &MdpData,
+    config: &GeneticConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+
     // Initialize population
-    let mut population = initialize_population(data, config.population_size);
+    let mut population = initialize_population(data, config.population_size, rng);
     evaluate_population(&mut population, data);
+    tracker.add_evaluations(population.len());
     
     for generation in 0..config.generations {
         // Sort by fitness (descending)
@@ -45,22 +129,23 @@ pub fn solve_genetic(data: &MdpData, config: &GeneticConfig) -> (Vec<usize>, f64
         // Generate rest of population through crossover and mutation
         while new_population.len() < config.population_size {
             // Selection: tournament selection
-            let parent1 = tournament_selection(&population, 3, &mut rng);
-            let parent2 = tournament_selection(&population, 3, &mut rng);
+            let parent1 = tournament_selection(&population, 3, rng);
+            let parent2 = tournament_selection(&population, 3, rng);
             
             // Crossover
             let mut offspring = if rng.gen::<f64>() < config.crossover_rate {
-                crossover(&parent1.selected, &parent2.selected, data, &mut rng)
+                crossover(&parent1.selected, &parent2.selected, data, rng)
             } else {
                 parent1.selected.clone()
             };
             
             // Mutation
             if rng.gen::<f64>() < config.mutation_rate {
-                mutate(&mut offspring, data, &mut rng);
+                mutate(&mut offspring, data, rng);
             }
             
             let fitness = calculate_diversity(&offspring, data);
+            tracker.add_evaluations(1);
             new_population.push(Individual {
                 selected: offspring,
                 fitness,
@@ -72,7 +157,7 @@ pub fn solve_genetic(data: &MdpData, config: &GeneticConfig) -> (Vec<usize>, f64
         // Optional: local search on best individual every N generations
         if generation % 10 == 0 {
             let best = &population[0];
-            let (improved, fitness) = local_improvement(&best.selected, data);
+            let (improved, fitness) = local_improvement::<SolutionState>(&best.selected, data, &mut tracker);
             if fitness > best.fitness {
                 population[0] = Individual {
                     selected: improved,
@@ -80,6 +165,14 @@ pub fn solve_genetic(data: &MdpData, config: &GeneticConfig) -> (Vec<usize>, f64
                 };
             }
         }
+
+        // Elites are carried over, so the population maximum never decreases
+        let best_fitness = population.iter().map(|ind| ind.fitness).fold(f64::NEG_INFINITY, f64::max);
+        tracker.observe(best_fitness);
+        tracker.next_iteration();
+        if tracker.exhausted() {
+            break;
+        }
     }
     
     // Return best individual
@@ -88,17 +181,12 @@ pub fn solve_genetic(data: &MdpData, config: &GeneticConfig) -> (Vec<usize>, f64
     (best.selected.clone(), best.fitness)
 }
 
-fn initialize_population(data: &MdpData, size: usize) -> Vec<Individual> {
-    let mut rng = rand::thread_rng();
+fn initialize_population(data: &MdpData, size: usize, rng: &mut impl Rng) -> Vec<Individual> {
     let mut population = Vec::with_capacity(size);
     
     for _ in 0..size {
-        let mut all_indices: Vec<usize> = (0..data.n).collect();
-        all_indices.shuffle(&mut rng);
-        let selected = all_indices[0..data.k].to_vec();
-        
         population.push(Individual {
-            selected,
+            selected: random_selection(data, data.k, rng),
             fitness: 0.0,
         });
     }
@@ -135,106 +223,285 @@ fn crossover(
     data: &MdpData,
     rng: &mut impl Rng,
 ) -> Vec<usize> {
-    // Path crossover: take elements from parent1, fill rest with parent2
-    let crossover_point = rng.gen_range(1..data.k);
-    
-    let mut offspring = parent1[0..crossover_point].to_vec();
-    
-    // Add elements from parent2 that aren't already in offspring
-    for &elem in parent2 {
-        if !offspring.contains(&elem) && offspring.len() < data.k {
-            offspring.push(elem);
-        }
-    }
-    
-    // If still not enough elements, add random ones
+    // Path crossover: take elements from parent1, fill rest with parent2;
+    // the included elements come first and every addition must be admissible
+    let crossover_point = rng.gen_range(1..data.k.max(2));
+    let mut offspring = data.constraints.as_ref().map(|spec| spec.required_elements()).unwrap_or_default();
+    let mut counts = ConstraintCounts::new(data, &offspring);
+
     let mut all_indices: Vec<usize> = (0..data.n).collect();
     all_indices.shuffle(rng);
-    
-    for &elem in &all_indices {
+    let candidates = parent1[0..crossover_point.min(parent1.len())].iter().chain(parent2).chain(&all_indices);
+    for &elem in candidates {
         if offspring.len() >= data.k {
             break;
         }
-        if !offspring.contains(&elem) {
+        if !offspring.contains(&elem) && counts.can_extend(elem, data.k - offspring.len() - 1) {
             offspring.push(elem);
+            counts.add(elem);
         }
     }
     
     offspring
 }
 
-fn mutate(solution: &mut Vec<usize>, data: &MdpData, rng: &mut impl Rng) {
-    // Swap mutation: remove one element, add a different one
-    let remove_idx = rng.gen_range(0..solution.len());
-    let removed = solution.remove(remove_idx);
-    
-    // Find an element not in solution
-    let mut candidates: Vec<usize> = (0..data.n)
-        .filter(|&i| !solution.contains(&i))
-        .collect();
-    
-    if candidates.is_empty() {
-        solution.push(removed);
-        return;
+fn mutate(solution: &mut [usize], data: &MdpData, rng: &mut impl Rng) {
+    // Swap mutation: replace one element by a different one, if any swap is admissible
+    let counts = ConstraintCounts::new(data, solution);
+    let mut positions: Vec<usize> = (0..solution.len()).collect();
+    positions.shuffle(rng);
+
+    for pos in positions {
+        let removed = solution[pos];
+        let candidates: Vec<usize> = (0..data.n)
+            .filter(|&i| !solution.contains(&i) && counts.can_swap(removed, i))
+            .collect();
+        if let Some(&added) = candidates.choose(rng) {
+            solution[pos] = added;
+            return;
+        }
     }
-    
-    candidates.shuffle(rng);
-    solution.push(candidates[0]);
 }
 
-fn local_improvement(solution: &[usize], data: &MdpData) -> (Vec<usize>, f64) {
-    let mut selected = solution.to_vec();
-    let mut unselected: Vec<usize> = (0..data.n)
-        .filter(|&i| !selected.contains(&i))
-        .collect();
-    
-    let mut current_diversity = calculate_diversity(&selected, data);
-    let mut improved = true;
+// Best-improvement swap descent on the objective of `S`
+fn local_improvement<'a, S: IncrementalState<'a>>(
+    solution: &[usize],
+    data: &'a MdpData,
+    tracker: &mut BudgetTracker,
+) -> (Vec<usize>, f64) {
+    let mut state = S::new(data, solution);
+    let mut counts = ConstraintCounts::new(data, solution);
     
-    while improved {
-        improved = false;
+    while !tracker.exhausted() {
         let mut best_gain = 0.0;
         let mut best_swap = None;
         
-        for i in 0..selected.len() {
-            for j in 0..unselected.len() {
-                let gain = calculate_swap_gain(selected[i], unselected[j], &selected, data);
-                if gain > best_gain {
+        for &out in state.selected() {
+            for &inc in state.unselected() {
+                let gain = state.swap_gain(out, inc);
+                if gain > best_gain && counts.can_swap(out, inc) {
                     best_gain = gain;
-                    best_swap = Some((i, j));
+                    best_swap = Some((out, inc));
                 }
             }
         }
+        tracker.add_evaluations(state.selected().len() * state.unselected().len());
         
-        if let Some((i, j)) = best_swap {
-            let temp = selected[i];
-            selected[i] = unselected[j];
-            unselected[j] = temp;
-            current_diversity += best_gain;
-            improved = true;
+        match best_swap {
+            Some((out, inc)) => {
+                state.apply_swap(out, inc);
+                counts.remove(out);
+                counts.add(inc);
+            }
+            None => break,
         }
     }
     
-    (selected, current_diversity)
+    let diversity = state.value();
+    (state.into_selected(), diversity)
+}
+
+// ============ NSGA-II ============
+
+/// NSGA-II for the bi-objective (max-sum, max-min) problem: parents are picked
+/// by front and crowding distance instead of fitness, offspring come from the
+/// genetic algorithm's crossover and mutation, and parents and offspring
+/// compete for survival front by front. The population starts from random
+/// selections plus a GRASP solution for either objective, and every 10
+/// generations the extremes of the first front are improved by a swap descent
+/// on their objective.
+/// Returns every non-dominated selection found, by decreasing sum;
+/// `elite_size` is unused since survival already keeps the best fronts.
+// One budget iteration is one generation; a target applies to the largest sum
+pub fn solve_nsga2(
+    data: &MdpData,
+    config: &GeneticConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> Vec<ParetoPoint> {
+    let started = Instant::now();
+    let mut tracker = BudgetTracker::start(budget);
+    let size = config.population_size.max(2);
+
+    let mut population: Vec<ParetoPoint> =
+        (2..size).map(|_| ParetoPoint::new(random_selection(data, data.k, rng), data)).collect();
+    // Random selections rarely come near the max-min end, where the greedy construction starts
+    for objective in [Objective::MaxSum, Objective::MaxMin] {
+        let grasp = GraspConfig { iterations: 5, objective, ..GraspConfig::default() };
+        // Only the time left counts here: the target and the other limits are meant for the front
+        let seeding_budget = Budget {
+            time_limit: budget.time_limit.map(|limit| limit.saturating_sub(started.elapsed())),
+            ..Default::default()
+        };
+        let (selected, _) = solve_grasp(data, &grasp, &seeding_budget, rng);
+        population.push(ParetoPoint::new(selected, data));
+    }
+    tracker.add_evaluations(population.len());
+    let mut archive = pareto_front(population.iter().cloned());
+
+    for generation in 0..config.generations {
+        if tracker.exhausted() {
+            break;
+        }
+        let (rank, crowding) = rank_population(&population);
+
+        let mut offspring = Vec::with_capacity(size);
+        while offspring.len() < size {
+            let parent1 = crowded_tournament(&rank, &crowding, rng);
+            let parent2 = crowded_tournament(&rank, &crowding, rng);
+
+            // Selections are kept sorted: shuffled, the crossover takes a random part of parent1
+            let mut selected = if rng.gen::<f64>() < config.crossover_rate {
+                let mut first = population[parent1].selected.clone();
+                first.shuffle(rng);
+                crossover(&first, &population[parent2].selected, data, rng)
+            } else {
+                population[parent1].selected.clone()
+            };
+            if rng.gen::<f64>() < config.mutation_rate {
+                mutate(&mut selected, data, rng);
+            }
+            offspring.push(ParetoPoint::new(selected, data));
+        }
+        tracker.add_evaluations(offspring.len());
+
+        if generation % 10 == 0 {
+            offspring.extend(improve_extremes(&population, &rank, data, &mut tracker));
+        }
+        archive = pareto_front(archive.into_iter().chain(offspring.iter().cloned()));
+
+        // Duplicates would crowd out the rest of their front
+        let mut candidates = population;
+        candidates.append(&mut offspring);
+        candidates.sort_by(|a, b| a.selected.cmp(&b.selected));
+        candidates.dedup_by(|a, b| a.selected == b.selected);
+        population = survivors(candidates, size);
+
+        tracker.observe(archive[0].sum);
+        tracker.next_iteration();
+    }
+
+    archive
 }
 
-fn calculate_diversity(selected: &[usize], data: &MdpData) -> f64 {
-    let mut sum = 0.0;
-    for i in 0..selected.len() {
-        for j in (i + 1)..selected.len() {
-            sum += data.get_dist(selected[i], selected[j]);
+// The largest-sum and largest-min members of the first front after a swap
+// descent on their objective
+fn improve_extremes(
+    population: &[ParetoPoint],
+    rank: &[usize],
+    data: &MdpData,
+    tracker: &mut BudgetTracker,
+) -> Vec<ParetoPoint> {
+    let first_front = || population.iter().zip(rank).filter(|(_, &r)| r == 0).map(|(point, _)| point);
+    let mut improved = Vec::with_capacity(2);
+    if let Some(best_sum) = first_front().max_by(|a, b| a.sum.total_cmp(&b.sum)) {
+        let (selected, _) = local_improvement::<SolutionState>(&best_sum.selected, data, tracker);
+        improved.push(ParetoPoint::new(selected, data));
+    }
+    if let Some(best_min) = first_front().max_by(|a, b| a.min.total_cmp(&b.min)) {
+        let (selected, _) = local_improvement::<MaxMinState>(&best_min.selected, data, tracker);
+        improved.push(ParetoPoint::new(selected, data));
+    }
+    improved
+}
+
+// Fronts of `points` by non-dominated sorting: the first holds the points no
+// other point dominates, the next those only dominated by the first, ...
+fn non_dominated_fronts(points: &[ParetoPoint]) -> Vec<Vec<usize>> {
+    let mut dominators = vec![0; points.len()];
+    let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            if points[i].dominates(&points[j]) {
+                dominated[i].push(j);
+                dominators[j] += 1;
+            } else if points[j].dominates(&points[i]) {
+                dominated[j].push(i);
+                dominators[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..points.len()).filter(|&i| dominators[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominated[i] {
+                dominators[j] -= 1;
+                if dominators[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+// Crowding distance of each member of `front`: the sides of the box between
+// its neighbors on both objectives, relative to the front's range; the
+// extremes of either objective get infinity
+fn crowding_distances(points: &[ParetoPoint], front: &[usize]) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    let objectives: [fn(&ParetoPoint) -> f64; 2] = [|point| point.sum, |point| point.min];
+    for objective in objectives {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| objective(&points[front[a]]).total_cmp(&objective(&points[front[b]])));
+        let value = |pos: usize| objective(&points[front[order[pos]]]);
+
+        let last = front.len() - 1;
+        distance[order[0]] = f64::INFINITY;
+        distance[order[last]] = f64::INFINITY;
+        let range = value(last) - value(0);
+        if range > 0.0 {
+            for pos in 1..last {
+                distance[order[pos]] += (value(pos + 1) - value(pos - 1)) / range;
+            }
         }
     }
-    sum
+    distance
 }
 
-fn calculate_swap_gain(old: usize, new: usize, selected: &[usize], data: &MdpData) -> f64 {
-    let mut gain = 0.0;
-    for &s in selected {
-        if s == old {
+// Front index (0 = non-dominated) and crowding distance of every member
+fn rank_population(population: &[ParetoPoint]) -> (Vec<usize>, Vec<f64>) {
+    let mut rank = vec![0; population.len()];
+    let mut crowding = vec![0.0; population.len()];
+    for (r, front) in non_dominated_fronts(population).into_iter().enumerate() {
+        for (&i, distance) in front.iter().zip(crowding_distances(population, &front)) {
+            rank[i] = r;
+            crowding[i] = distance;
+        }
+    }
+    (rank, crowding)
+}
+
+// Binary tournament: the lower front wins, then the larger crowding distance
+fn crowded_tournament(rank: &[usize], crowding: &[f64], rng: &mut impl Rng) -> usize {
+    let a = rng.gen_range(0..rank.len());
+    let b = rng.gen_range(0..rank.len());
+    if rank[a] < rank[b] || (rank[a] == rank[b] && crowding[a] > crowding[b]) {
+        a
+    } else {
+        b
+    }
+}
+
+// The `size` best candidates: whole fronts while they fit, then the least
+// crowded members of the first front that does not
+fn survivors(candidates: Vec<ParetoPoint>, size: usize) -> Vec<ParetoPoint> {
+    let mut chosen = Vec::with_capacity(size);
+    for front in non_dominated_fronts(&candidates) {
+        if chosen.len() + front.len() <= size {
+            chosen.extend(front);
             continue;
         }
-        gain += data.get_dist(new, s) - data.get_dist(old, s);
+        let distance = crowding_distances(&candidates, &front);
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| distance[b].total_cmp(&distance[a]));
+        chosen.extend(order.into_iter().take(size - chosen.len()).map(|pos| front[pos]));
+        break;
     }
-    gain
-}
\ No newline at end of file
+    chosen.into_iter().map(|i| candidates[i].clone()).collect()
+}
@@ -1,101 +1,360 @@
+//! QUBO formulation of the MDP, solved with Gurobi.
+
 use grb::prelude::*;
+use crate::graph::Graph;
 use crate::parser::MdpData;
+use crate::qubo::{initial_adaptive_penalty, sufficient_penalty, QuboModel, ADAPTIVE_GROWTH, ADAPTIVE_MAX_ROUNDS};
+use crate::solver::{parse_param, unknown_param, Budget, ParamError, SolveOutcome, Solver};
 use grb::expr::QuadExpr;
+use std::time::{Duration, Instant};
+
+pub use crate::qubo::{PenaltyStrategy, QuboResult};
+
+// Used when the budget does not carry a time limit
+const DEFAULT_TIME_LIMIT: f64 = 300.0;
+
+/// Solves the penalized QUBO formulation with Gurobi.
+pub struct QuboSolver {
+    pub penalty: PenaltyStrategy,
+    pub seed: Option<u64>, // Passed to Gurobi's Seed parameter; Gurobi's default when None
+    pub repair: bool,      // Greedily fix selections with the wrong number of elements
+}
+
+impl Default for QuboSolver {
+    fn default() -> Self {
+        QuboSolver {
+            penalty: PenaltyStrategy::Adaptive,
+            seed: None,
+            repair: true,
+        }
+    }
+}
+
+/// One or more Gurobi solves of the QUBO: the statistics of the run that
+/// produced `result` (or of the last run, if none found an incumbent).
+pub struct QuboRun {
+    pub stats: GurobiStats,
+    pub result: Option<QuboResult>,
+    pub rounds: usize, // Models solved, more than one for adaptive penalties
+}
+
+/// Status and search statistics of one Gurobi run, in the model's own objective.
+#[derive(Clone, Debug)]
+pub struct GurobiStats {
+    pub status: String,
+    pub objective: Option<f64>, // Incumbent objective, if there is one
+    pub bound: Option<f64>,     // Best proven bound on the objective
+    pub gap: Option<f64>,       // Relative gap between the two
+    pub nodes: f64,
+    pub simplex_iterations: f64,
+    pub runtime: f64, // Seconds
+}
+
+impl GurobiStats {
+    pub(crate) fn from_model(model: &Model) -> grb::Result<Self> {
+        let has_incumbent = model.get_attr(attr::SolCount)? > 0;
+        Ok(GurobiStats {
+            status: format!("{:?}", model.status()?),
+            objective: if has_incumbent { model.get_attr(attr::ObjVal).ok() } else { None },
+            bound: model.get_attr(attr::ObjBound).ok(),
+            gap: if has_incumbent { model.get_attr(attr::MIPGap).ok() } else { None },
+            nodes: model.get_attr(attr::NodeCount)?,
+            simplex_iterations: model.get_attr(attr::IterCount)?,
+            runtime: model.get_attr(attr::Runtime)?,
+        })
+    }
+
+    /// Whether Gurobi proved the incumbent optimal (within its MIPGap setting).
+    pub fn is_optimal(&self) -> bool {
+        self.status == "Optimal"
+    }
+
+    /// Adds the statistics to `outcome`'s details.
+    pub fn annotate(&self, mut outcome: SolveOutcome) -> SolveOutcome {
+        outcome = outcome.with_detail("status", self.status.clone());
+        for (key, value) in [("objective", self.objective), ("objective_bound", self.bound), ("mip_gap", self.gap)] {
+            if let Some(value) = value {
+                outcome = outcome.with_detail(key, value);
+            }
+        }
+        outcome
+            .with_detail("nodes", self.nodes)
+            .with_detail("simplex_iterations", self.simplex_iterations)
+            .with_detail("gurobi_runtime_s", self.runtime)
+    }
+}
+
+impl Solver for QuboSolver {
+    fn name(&self) -> &str {
+        "QUBO"
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("penalty", self.penalty.to_string()),
+            ("seed", self.seed.map_or_else(|| "default".to_string(), |seed| seed.to_string())),
+            ("repair", self.repair.to_string()),
+        ]
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "penalty" => self.penalty = parse_param(key, value)?,
+            "seed" => self.seed = Some(parse_param(key, value)?),
+            "repair" => self.repair = parse_param(key, value)?,
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        self.run(data, budget, None)
+    }
+
+    fn solve_from(&self, data: &MdpData, budget: &Budget, start: &[usize]) -> SolveOutcome {
+        self.run(data, budget, Some(start))
+    }
+}
+
+impl QuboSolver {
+    fn run(&self, data: &MdpData, budget: &Budget, start: Option<&[usize]>) -> SolveOutcome {
+        let run = match solve_with_penalty_strategy(data, self.penalty, self.seed, self.repair, start, budget) {
+            Ok(run) => run,
+            Err(e) => return SolveOutcome::failed().with_detail("error", e.to_string()),
+        };
+
+        let outcome = match run.result {
+            Some(result) => SolveOutcome {
+                success: result.selected.len() == data.k,
+                seed: self.seed,
+                // A feasible QUBO optimum is optimal for the MDP: every k-subset pays no penalty
+                proven_optimal: run.stats.is_optimal() && result.feasible,
+                ..SolveOutcome::new(result.selected, result.diversity)
+            }
+            .with_detail("feasible", result.feasible)
+            .with_detail("raw_size", result.raw_selected.len())
+            .with_detail("raw_diversity", result.raw_diversity)
+            .with_detail("penalized_objective", result.penalized_objective)
+            .with_detail("repaired", result.repaired)
+            .with_detail("penalty", result.penalty),
+            // No incumbent before the run ended
+            None => SolveOutcome::failed(),
+        };
+        run.stats.annotate(outcome).with_detail("penalty_rounds", run.rounds)
+    }
+}
+
+/// Runs `solve_with_qubo` with λ chosen by `strategy`. Adaptive calibration
+/// re-solves with a larger λ while the incumbent has the wrong size, up to the
+/// provable bound, sharing the budget's time limit across rounds.
+pub fn solve_with_penalty_strategy(
+    data: &MdpData,
+    strategy: PenaltyStrategy,
+    seed: Option<u64>,
+    repair: bool,
+    start: Option<&[usize]>,
+    budget: &Budget,
+) -> grb::Result<QuboRun> {
+    let Some(penalty) = strategy.penalty_for(data) else {
+        return solve_adaptive(data, seed, repair, start, budget);
+    };
+    solve_with_qubo(data, penalty, seed, repair, start, budget)
+}
 
+fn solve_adaptive(
+    data: &MdpData,
+    seed: Option<u64>,
+    repair: bool,
+    start: Option<&[usize]>,
+    budget: &Budget,
+) -> grb::Result<QuboRun> {
+    let started = Instant::now();
+    let bound = sufficient_penalty(data);
+    let mut penalty = initial_adaptive_penalty(data);
+    let mut last: Option<QuboRun> = None;
+
+    for round in 1..=ADAPTIVE_MAX_ROUNDS {
+        let mut round_budget = budget.clone();
+        if let Some(limit) = budget.time_limit {
+            match limit.checked_sub(started.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => round_budget.time_limit = Some(remaining),
+                _ if last.is_some() => break,
+                // Always solve at least once, if only to report Gurobi's status
+                _ => round_budget.time_limit = Some(Duration::ZERO),
+            }
+        }
+
+        let run = QuboRun {
+            rounds: round,
+            ..solve_with_qubo(data, penalty, seed, repair, start, &round_budget)?
+        };
+        let feasible = run.result.as_ref().is_some_and(|result| result.feasible);
+        // A round without an incumbent never replaces one that had one
+        if run.result.is_some() || last.as_ref().is_none_or(|last| last.result.is_none()) {
+            last = Some(run);
+        } else if let Some(last) = last.as_mut() {
+            last.rounds = round;
+        }
+        if feasible || penalty >= bound {
+            break;
+        }
+        penalty = (penalty * ADAPTIVE_GROWTH).min(bound);
+    }
+
+    Ok(last.expect("at least one round is solved"))
+}
+
+/// Maximizes the pairwise sum minus `penalty_param * (sum x - k)^2` and the
+/// side-constraint penalties over binary `x`, i.e. minimizes
+/// `QuboModel::mdp(data, penalty_param)`.
+/// With `repair`, an infeasible incumbent is fixed by `repair_selection`.
+/// A `start` selection is handed to Gurobi as a MIP start.
 pub fn solve_with_qubo(
     data: &MdpData,
     penalty_param: f64,
-    time_limit: f64,  // Time limit in seconds
-) -> grb::Result<(Vec<usize>, f64)> {
+    seed: Option<u64>,
+    repair: bool,
+    start: Option<&[usize]>,
+    budget: &Budget,
+) -> grb::Result<QuboRun> {
     let mut model = Model::new("MDP_QUBO")?;
-    let n = data.n;
-    let k = data.k as f64;
+    let qubo = QuboModel::mdp(data, penalty_param);
 
     // Set Gurobi parameters for time limit and gap tolerance
-    model.set_param(param::TimeLimit, time_limit)?;
-    model.set_param(param::MIPGap, 0.01)?;  // 1% optimality gap
-    model.set_param(param::OutputFlag, 0)?;  // Suppress output for cleaner logs
+    configure_model(&mut model, 0.01, seed, budget)?;  // 1% optimality gap
 
     // ---------------- Variables ----------------
-    let x: Vec<Var> = (0..n)
+    // Selection variables x_0..x_{n-1}, then the quota slack bits
+    let x: Vec<Var> = (0..qubo.n)
         .map(|i| add_binvar!(model, name: &format!("x{}", i)))
         .collect::<grb::Result<_>>()?;
 
     // ---------------- Objective ----------------
+    // Maximize sum_{i<j} d_ij x_i x_j - λ (sum_i x_i - k)^2 - side penalties, the negated QUBO
     let mut obj = QuadExpr::new();
-
-    // Diversity term: sum_{i<j} d_ij x_i x_j
-    for i in 0..n {
-        for j in (i + 1)..n {
-            let dist = data.get_dist(i, j);
-            if dist != 0.0 {
-                obj.add_qterm(dist, x[i], x[j]);
+    for (i, &xi) in x.iter().enumerate() {
+        obj.add_term(-qubo.linear[i], xi);
+        for (j, &xj) in x.iter().enumerate().skip(i + 1) {
+            let coupling = qubo.coupling(i, j);
+            if coupling != 0.0 {
+                obj.add_qterm(-coupling, xi, xj);
             }
         }
     }
+    obj.add_constant(-qubo.offset);
 
-    // Penalty term: -λ (sum_i x_i - k)^2
-    // Expanded explicitly for binary variables
-
-    // -λ * x_i^2 = -λ * x_i
-    for i in 0..n {
-        obj.add_term(-penalty_param, x[i]);
-    }
-
-    // -λ * 2 x_i x_j
-    for i in 0..n {
-        for j in (i + 1)..n {
-            obj.add_qterm(-2.0 * penalty_param, x[i], x[j]);
-        }
+    // ---------------- Solve ----------------
+    model.set_objective(obj, Maximize)?;
+    if let Some(start) = start {
+        set_mip_start(&mut model, &x, &qubo.assignment(start))?;
     }
+    model.optimize()?;
 
-    // -λ * (-2k x_i) = +2kλ x_i
-    for i in 0..n {
-        obj.add_term(2.0 * k * penalty_param, x[i]);
-    }
+    // ---------------- Extract solution ----------------
+    let stats = GurobiStats::from_model(&model)?;
+    let Some(raw_selected) = extract_selection(&model, &x[..data.n])? else {
+        return Ok(QuboRun { stats, result: None, rounds: 1 });
+    };
+    let result = QuboResult::new(data, raw_selected, penalty_param, repair);
+    Ok(QuboRun { stats, result: Some(result), rounds: 1 })
+}
 
-    // -λ * k^2
-    obj.add_constant(-penalty_param * k * k);
+/// MaxCut as a QUBO: maximize `sum_{ij} w_ij (x_i + x_j - 2 x_i x_j)`, the
+/// weight of the edges whose ends take different values. Returns Gurobi's
+/// statistics and the incumbent's sides, if the run found one.
+pub fn solve_max_cut(
+    graph: &Graph,
+    seed: Option<u64>,
+    budget: &Budget,
+) -> grb::Result<(GurobiStats, Option<Vec<bool>>)> {
+    let mut model = Model::new("MaxCut_QUBO")?;
+    configure_model(&mut model, 0.01, seed, budget)?;
 
-    // ---------------- Solve ----------------
+    let x: Vec<Var> = (0..graph.n)
+        .map(|i| add_binvar!(model, name: &format!("x{}", i)))
+        .collect::<grb::Result<_>>()?;
+    let mut obj = QuadExpr::new();
+    for &(i, j, w) in &graph.edges {
+        obj.add_term(w, x[i]);
+        obj.add_term(w, x[j]);
+        obj.add_qterm(-2.0 * w, x[i], x[j]);
+    }
     model.set_objective(obj, Maximize)?;
     model.optimize()?;
 
-    // Check if we got a solution (might have timed out)
-    let status = model.status()?;
-    
-    // Even if timed out, we can still extract the best solution found
-    if status == Status::Optimal || 
-       status == Status::TimeLimit ||
-       status == Status::Interrupted {
-        
-        // ---------------- Extract solution ----------------
-        let mut selected = Vec::new();
-        for i in 0..n {
-            let val = model.get_obj_attr(attr::X, &x[i])?;
-            if val > 0.5 {
-                selected.push(i);
-            }
+    let stats = GurobiStats::from_model(&model)?;
+    let side = extract_selection(&model, &x)?.map(|selected| {
+        let mut side = vec![false; graph.n];
+        for v in selected {
+            side[v] = true;
         }
+        side
+    });
+    Ok((stats, side))
+}
 
-        let actual_diversity = calculate_true_diversity(&selected, data);
-        Ok((selected, actual_diversity))
-    } else {
-        // Return a simple error - use panic! or just return a default empty solution
-        // Since grb::Error doesn't support creating custom errors easily,
-        // we'll return an empty solution with 0 diversity
-        Ok((Vec::new(), 0.0))
+/// Common settings of every Gurobi model: quiet output, gap tolerance, budget and seed.
+pub(crate) fn configure_model(model: &mut Model, mip_gap: f64, seed: Option<u64>, budget: &Budget) -> grb::Result<()> {
+    model.set_param(param::MIPGap, mip_gap)?;
+    model.set_param(param::OutputFlag, 0)?;  // Suppress output for cleaner logs
+    apply_budget(model, budget)?;
+    if let Some(seed) = seed {
+        // Gurobi seeds are non-negative 32-bit integers
+        model.set_param(param::Seed, (seed % i32::MAX as u64) as i32)?;
     }
+    Ok(())
 }
 
-// ---------------------------------------------------
+/// Sets `x_i = 1` for the elements of `start` and 0 elsewhere as Gurobi's MIP start.
+pub(crate) fn set_mip_start(model: &mut Model, x: &[Var], start: &[usize]) -> grb::Result<()> {
+    model.update()?;
+    let mut values = vec![0.0; x.len()];
+    for &i in start.iter().filter(|&&i| i < x.len()) {
+        values[i] = 1.0;
+    }
+    model.set_obj_attr_batch(attr::Start, x.iter().copied().zip(values))
+}
+
+/// Indices with `x_i = 1` in the incumbent, or `None` if the run ended without one.
+pub(crate) fn extract_selection(model: &Model, x: &[Var]) -> grb::Result<Option<Vec<usize>>> {
+    // Even if a budget limit stopped the search, we can still extract the best solution found
+    let stopped_with_solution = matches!(
+        model.status()?,
+        Status::Optimal
+            | Status::TimeLimit
+            | Status::NodeLimit
+            | Status::IterationLimit
+            | Status::UserObjLimit
+            | Status::Interrupted
+    );
+    if !stopped_with_solution || model.get_attr(attr::SolCount)? == 0 {
+        return Ok(None);
+    }
 
-fn calculate_true_diversity(selected: &[usize], data: &MdpData) -> f64 {
-    let mut sum = 0.0;
-    for i in 0..selected.len() {
-        for j in (i + 1)..selected.len() {
-            sum += data.get_dist(selected[i], selected[j]);
+    let mut selected = Vec::new();
+    for (i, xi) in x.iter().enumerate() {
+        if model.get_obj_attr(attr::X, xi)? > 0.5 {
+            selected.push(i);
         }
     }
-    sum
-}
\ No newline at end of file
+    Ok(Some(selected))
+}
+
+// Maps a Budget onto Gurobi's own limits: iterations are branch-and-bound nodes
+// and evaluations are simplex iterations, the closest units of work Gurobi exposes
+fn apply_budget(model: &mut Model, budget: &Budget) -> grb::Result<()> {
+    let time_limit = budget
+        .time_limit
+        .map_or(DEFAULT_TIME_LIMIT, |limit| limit.as_secs_f64());
+    model.set_param(param::TimeLimit, time_limit)?;
+    if let Some(nodes) = budget.max_iterations {
+        model.set_param(param::NodeLimit, nodes as f64)?;
+    }
+    if let Some(iterations) = budget.max_evaluations {
+        model.set_param(param::IterationLimit, iterations as f64)?;
+    }
+    if let Some(target) = budget.target_value {
+        model.set_param(param::BestObjStop, target)?;
+    }
+    Ok(())
+}
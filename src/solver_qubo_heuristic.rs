@@ -0,0 +1,407 @@
+//! Pure-Rust heuristics on the penalized QUBO: 1-flip tabu search and
+//! simulated annealing over the same matrix the Gurobi QUBO solver uses.
+
+use crate::parser::MdpData;
+use crate::qubo::{
+    distance_scale, initial_adaptive_penalty, sufficient_penalty, PenaltyStrategy, QuboModel, QuboResult,
+    ADAPTIVE_GROWTH, ADAPTIVE_MAX_ROUNDS,
+};
+use crate::solver::{
+    parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError, SolveOutcome,
+    Solver,
+};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+
+// Energy improvements smaller than this are treated as ties
+const ENERGY_TOLERANCE: f64 = 1e-9;
+
+/// Parameters of a search over single-variable flips of the QUBO.
+pub struct QuboHeuristicConfig {
+    pub method: QuboSearchMethod,
+    pub penalty: PenaltyStrategy,
+    pub max_iters: usize, // Flips (tabu) or proposed flips (annealing) per penalty round
+    pub repair: bool,     // Greedily fix selections with the wrong number of elements
+    pub seed: Option<u64>, // None draws a fresh seed per run
+}
+
+/// How the next flip is chosen.
+pub enum QuboSearchMethod {
+    TabuSearch { tabu_tenure: usize },
+    // Temperatures are multiples of the largest distance, cooled geometrically
+    Annealing { start_temperature: f64, end_temperature: f64 },
+}
+
+impl Default for QuboHeuristicConfig {
+    fn default() -> Self {
+        QuboHeuristicConfig {
+            method: QuboSearchMethod::TabuSearch { tabu_tenure: 20 },
+            penalty: PenaltyStrategy::Adaptive,
+            max_iters: 10_000,
+            repair: true,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct QuboHeuristicSolver {
+    pub config: QuboHeuristicConfig,
+}
+
+impl QuboHeuristicSolver {
+    pub fn new(config: QuboHeuristicConfig) -> Self {
+        QuboHeuristicSolver { config }
+    }
+
+    fn run(&self, data: &MdpData, budget: &Budget, start: Option<&[usize]>) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (result, rounds) = solve_qubo_heuristic(data, &self.config, start, budget, &mut rng);
+
+        SolveOutcome {
+            success: result.selected.len() == data.k,
+            ..SolveOutcome::new(result.selected, result.diversity).with_seed(seed)
+        }
+        .with_detail("feasible", result.feasible)
+        .with_detail("raw_size", result.raw_selected.len())
+        .with_detail("raw_diversity", result.raw_diversity)
+        .with_detail("penalized_objective", result.penalized_objective)
+        .with_detail("repaired", result.repaired)
+        .with_detail("penalty", result.penalty)
+        .with_detail("penalty_rounds", rounds)
+    }
+}
+
+impl Solver for QuboHeuristicSolver {
+    fn name(&self) -> &str {
+        match self.config.method {
+            QuboSearchMethod::TabuSearch { .. } => "QUBO-Tabu",
+            QuboSearchMethod::Annealing { .. } => "QUBO-SA",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("penalty", self.config.penalty.to_string()),
+            ("max_iters", self.config.max_iters.to_string()),
+            ("repair", self.config.repair.to_string()),
+            ("seed", seed_param(self.config.seed)),
+        ];
+        match self.config.method {
+            QuboSearchMethod::TabuSearch { tabu_tenure } => params.push(("tabu_tenure", tabu_tenure.to_string())),
+            QuboSearchMethod::Annealing { start_temperature, end_temperature } => {
+                params.push(("start_temperature", start_temperature.to_string()));
+                params.push(("end_temperature", end_temperature.to_string()));
+            }
+        }
+        params
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("penalty", _) => self.config.penalty = parse_param(key, value)?,
+            ("max_iters", _) => self.config.max_iters = parse_param(key, value)?,
+            ("repair", _) => self.config.repair = parse_param(key, value)?,
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("tabu_tenure", QuboSearchMethod::TabuSearch { tabu_tenure }) => *tabu_tenure = parse_param(key, value)?,
+            ("start_temperature", QuboSearchMethod::Annealing { start_temperature, .. }) => {
+                *start_temperature = parse_param(key, value)?
+            }
+            ("end_temperature", QuboSearchMethod::Annealing { end_temperature, .. }) => {
+                *end_temperature = parse_param(key, value)?
+            }
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        self.run(data, budget, None)
+    }
+
+    fn solve_from(&self, data: &MdpData, budget: &Budget, start: &[usize]) -> SolveOutcome {
+        self.run(data, budget, Some(start))
+    }
+}
+
+/// Searches the QUBO with λ chosen by `config.penalty`, starting from `start`
+/// or a random k-subset. Adaptive calibration re-runs the search with a larger
+/// λ, from the previous round's selection, while the best assignment found has
+/// the wrong size. Returns the final round's result and the number of rounds.
+// One budget iteration is one flip (tabu) or one proposed flip (annealing)
+pub fn solve_qubo_heuristic(
+    data: &MdpData,
+    config: &QuboHeuristicConfig,
+    start: Option<&[usize]>,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (QuboResult, usize) {
+    let mut tracker = BudgetTracker::start(budget);
+    let mut selected = match start {
+        Some(start) => start.iter().copied().filter(|&i| i < data.n).collect(),
+        None => sample(rng, data.n, data.k).into_vec(),
+    };
+
+    let (mut penalty, bound) = match config.penalty.penalty_for(data) {
+        Some(penalty) => (penalty, penalty),
+        None => (initial_adaptive_penalty(data), sufficient_penalty(data)),
+    };
+    let mut round = 1;
+    loop {
+        let model = QuboModel::mdp(data, penalty);
+        let mut state = FlipState::new(&model, &selected);
+        let raw_selected = match config.method {
+            QuboSearchMethod::TabuSearch { tabu_tenure } => {
+                tabu_search(&mut state, data.k, config.max_iters, tabu_tenure, &mut tracker)
+            }
+            QuboSearchMethod::Annealing { start_temperature, end_temperature } => {
+                // Instances with all-zero distances still need a positive temperature
+                let scale = distance_scale(data).max(f64::EPSILON);
+                let temperatures = (start_temperature * scale, end_temperature * scale);
+                annealing(&mut state, data.k, config.max_iters, temperatures, &mut tracker, rng)
+            }
+        };
+        let result = QuboResult::new(data, raw_selected, penalty, config.repair);
+
+        if result.feasible || penalty >= bound || round == ADAPTIVE_MAX_ROUNDS || tracker.exhausted() {
+            return (result, round);
+        }
+        selected = result.selected;
+        penalty = (penalty * ADAPTIVE_GROWTH).min(bound);
+        round += 1;
+    }
+}
+
+/// A binary assignment of a `QuboModel` with the energy change of flipping
+/// each variable, so a flip is evaluated in O(1) and applied in O(n).
+pub struct FlipState<'a> {
+    model: &'a QuboModel,
+    x: Vec<bool>,
+    delta: Vec<f64>,
+    energy: f64,
+    ones: usize, // Number of selection variables at 1
+}
+
+impl<'a> FlipState<'a> {
+    /// Builds the state with `x_i = 1` exactly for `selected`, and the quota
+    /// slack bits at their best values, in O(n²).
+    pub fn new(model: &'a QuboModel, selected: &[usize]) -> Self {
+        let n = model.n;
+        let mut x = vec![false; n];
+        for i in model.assignment(selected) {
+            x[i] = true;
+        }
+        let ones = x[..model.elements].iter().filter(|&&xi| xi).count();
+        let selected: Vec<usize> = (0..n).filter(|&i| x[i]).collect();
+
+        let delta = (0..n)
+            .map(|i| {
+                let field = model.linear[i]
+                    + selected.iter().filter(|&&j| j != i).map(|&j| model.coupling(i, j)).sum::<f64>();
+                if x[i] { -field } else { field }
+            })
+            .collect();
+        FlipState {
+            model,
+            x,
+            delta,
+            energy: model.energy(&selected),
+            ones,
+        }
+    }
+
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    pub fn ones(&self) -> usize {
+        self.ones
+    }
+
+    /// Change in energy if variable `i` is flipped.
+    pub fn delta(&self, i: usize) -> f64 {
+        self.delta[i]
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        self.energy += self.delta[i];
+        self.delta[i] = -self.delta[i];
+        self.x[i] = !self.x[i];
+        let step = if self.x[i] { 1.0 } else { -1.0 };
+        // Slack bits are not part of the selection
+        if i < self.model.elements {
+            if self.x[i] {
+                self.ones += 1;
+            } else {
+                self.ones -= 1;
+            }
+        }
+
+        for j in (0..self.model.n).filter(|&j| j != i) {
+            let sign = if self.x[j] { -1.0 } else { 1.0 };
+            self.delta[j] += sign * step * self.model.coupling(i, j);
+        }
+    }
+
+    /// Indices of the selection variables at 1.
+    pub fn selected(&self) -> Vec<usize> {
+        (0..self.model.elements).filter(|&i| self.x[i]).collect()
+    }
+}
+
+// Best non-tabu flip each iteration; a tabu flip is allowed when it reaches a
+// new best energy. Returns the lowest-energy assignment visited.
+fn tabu_search(
+    state: &mut FlipState,
+    k: usize,
+    max_iters: usize,
+    tabu_tenure: usize,
+    tracker: &mut BudgetTracker,
+) -> Vec<usize> {
+    let n = state.model.n;
+    let mut best = state.selected();
+    let mut best_energy = state.energy();
+    let mut tabu_until = vec![0; n];
+    observe_feasible(state, k, tracker);
+
+    for iter in 0..max_iters {
+        if tracker.exhausted() || n == 0 {
+            break;
+        }
+
+        let mut best_flip = None;
+        let mut best_delta = f64::INFINITY;
+        for (i, &until) in tabu_until.iter().enumerate() {
+            let delta = state.delta(i);
+            let aspiration = state.energy() + delta < best_energy - ENERGY_TOLERANCE;
+            if (until <= iter || aspiration) && delta < best_delta {
+                best_delta = delta;
+                best_flip = Some(i);
+            }
+        }
+        tracker.add_evaluations(n);
+
+        // Every variable is tabu when the tenure is at least n: release the oldest
+        let i = best_flip.unwrap_or_else(|| (0..n).min_by_key(|&i| tabu_until[i]).unwrap());
+        state.flip(i);
+        tabu_until[i] = iter + 1 + tabu_tenure;
+        tracker.next_iteration();
+
+        if state.energy() < best_energy - ENERGY_TOLERANCE {
+            best_energy = state.energy();
+            best = state.selected();
+            observe_feasible(state, k, tracker);
+        }
+    }
+
+    best
+}
+
+// Metropolis acceptance of random single flips under a geometric cooling
+// schedule from `temperatures.0` to `temperatures.1` over `max_iters` proposals.
+fn annealing(
+    state: &mut FlipState,
+    k: usize,
+    max_iters: usize,
+    temperatures: (f64, f64),
+    tracker: &mut BudgetTracker,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    let n = state.model.n;
+    let mut best = state.selected();
+    let mut best_energy = state.energy();
+    observe_feasible(state, k, tracker);
+
+    let (mut temperature, end_temperature) = temperatures;
+    let cooling = (end_temperature / temperature).powf(1.0 / max_iters.max(1) as f64);
+
+    for _ in 0..max_iters {
+        if tracker.exhausted() || n == 0 {
+            break;
+        }
+
+        let i = rng.gen_range(0..n);
+        let delta = state.delta(i);
+        tracker.add_evaluations(1);
+        if delta <= 0.0 || rng.gen::<f64>() < (-delta / temperature).exp() {
+            state.flip(i);
+            if state.energy() < best_energy - ENERGY_TOLERANCE {
+                best_energy = state.energy();
+                best = state.selected();
+                observe_feasible(state, k, tracker);
+            }
+        }
+        temperature *= cooling;
+        tracker.next_iteration();
+    }
+
+    best
+}
+
+// The target value is a diversity, which the energy equals (negated) only for k-subsets
+fn observe_feasible(state: &FlipState, k: usize, tracker: &mut BudgetTracker) {
+    if state.ones() == k {
+        tracker.observe(-state.energy());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate_instance, GeneratorConfig, InstanceKind};
+    use crate::parser::SideConstraints;
+
+    // Element 0 included, 7 excluded, 1..=5 a category of one to four and 6..=8
+    // one of at most one, so the model has three slack bits
+    fn constrained(mut data: MdpData) -> MdpData {
+        let mut spec = SideConstraints::new(data.n);
+        spec.required[0] = true;
+        spec.forbidden[7] = true;
+        for i in 1..=8 {
+            spec.category[i] = Some(if i <= 5 { 0 } else { 1 });
+        }
+        spec.quotas = vec![(1, 4), (0, 1)];
+        data.constraints = Some(spec);
+        data
+    }
+
+    fn ones(state: &FlipState) -> Vec<usize> {
+        (0..state.model.n).filter(|&i| state.x[i]).collect()
+    }
+
+    #[test]
+    fn flip_deltas_and_energy_match_the_model_after_random_flips() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for kind in [InstanceKind::Euclidean, InstanceKind::Integer, InstanceKind::Signed] {
+            for constrain in [false, true] {
+                let data = generate_instance(&GeneratorConfig { n: 10, k: 4, kind, dims: 2 }, &mut rng);
+                let data = if constrain { constrained(data) } else { data };
+                let model = QuboModel::mdp(&data, initial_adaptive_penalty(&data));
+                assert_eq!(model.n, data.n + if constrain { 3 } else { 0 });
+
+                let start: Vec<usize> = sample(&mut rng, data.n, data.k).into_vec();
+                let mut state = FlipState::new(&model, &start);
+                for step in 0..200 {
+                    let x = ones(&state);
+                    let context = format!("{:?} constrained={} step {} {:?}", kind, constrain, step, x);
+                    let energy = model.energy(&x);
+                    assert!((state.energy() - energy).abs() < 1e-6, "{}: {} != {}", context, state.energy(), energy);
+                    assert_eq!(state.ones(), x.iter().filter(|&&i| i < model.elements).count(), "{}", context);
+                    for i in 0..model.n {
+                        let flipped: Vec<usize> = if state.x[i] {
+                            x.iter().copied().filter(|&j| j != i).collect()
+                        } else {
+                            [x.clone(), vec![i]].concat()
+                        };
+                        let expected = model.energy(&flipped) - energy;
+                        let delta = state.delta(i);
+                        assert!((delta - expected).abs() < 1e-6, "{}: delta({}) {} != {}", context, i, delta, expected);
+                    }
+                    state.flip(rng.gen_range(0..model.n));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Variable neighborhood search: shake the incumbent by 1..=k_max random swaps
+//! and descend with the best-improvement local search, widening the shake
+//! while it fails and returning to one swap after every move. Skewed VNS also
+//! moves to slightly worse local optima far enough from the incumbent.
+//! Shakes, like the descent, keep the side constraints.
+
+use crate::evaluation::{
+    random_selection, ConstraintCounts, IncrementalState, MaxMeanState, MaxMinState, MaxMinSumState, MinDiffState,
+    Objective, SolutionState,
+};
+use crate::parser::MdpData;
+use crate::solver::{
+    parse_param, resolve_seed, seed_param, unknown_param, Budget, BudgetTracker, ParamError, SolveOutcome, Solver,
+};
+use crate::solver_local_search::{descend, start_size};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+/// Parameters of the variable neighborhood search.
+pub struct VnsConfig {
+    pub method: VnsMethod,
+    pub k_max: usize,      // Largest shake, in random swaps; capped by the selection size
+    pub max_iters: usize,  // Shake + descent rounds
+    pub time_limit: f64,   // Seconds; caps the budget's time limit, inf (the default) leaves it to the budget
+    pub seed: Option<u64>, // None draws a fresh seed per run
+    pub objective: Objective,
+}
+
+/// Acceptance rule of the variable neighborhood search.
+pub enum VnsMethod {
+    Basic,
+    // Also accepts a local optimum whose value is within alpha * d / |S| * |f(S)|
+    // of the incumbent's, d being the number of elements they do not share
+    Skewed { alpha: f64 },
+}
+
+impl Default for VnsConfig {
+    fn default() -> Self {
+        VnsConfig {
+            method: VnsMethod::Basic,
+            k_max: 10,
+            max_iters: 1000,
+            time_limit: f64::INFINITY,
+            seed: None,
+            objective: Objective::MaxSum,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct VnsSolver {
+    pub config: VnsConfig,
+}
+
+impl VnsSolver {
+    pub fn new(config: VnsConfig) -> Self {
+        VnsSolver { config }
+    }
+}
+
+impl Solver for VnsSolver {
+    fn name(&self) -> &str {
+        match self.config.method {
+            VnsMethod::Basic => "VNS",
+            VnsMethod::Skewed { .. } => "SVNS",
+        }
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("k_max", self.config.k_max.to_string()),
+            ("max_iters", self.config.max_iters.to_string()),
+            ("time_limit", self.config.time_limit.to_string()),
+            ("seed", seed_param(self.config.seed)),
+            ("objective", self.config.objective.to_string()),
+        ];
+        if let VnsMethod::Skewed { alpha } = self.config.method {
+            params.push(("alpha", alpha.to_string()));
+        }
+        params
+    }
+
+    fn set_param(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match (key, &mut self.config.method) {
+            ("k_max", _) => self.config.k_max = parse_param(key, value)?,
+            ("max_iters", _) => self.config.max_iters = parse_param(key, value)?,
+            ("time_limit", _) => {
+                let seconds: f64 = parse_param(key, value)?;
+                if seconds.is_nan() || seconds < 0.0 {
+                    return Err(ParamError::InvalidValue { key: key.to_string(), value: value.to_string() });
+                }
+                self.config.time_limit = seconds;
+            }
+            ("seed", _) => self.config.seed = Some(parse_param(key, value)?),
+            ("objective", _) => self.config.objective = parse_param(key, value)?,
+            ("alpha", VnsMethod::Skewed { alpha }) => *alpha = parse_param(key, value)?,
+            _ => return Err(unknown_param(self, key)),
+        }
+        Ok(())
+    }
+
+    fn solve(&self, data: &MdpData, budget: &Budget) -> SolveOutcome {
+        let seed = resolve_seed(self.config.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (selected, value) = solve_vns(data, &self.config, budget, &mut rng);
+        SolveOutcome::new(selected, value).with_seed(seed).with_objective(self.config.objective)
+    }
+}
+
+/// Runs VNS from a random start until `max_iters` rounds or the budget run
+/// out, within `time_limit` if it is shorter than the budget's; returns the
+/// best selection and its value under the configured objective.
+// One budget iteration is one shake + descent
+pub fn solve_vns(data: &MdpData, config: &VnsConfig, budget: &Budget, rng: &mut impl Rng) -> (Vec<usize>, f64) {
+    let mut budget = budget.scored(config.objective);
+    // Infinite (or too long to represent) limits leave the budget's as it is
+    if let Ok(limit) = Duration::try_from_secs_f64(config.time_limit) {
+        budget.time_limit = Some(budget.time_limit.map_or(limit, |time_limit| time_limit.min(limit)));
+    }
+    let (selected, score) = match config.objective {
+        Objective::MaxSum => vns::<SolutionState>(data, config, &budget, rng),
+        Objective::MaxMin => vns::<MaxMinState>(data, config, &budget, rng),
+        Objective::MaxMean => vns::<MaxMeanState>(data, config, &budget, rng),
+        Objective::MaxMinSum => vns::<MaxMinSumState>(data, config, &budget, rng),
+        Objective::MinDiff => vns::<MinDiffState>(data, config, &budget, rng),
+    };
+    (selected, config.objective.score(score))
+}
+
+fn vns<'a, S: IncrementalState<'a>>(
+    data: &'a MdpData,
+    config: &VnsConfig,
+    budget: &Budget,
+    rng: &mut impl Rng,
+) -> (Vec<usize>, f64) {
+    let mut tracker = BudgetTracker::start(budget);
+    let start = random_selection(data, start_size(data, config.objective), rng);
+    let (mut current, mut current_value) = descend::<S>(data, &start, &mut tracker);
+    let (mut best, mut best_value) = (current.clone(), current_value);
+    tracker.observe(best_value);
+
+    let mut k = 1;
+    for _ in 0..config.max_iters {
+        // Shakes swap distinct elements, so there must be one to take out and one to put in
+        let k_max = config.k_max.min(current.len()).min(data.n - current.len());
+        if k_max == 0 || tracker.exhausted() {
+            break;
+        }
+        k = k.min(k_max);
+
+        let shaken = shake(&current, data, k, rng);
+        let (candidate, value) = descend::<S>(data, &shaken, &mut tracker);
+        let accepted = match config.method {
+            VnsMethod::Basic => value > current_value + 1e-9,
+            VnsMethod::Skewed { alpha } => {
+                let distance = candidate.iter().filter(|v| !current.contains(v)).count();
+                let slack = alpha * distance as f64 / current.len() as f64 * current_value.abs();
+                value > current_value + 1e-9 || (distance > 0 && value + slack > current_value)
+            }
+        };
+
+        if accepted {
+            current = candidate;
+            current_value = value;
+            k = 1;
+            if current_value > best_value {
+                best = current.clone();
+                best_value = current_value;
+            }
+        } else {
+            k = if k >= k_max { 1 } else { k + 1 };
+        }
+        tracker.next_iteration();
+        tracker.observe(best_value);
+    }
+
+    (best, best_value)
+}
+
+// Replaces `swaps` elements of `selected` by random unselected ones, one
+// admissible swap at a time; swapped-out elements do not come back
+fn shake(selected: &[usize], data: &MdpData, swaps: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut shaken = selected.to_vec();
+    let mut counts = ConstraintCounts::new(data, selected);
+    let mut touched = vec![false; data.n];
+    for &v in selected {
+        touched[v] = true;
+    }
+
+    let mut positions: Vec<usize> = (0..shaken.len()).collect();
+    positions.shuffle(rng);
+    let mut done = 0;
+    for pos in positions {
+        if done == swaps {
+            break;
+        }
+        let out = shaken[pos];
+        let candidates: Vec<usize> = (0..data.n).filter(|&v| !touched[v] && counts.can_swap(out, v)).collect();
+        if let Some(&inc) = candidates.choose(rng) {
+            shaken[pos] = inc;
+            touched[inc] = true;
+            counts.remove(out);
+            counts.add(inc);
+            done += 1;
+        }
+    }
+    shaken
+}